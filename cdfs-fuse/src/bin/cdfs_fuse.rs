@@ -4,7 +4,7 @@
 use log::{debug, error, info, trace, warn};
 
 use std::{
-    collections::HashMap,
+    collections::{BTreeMap, HashMap},
     convert::TryFrom,
     ffi::OsStr,
     fs::File,
@@ -19,11 +19,11 @@ use fuser::{
     MountOption, ReplyAttr, ReplyData, ReplyDirectory, ReplyEmpty, ReplyEntry, ReplyOpen,
     ReplyXattr, Request,
 };
-use libc::{EINVAL, EIO, EISDIR, ENFILE, ENOENT, ENOTDIR, ENOTSUP};
+use libc::{E2BIG, EINVAL, EIO, EISDIR, ENFILE, ENODATA, ENOENT, ENOTDIR, ERANGE};
 
 use cdfs::{
     BlockBuffer, BlockBufferCtor, DirectoryEntry, ExtraAttributes, ISODirectory, ISOFileReader,
-    BLOCK_SIZE, ISO9660,
+    SpecialType, ISO9660,
 };
 
 #[derive(Debug, Parser)]
@@ -31,6 +31,10 @@ use cdfs::{
 struct Args {
     iso_path: PathBuf,
     mountpoint: PathBuf,
+
+    /// Mount the given session (counting from 0) of a multisession image instead of the last one
+    #[arg(long)]
+    session: Option<usize>,
 }
 
 fn entry_to_filetype(entry: &DirectoryEntry<File>) -> fuser::FileType {
@@ -38,14 +42,43 @@ fn entry_to_filetype(entry: &DirectoryEntry<File>) -> fuser::FileType {
         DirectoryEntry::Directory(_) => fuser::FileType::Directory,
         DirectoryEntry::File(_) => fuser::FileType::RegularFile,
         DirectoryEntry::Symlink(_) => fuser::FileType::Symlink,
+        DirectoryEntry::Special(special) => match special.special_type() {
+            SpecialType::BlockDevice => fuser::FileType::BlockDevice,
+            SpecialType::CharDevice => fuser::FileType::CharDevice,
+            SpecialType::Fifo => fuser::FileType::NamedPipe,
+            SpecialType::Socket => fuser::FileType::Socket,
+        },
+    }
+}
+
+/// Encodes a device number the way the kernel's `new_encode_dev()` does, which is how FUSE
+/// expects `rdev`.
+fn get_rdev(entry: &DirectoryEntry<File>) -> u32 {
+    let device = match entry {
+        DirectoryEntry::Special(special) => special.device(),
+        _ => None,
+    };
+
+    match device {
+        Some(device) => {
+            let (major, minor) = (device.major(), device.minor());
+            (minor & 0xFF) | (major << 8) | ((minor & !0xFF) << 12)
+        }
+        None => 0,
     }
 }
 
-fn get_fileattr(ino: u64, entry: &DirectoryEntry<File>) -> fuser::FileAttr {
-    let blksize = u32::from(BLOCK_SIZE);
-    let blocks = (entry.header().extent_length + blksize - 1) / blksize; // ceil(len / blksize)
-    let blocks = u64::from(blocks);
-    let size = u64::from(entry.header().extent_length);
+fn get_fileattr(ino: u64, entry: &DirectoryEntry<File>, block_size: u16) -> fuser::FileAttr {
+    let blksize = u32::from(block_size);
+    // Holes in sparse files take up no space on the disc.
+    let (size, recorded) = match entry {
+        DirectoryEntry::File(file) => (file.size(), file.recorded_size()),
+        _ => {
+            let size = u64::from(entry.header().extent_length);
+            (size, size)
+        }
+    };
+    let blocks = recorded.div_ceil(u64::from(blksize));
 
     let atime = entry.access_time().into();
     let ctime = entry.attribute_change_time().into();
@@ -67,6 +100,7 @@ fn get_fileattr(ino: u64, entry: &DirectoryEntry<File>) -> fuser::FileAttr {
             DirectoryEntry::Directory(_) => 0o0555,
             DirectoryEntry::File(_) => 0o0444,
             DirectoryEntry::Symlink(_) => 0o0444,
+            DirectoryEntry::Special(_) => 0o0444,
         },
     };
 
@@ -83,15 +117,46 @@ fn get_fileattr(ino: u64, entry: &DirectoryEntry<File>) -> fuser::FileAttr {
         nlink: 1,
         uid,
         gid,
-        rdev: 0,
+        rdev: get_rdev(entry),
         flags: 0,
         blksize,
     }
 }
 
+/// Extended attributes recorded with AAIP, plus the Amiga file comment as `user.amiga.comment`.
+fn get_xattrs(entry: &DirectoryEntry<File>) -> BTreeMap<String, Vec<u8>> {
+    let mut xattrs = entry.xattrs().clone();
+
+    if let Some(comment) = entry.amiga_info().and_then(|amiga| amiga.comment.as_ref()) {
+        xattrs.insert(
+            "user.amiga.comment".to_string(),
+            comment.as_bytes().to_vec(),
+        );
+    }
+
+    xattrs
+}
+
+/// A `size` of zero asks how big the buffer needs to be.
+fn reply_xattr(data: &[u8], size: u32, reply: ReplyXattr) {
+    let len = match u32::try_from(data.len()) {
+        Ok(len) => len,
+        Err(_) => return reply.error(E2BIG),
+    };
+
+    if size == 0 {
+        reply.size(len)
+    } else if len > size {
+        reply.error(ERANGE)
+    } else {
+        reply.data(data)
+    }
+}
+
 struct ISOFuse {
     _iso9660: ISO9660<File>,
     inodes: HashMap<u64, DirectoryEntry<File>>,
+    block_size: u16,
     inode_number: u64,
     inode_autogenerated: bool,
     directory_number: u64,
@@ -101,12 +166,23 @@ struct ISOFuse {
 }
 
 impl ISOFuse {
-    fn new<P>(path: P) -> Result<Self>
+    fn new<P>(path: P, session: Option<usize>) -> Result<Self>
     where
         P: AsRef<Path>,
     {
-        let file = File::open(path.as_ref()).context("Could not open ISO image")?;
-        let iso9660 = ISO9660::new(file).context("Could not parse ISO image")?;
+        let mut file = File::open(path.as_ref()).context("Could not open ISO image")?;
+
+        let sessions = ISO9660::sessions(&mut file).context("Could not parse ISO image")?;
+        let session = match session {
+            Some(index) => sessions
+                .get(index)
+                .with_context(|| format!("Image only has {} session(s)", sessions.len()))?,
+            None => sessions.last().context("Image has no sessions")?,
+        };
+        info!("Mounting session at sector {}", session.start);
+
+        let iso9660 =
+            ISO9660::new_at_session(file, session.start).context("Could not parse ISO image")?;
 
         let mut inodes = HashMap::new();
         let root = iso9660.root();
@@ -124,6 +200,7 @@ impl ISOFuse {
         inodes.insert(fuser::FUSE_ROOT_ID, DirectoryEntry::Directory(root.clone()));
 
         Ok(Self {
+            block_size: iso9660.block_size(),
             _iso9660: iso9660,
             inodes,
             inode_number: fuser::FUSE_ROOT_ID + 1,
@@ -154,7 +231,7 @@ impl fuser::Filesystem for ISOFuse {
                     false => u64::from(current_entry.inode().expect("missing inode?!")),
                 };
 
-                let fileattr = get_fileattr(inode_number, &current_entry);
+                let fileattr = get_fileattr(inode_number, &current_entry, self.block_size);
                 self.inodes.insert(inode_number, current_entry);
 
                 if self.inode_autogenerated {
@@ -182,7 +259,7 @@ impl fuser::Filesystem for ISOFuse {
             None => return reply.error(EINVAL),
         };
 
-        let fileattr = get_fileattr(ino, entry);
+        let fileattr = get_fileattr(ino, entry, self.block_size);
         reply.attr(&Duration::from_secs(0), &fileattr);
     }
 
@@ -302,12 +379,37 @@ impl fuser::Filesystem for ISOFuse {
     fn getxattr(
         &mut self,
         _req: &Request<'_>,
-        _ino: u64,
-        _name: &OsStr,
-        _size: u32,
+        ino: u64,
+        name: &OsStr,
+        size: u32,
         reply: ReplyXattr,
     ) {
-        reply.error(ENOTSUP)
+        let entry = match self.inodes.get(&ino) {
+            Some(entry) => entry,
+            None => return reply.error(EINVAL),
+        };
+
+        let xattrs = get_xattrs(entry);
+        let value = match name.to_str().and_then(|name| xattrs.get(name)) {
+            Some(value) => value,
+            None => return reply.error(ENODATA),
+        };
+
+        reply_xattr(value, size, reply)
+    }
+
+    fn listxattr(&mut self, _req: &Request<'_>, ino: u64, size: u32, reply: ReplyXattr) {
+        let entry = match self.inodes.get(&ino) {
+            Some(entry) => entry,
+            None => return reply.error(EINVAL),
+        };
+
+        let names: Vec<u8> = get_xattrs(entry)
+            .keys()
+            .flat_map(|name| name.bytes().chain([0]))
+            .collect();
+
+        reply_xattr(&names, size, reply)
     }
 
     fn readdir(
@@ -419,7 +521,7 @@ fn main() -> anyhow::Result<()> {
     info!("NOTE: The filesystem must be manually unmounted after exit");
 
     fuser::mount2(
-        ISOFuse::new(args.iso_path)?,
+        ISOFuse::new(args.iso_path, args.session)?,
         &args.mountpoint,
         &[MountOption::RO],
     )?;
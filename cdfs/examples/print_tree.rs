@@ -41,7 +41,9 @@ fn main() -> anyhow::Result<()> {
                 Some(DirectoryEntry::Directory(dir)) => {
                     print_tree(&dir, 0, &time_format);
                 }
-                Some(DirectoryEntry::File(_)) | Some(DirectoryEntry::Symlink(_)) => {
+                Some(DirectoryEntry::File(_))
+                | Some(DirectoryEntry::Symlink(_))
+                | Some(DirectoryEntry::Special(_)) => {
                     bail!("'{dir_path}' is not a directory");
                 }
                 None => {
@@ -97,6 +99,23 @@ fn print_tree<T: ISO9660Reader>(dir: &ISODirectory<T>, level: usize, time_format
 
                 println!("{}{} → {target}", INDENT.repeat(level), link.identifier);
             }
+            DirectoryEntry::Special(special) => {
+                if let Some(mode) = special.mode() {
+                    print!("{mode} ");
+                }
+                print!(" {} ", special.modify_time().format(&time_format).unwrap());
+
+                match special.device() {
+                    Some(device) => println!(
+                        "{}{} ({}, {})",
+                        INDENT.repeat(level),
+                        special.identifier,
+                        device.major(),
+                        device.minor()
+                    ),
+                    None => println!("{}{}", INDENT.repeat(level), special.identifier),
+                }
+            }
         }
     }
 }
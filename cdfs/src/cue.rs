@@ -0,0 +1,404 @@
+// SPDX-License-Identifier: (MIT OR Apache-2.0)
+
+#[allow(unused)]
+use log::{debug, error, info, trace, warn};
+
+use std::{
+    fs::{self, File},
+    path::{Path, PathBuf},
+};
+
+use crate::{ISOError, RawSectorReader, Result, SectorFormat};
+
+/// The number of frames (sectors) per second of audio.
+const FRAMES_PER_SECOND: u32 = 75;
+
+/// The type of data a track holds, and how its sectors are stored in the image file.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum TrackMode {
+    /// Red Book audio, 2352 bytes per sector.
+    Audio,
+
+    /// Karaoke CD+G, 2448 bytes per sector.
+    Cdg,
+
+    /// Mode 1 data, cooked to 2048 bytes per sector.
+    Mode1_2048,
+
+    /// Mode 1 data, raw 2352 byte sectors.
+    Mode1_2352,
+
+    /// Mode 2 Form 1 data, cooked to 2048 bytes per sector.
+    Mode2_2048,
+
+    /// Mode 2 Form 2 data, cooked to 2324 bytes per sector.
+    Mode2_2324,
+
+    /// Mode 2 data without sync and header, 2336 bytes per sector.
+    Mode2_2336,
+
+    /// Mode 2 data, raw 2352 byte sectors.
+    Mode2_2352,
+
+    /// CD-i Mode 2 data, 2336 bytes per sector.
+    Cdi2336,
+
+    /// CD-i Mode 2 data, raw 2352 byte sectors.
+    Cdi2352,
+}
+
+impl TrackMode {
+    fn parse(mode: &str) -> Option<Self> {
+        Some(match mode.to_ascii_uppercase().as_str() {
+            "AUDIO" => Self::Audio,
+            "CDG" => Self::Cdg,
+            "MODE1/2048" => Self::Mode1_2048,
+            "MODE1/2352" => Self::Mode1_2352,
+            "MODE2/2048" => Self::Mode2_2048,
+            "MODE2/2324" => Self::Mode2_2324,
+            "MODE2/2336" => Self::Mode2_2336,
+            "MODE2/2352" => Self::Mode2_2352,
+            "CDI/2336" => Self::Cdi2336,
+            "CDI/2352" => Self::Cdi2352,
+            _ => return None,
+        })
+    }
+
+    /// Returns the number of bytes each sector of the track occupies in the image file.
+    pub fn sector_size(&self) -> u64 {
+        match self {
+            Self::Audio | Self::Mode1_2352 | Self::Mode2_2352 | Self::Cdi2352 => 2352,
+            Self::Cdg => 2448,
+            Self::Mode1_2048 | Self::Mode2_2048 => 2048,
+            Self::Mode2_2324 => 2324,
+            Self::Mode2_2336 | Self::Cdi2336 => 2336,
+        }
+    }
+
+    /// Returns true for data (as opposed to audio) tracks.
+    pub fn is_data(&self) -> bool {
+        !matches!(self, Self::Audio | Self::Cdg)
+    }
+
+    /// Returns the [`SectorFormat`] needed to read an ISO 9660 filesystem from the track, if the
+    /// track can contain one.
+    pub fn sector_format(&self) -> Option<SectorFormat> {
+        match self {
+            Self::Mode1_2048 | Self::Mode2_2048 => Some(SectorFormat::Cooked),
+            Self::Mode1_2352 | Self::Mode2_2352 | Self::Cdi2352 => Some(SectorFormat::Raw),
+            Self::Mode2_2336 | Self::Cdi2336 => Some(SectorFormat::Mode2),
+            Self::Audio | Self::Cdg | Self::Mode2_2324 => None,
+        }
+    }
+}
+
+/// An `INDEX` within a track.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct TrackIndex {
+    /// The index number.  Index 0 is the pregap, index 1 the start of the track proper.
+    pub number: u8,
+
+    /// The position of the index within the track's file, in frames (sectors).
+    pub position: u32,
+}
+
+/// A single `TRACK` from a CUE sheet.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Track {
+    /// The track number.
+    pub number: u8,
+
+    /// The track mode.
+    pub mode: TrackMode,
+
+    /// The image file containing the track.  Relative paths are resolved against the directory
+    /// containing the CUE sheet.
+    pub file: PathBuf,
+
+    /// The track's indexes, in order.
+    pub indexes: Vec<TrackIndex>,
+
+    /// Length of a `PREGAP` (silence not stored in the file), in frames.
+    pub pregap: u32,
+
+    /// Length of a `POSTGAP` (silence not stored in the file), in frames.
+    pub postgap: u32,
+
+    /// The byte offset of the start of the track (`INDEX 01`) within [`file`](Self::file).
+    pub offset: u64,
+}
+
+impl Track {
+    /// Returns the position of `INDEX 01`, in frames, within the track's file.
+    pub fn start(&self) -> Option<u32> {
+        self.indexes
+            .iter()
+            .find(|index| index.number == 1)
+            .map(|index| index.position)
+    }
+}
+
+/// A parsed CUE sheet describing the tracks of a BIN/CUE image set.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use cdfs::{CueSheet, ISO9660};
+///
+/// let cue = CueSheet::open("game.cue")?;
+/// for track in cue.tracks() {
+///     println!("{:02} {:?}", track.number, track.mode);
+/// }
+///
+/// let iso = ISO9660::new(cue.open_data_track()?)?;
+/// # Ok::<(), cdfs::ISOError>(())
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct CueSheet {
+    tracks: Vec<Track>,
+}
+
+impl CueSheet {
+    /// Reads and parses a CUE sheet from a file.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error variant if the file cannot be read or is not a valid CUE sheet.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let text = fs::read(path)?;
+        // CUE sheets are nominally ASCII, but file names are often in the local code page.
+        let text = String::from_utf8_lossy(&text);
+        let base = path.parent().unwrap_or_else(|| Path::new(""));
+
+        Self::parse(&text, base)
+    }
+
+    /// Parses a CUE sheet.  Relative `FILE` paths are resolved against `base`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ISOError::InvalidCue`] if the sheet is malformed.
+    pub fn parse(text: &str, base: &Path) -> Result<Self> {
+        let mut tracks: Vec<Track> = Vec::new();
+        let mut file: Option<PathBuf> = None;
+
+        for (line_number, line) in text.lines().enumerate() {
+            let line_number = line_number + 1;
+            let invalid = |reason| ISOError::InvalidCue {
+                line: line_number,
+                reason,
+            };
+
+            let tokens = tokenize(line);
+            let mut tokens = tokens.iter().map(String::as_str);
+            let command = match tokens.next() {
+                Some(command) => command.to_ascii_uppercase(),
+                None => continue,
+            };
+
+            match command.as_str() {
+                "FILE" => {
+                    let name = tokens.next().ok_or(invalid("FILE without a file name"))?;
+                    file = Some(base.join(name));
+                }
+                "TRACK" => {
+                    let file = file.clone().ok_or(invalid("TRACK before FILE"))?;
+                    let number = tokens
+                        .next()
+                        .and_then(|number| number.parse().ok())
+                        .ok_or(invalid("Invalid track number"))?;
+                    let mode = tokens
+                        .next()
+                        .and_then(TrackMode::parse)
+                        .ok_or(invalid("Unknown track mode"))?;
+
+                    tracks.push(Track {
+                        number,
+                        mode,
+                        file,
+                        indexes: Vec::new(),
+                        pregap: 0,
+                        postgap: 0,
+                        offset: 0,
+                    });
+                }
+                "INDEX" => {
+                    let track = tracks.last_mut().ok_or(invalid("INDEX before TRACK"))?;
+                    let number = tokens
+                        .next()
+                        .and_then(|number| number.parse().ok())
+                        .ok_or(invalid("Invalid index number"))?;
+                    let position = tokens
+                        .next()
+                        .and_then(parse_msf)
+                        .ok_or(invalid("Invalid index position"))?;
+
+                    track.indexes.push(TrackIndex { number, position });
+                }
+                "PREGAP" | "POSTGAP" => {
+                    let track = tracks.last_mut().ok_or(invalid("Gap before TRACK"))?;
+                    let length = tokens
+                        .next()
+                        .and_then(parse_msf)
+                        .ok_or(invalid("Invalid gap length"))?;
+
+                    if command == "PREGAP" {
+                        track.pregap = length;
+                    } else {
+                        track.postgap = length;
+                    }
+                }
+                "REM" | "CATALOG" | "CDTEXTFILE" | "FLAGS" | "ISRC" | "PERFORMER"
+                | "SONGWRITER" | "TITLE" => {}
+                _ => warn!("Ignoring unknown CUE command {command:?} on line {line_number}"),
+            }
+        }
+
+        if tracks.is_empty() {
+            return Err(ISOError::InvalidCue {
+                line: 0,
+                reason: "No tracks",
+            });
+        }
+
+        compute_offsets(&mut tracks)?;
+
+        Ok(Self { tracks })
+    }
+
+    /// Returns every track in the CUE sheet, including audio tracks.
+    pub fn tracks(&self) -> &[Track] {
+        &self.tracks
+    }
+
+    /// Returns the first data track, which is where the ISO 9660 filesystem normally lives.
+    pub fn data_track(&self) -> Option<&Track> {
+        self.tracks.iter().find(|track| track.mode.is_data())
+    }
+
+    /// Opens a track's file and returns a [`RawSectorReader`] whose logical block 0 is the start
+    /// of the track.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error variant if the track can't contain a filesystem or the file cannot be
+    /// opened.
+    pub fn open_track(&self, track: &Track) -> Result<RawSectorReader<File>> {
+        let format = track.mode.sector_format().ok_or(ISOError::InvalidCue {
+            line: 0,
+            reason: "Track cannot contain a filesystem",
+        })?;
+
+        let file = File::open(&track.file)?;
+        Ok(RawSectorReader::with_offset(file, format, track.offset))
+    }
+
+    /// Opens the first data track.  See [`open_track()`](Self::open_track).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error variant if there is no data track or its file cannot be opened.
+    pub fn open_data_track(&self) -> Result<RawSectorReader<File>> {
+        let track = self.data_track().ok_or(ISOError::InvalidCue {
+            line: 0,
+            reason: "No data track",
+        })?;
+
+        self.open_track(track)
+    }
+}
+
+/// Splits a line into whitespace separated tokens, honoring double quotes.
+fn tokenize(line: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let mut token = String::new();
+    let mut quoted = false;
+    let mut in_token = false;
+
+    for c in line.chars() {
+        match c {
+            '"' => {
+                quoted = !quoted;
+                in_token = true;
+            }
+            c if c.is_whitespace() && !quoted => {
+                if in_token {
+                    tokens.push(std::mem::take(&mut token));
+                    in_token = false;
+                }
+            }
+            c => {
+                token.push(c);
+                in_token = true;
+            }
+        }
+    }
+
+    if in_token {
+        tokens.push(token);
+    }
+
+    tokens
+}
+
+/// Parses an `mm:ss:ff` timestamp into a number of frames.
+fn parse_msf(msf: &str) -> Option<u32> {
+    let mut parts = msf.split(':').map(str::parse::<u32>);
+    let (minutes, seconds, frames) = (
+        parts.next()?.ok()?,
+        parts.next()?.ok()?,
+        parts.next()?.ok()?,
+    );
+
+    if parts.next().is_some() || seconds >= 60 || frames >= FRAMES_PER_SECOND {
+        return None;
+    }
+
+    Some((minutes * 60 + seconds) * FRAMES_PER_SECOND + frames)
+}
+
+/// Converts each track's `INDEX 01` position into a byte offset.  Tracks sharing a file may use
+/// different sector sizes, so the offset accumulates track by track.
+fn compute_offsets(tracks: &mut [Track]) -> Result<()> {
+    let mut previous: Option<(PathBuf, u32, u64, u64)> = None;
+
+    for track in tracks.iter_mut() {
+        let start = track.start().ok_or(ISOError::InvalidCue {
+            line: 0,
+            reason: "Track without INDEX 01",
+        })?;
+
+        // Sectors between the end of the previous track and INDEX 01 (i.e. the pregap stored in
+        // the file as INDEX 00) use this track's sector size.
+        let first = track
+            .indexes
+            .iter()
+            .map(|index| index.position)
+            .min()
+            .unwrap_or(start);
+        let out_of_order = ISOError::InvalidCue {
+            line: 0,
+            reason: "Track indexes out of order",
+        };
+
+        track.offset = match &previous {
+            Some((file, position, offset, sector_size)) if *file == track.file => {
+                let previous_frames = first.checked_sub(*position).ok_or(out_of_order)?;
+                offset
+                    + u64::from(previous_frames) * sector_size
+                    + u64::from(start - first) * track.mode.sector_size()
+            }
+            _ => u64::from(start) * track.mode.sector_size(),
+        };
+
+        previous = Some((
+            track.file.clone(),
+            start,
+            track.offset,
+            track.mode.sector_size(),
+        ));
+    }
+
+    Ok(())
+}
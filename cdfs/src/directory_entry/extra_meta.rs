@@ -1,8 +1,11 @@
-use std::collections::HashSet;
+use std::collections::BTreeMap;
 
 use time::OffsetDateTime;
 
-use super::{DirectoryEntryHeader, PosixAttributes, PosixFileMode, PosixTimestamp, SuspExtension};
+use super::{
+    Acl, AmigaInfo, AppleInfo, DeviceNumber, DirectoryEntryHeader, PosixAttributes, PosixFileMode,
+    PosixTimestamp, SparseFile, SuspExtension, SystemUseOrigin, XaRecord, ZisofsFile,
+};
 
 /// Holds information from system use (SUSP) entries.
 ///
@@ -23,6 +26,10 @@ pub struct ExtraMeta {
     /// System Use Sharing Protocol § 4.1.4
     pub alt_name: Option<String>,
 
+    /// The bytes of [`ExtraMeta::alt_name`] as recorded, before being decoded with the code page
+    /// set by [`OpenOptions::code_page()`](crate::OpenOptions::code_page).
+    pub raw_alt_name: Option<Vec<u8>>,
+
     /// POSIX attributes (permissions, ownership, links, inode)
     ///
     /// This contains a [`PosixAttributes`] struct generated
@@ -33,6 +40,16 @@ pub struct ExtraMeta {
     /// System Use Sharing Protocol § 4.1.1
     pub attributes: Option<PosixAttributes>,
 
+    /// If the directory entry is a block or character device, its device number.
+    ///
+    /// This contains a [`DeviceNumber`] struct generated from a `PN` entry in the system use
+    /// table.
+    ///
+    /// ## See Also
+    ///
+    /// Rock Ridge Interchange Protocol § 4.1.2
+    pub device: Option<DeviceNumber>,
+
     /// If the directory entry is a symbolic link, its target is stored here.
     ///
     /// This contains a path specified by one or more `SL` entries
@@ -43,15 +60,23 @@ pub struct ExtraMeta {
     /// System Use Sharing Protocol § 4.1.3
     pub symlink_target: Option<String>,
 
-    /// [`HashSet`] of all the system use extensions used by this directory hierarchy.
+    /// The system use extensions registered by `ER` entries, in order.  `ES` entries refer to
+    /// them by position.
     ///
     /// All SUSP-compliant extensions are required to include an `ER` entry in
-    /// the system use table.
+    /// the system use table of the root directory's `.` entry.
     ///
     /// ## See Also
     ///
     /// System Use Sharing Protocol § 5.5
-    pub extensions: HashSet<SuspExtension>,
+    pub extensions: Vec<SuspExtension>,
+
+    /// Every system use entry in the directory record, in order, with the extension it belongs to.
+    ///
+    /// ## See Also
+    ///
+    /// System Use Sharing Protocol § 5.6
+    pub origins: Vec<SystemUseOrigin>,
 
     /// POSIX style timestamps (access, creation, modification, etc.)
     ///
@@ -71,6 +96,56 @@ pub struct ExtraMeta {
     ///
     /// Rock Ridge Interchange Protocol § 4.1.5
     pub relocated: bool,
+
+    /// If the file is sparse, its logical size and the depth of its sparse tables.
+    ///
+    /// This contains a [`SparseFile`] struct generated from an `SF` entry in the system use table.
+    ///
+    /// ## See Also
+    ///
+    /// Rock Ridge Interchange Protocol § 4.1.7
+    pub sparse: Option<SparseFile>,
+
+    /// If the file is zisofs compressed, how it was compressed and its uncompressed size.
+    ///
+    /// This contains a [`ZisofsFile`] struct generated from a `ZF` entry in the system use table.
+    pub zisofs: Option<ZisofsFile>,
+
+    /// Extended attributes, keyed by their full names (e.g. `user.comment`).
+    ///
+    /// This contains the name/value pairs collected from one or more `AL` entries in the system
+    /// use table.  The ACL is decoded into [`acl`](Self::acl) instead.
+    ///
+    /// ## See Also
+    ///
+    /// `libisofs`' `doc/susp_aaip_2_0.txt`
+    pub xattrs: BTreeMap<String, Vec<u8>>,
+
+    /// The POSIX.1e access control list, if one was recorded alongside the extended attributes.
+    ///
+    /// ## See Also
+    ///
+    /// `libisofs`' `doc/susp_aaip_2_0.txt`
+    pub acl: Option<Acl>,
+
+    /// Finder or ProDOS information recorded on Apple discs.
+    ///
+    /// This contains an [`AppleInfo`] struct generated from an `AA` (or older `BA`) entry in the
+    /// system use table.
+    pub apple: Option<AppleInfo>,
+
+    /// Protection bits and the file comment recorded on Amiga discs.
+    ///
+    /// This contains an [`AmigaInfo`] struct generated from one or more `AS` entries in the system
+    /// use table.
+    pub amiga: Option<AmigaInfo>,
+
+    /// The CD-ROM XA record, found on Video CD, PlayStation, and CD-i discs.
+    ///
+    /// ## See Also
+    ///
+    /// System Description CD-ROM XA (the "White Book") § 4.3
+    pub xa: Option<XaRecord>,
 }
 
 /// `ExtraAttributes` encapsulates various metadata specified by ISO-9660 / ECMA-119 extensions.
@@ -218,6 +293,45 @@ pub trait ExtraAttributes {
         self.ext().timestamps.modify.unwrap_or(self.time())
     }
 
+    /// Returns the extension the first system use entry with `signature` belongs to, if there is
+    /// such an entry and a registered extension claims it.
+    ///
+    /// # See Also
+    ///
+    /// System Use Sharing Protocol § 5.6
+    fn entry_extension(&self, signature: &[u8; 2]) -> Option<&SuspExtension> {
+        self.ext()
+            .origins
+            .iter()
+            .find(|origin| &origin.signature == signature)
+            .and_then(|origin| origin.extension.as_ref())
+    }
+
+    /// Returns the extended attributes, keyed by their full names.
+    fn xattrs(&self) -> &BTreeMap<String, Vec<u8>> {
+        &self.ext().xattrs
+    }
+
+    /// Returns the POSIX.1e access control list, if available.
+    fn acl(&self) -> Option<&Acl> {
+        self.ext().acl.as_ref()
+    }
+
+    /// Returns the Apple Finder or ProDOS information, if available.
+    fn apple_info(&self) -> Option<&AppleInfo> {
+        self.ext().apple.as_ref()
+    }
+
+    /// Returns the Amiga protection bits and file comment, if available.
+    fn amiga_info(&self) -> Option<&AmigaInfo> {
+        self.ext().amiga.as_ref()
+    }
+
+    /// Returns the CD-ROM XA record, if available.
+    fn xa(&self) -> Option<&XaRecord> {
+        self.ext().xa.as_ref()
+    }
+
     /// Returns the serial number (a.k.a. inode), if available.
     ///
     /// # See Also
@@ -3,26 +3,23 @@
 #[allow(unused)]
 use log::{debug, error, info, trace, warn};
 
-use std::{
-    collections::HashSet,
-    convert::TryFrom,
-    fmt,
-    path::{Component as PathComponent, PathBuf},
-    str,
-};
+use std::{convert::TryFrom, fmt, str};
 
 use itertools::Itertools;
 
 use super::{DirectoryEntry, ExtraAttributes, ExtraMeta};
 use crate::{
     parse::{
+        aaip,
         directory_entry::{DirectoryEntryHeader, FileFlags},
         susp::{
-            system_use_entries, AlternateNameFlags, ChildLink, PosixAttributes, PosixTimestamp,
-            SuspExtension, SymbolicLinkRecordFlags, SystemUseEntry,
+            attribute, system_use_entries, AlternateNameFlags, AmigaInfo, AppleInfo, ChildLink,
+            DeviceNumber, PosixAttributes, PosixTimestamp, SparseFile, SuspExtension,
+            SymbolicLinkRecordFlags, SystemUseEntry, SystemUseLayout, ZisofsFile,
         },
+        xa::XaRecord,
     },
-    BlockBuffer, BlockBufferCtor, FileRef, ISO9660Reader, ISOError, Result, BLOCK_SIZE,
+    Anomaly, BlockBuffer, BlockBufferCtor, FileRef, ISO9660Reader, ISOError, Result,
 };
 
 /// [`DirectoryEntry`](crate::DirectoryEntry) for directories.
@@ -38,7 +35,7 @@ pub struct ISODirectory<T: ISO9660Reader> {
 
     pub(super) ext: ExtraMeta,
 
-    file: FileRef<T>,
+    pub(crate) file: FileRef<T>,
 }
 
 impl<T: ISO9660Reader> ExtraAttributes for ISODirectory<T> {
@@ -98,29 +95,181 @@ impl<T: ISO9660Reader> ISODirectory<T> {
         }
     }
 
-    /// Returns the number of [`BLOCK_SIZE`](crate::BLOCK_SIZE) byte blocks required to contain the directory entry.
+    /// Reads the `SP` entry and the extensions registered by this root directory's `.` entry, so
+    /// that system use fields anywhere in the hierarchy are parsed accordingly and their entries
+    /// can be attributed to the extensions.  Without an `SP` entry, system use fields are ignored.
+    ///
+    /// # See Also
+    ///
+    /// System Use Sharing Protocol §§ 5.3, 5.5
+    pub(crate) fn register_susp(mut self) -> Self {
+        let system_use = match self.read_system_use_layout() {
+            Ok(system_use) => system_use,
+            Err(err) => {
+                warn!("Unable to read the root directory's system use field: {err}");
+                return self;
+            }
+        };
+
+        self.file = self.file.with_susp(system_use, Vec::new());
+        if system_use == SystemUseLayout::Absent {
+            return self;
+        }
+
+        let extensions = match self.contents().next() {
+            Some(Ok(DirectoryEntry::Directory(dot))) => dot.ext.extensions,
+            _ => return self,
+        };
+
+        self.file = self.file.with_susp(system_use, extensions);
+        self
+    }
+
+    /// SUSP is in use only if the first entry of the `.` entry's system use field is `SP`.
+    fn read_system_use_layout(&self) -> Result<SystemUseLayout> {
+        let mut block = BlockBuffer::new();
+        let block = &mut block[..usize::from(self.block_size())];
+        let count = self.file.read_at(block, self.header.extent_loc as u64)?;
+        if count != block.len() {
+            return Err(ISOError::ReadSize(count));
+        }
+
+        let (_, _, susp) = DirectoryEntryHeader::parse(
+            block,
+            self.header.character_encoding,
+            SystemUseLayout::Unknown,
+            self.file.decoding(),
+            self.file.tolerance(),
+        )?;
+
+        // A CD-ROM XA record may precede it.
+        let first = susp
+            .iter()
+            .flatten()
+            .find(|entry| !matches!(entry, SystemUseEntry::Xa(_)));
+        Ok(match first {
+            Some(SystemUseEntry::SuspIndicator(sp)) => SystemUseLayout::Present { skip: sp.skip },
+            _ => SystemUseLayout::Absent,
+        })
+    }
+
+    /// Returns the number of logical blocks required to contain the directory entry.
     pub fn block_count(&self) -> u32 {
         let len = self.header.extent_length;
-        let block_size = u32::from(BLOCK_SIZE);
-        (len + block_size - 1) / block_size // ceil(len / block_size)
+        let block_size = u32::from(self.block_size());
+        len.div_ceil(block_size)
+    }
+
+    /// Returns the logical block size of the volume this directory belongs to.
+    ///
+    /// # See Also
+    /// ISO-9660 / ECMA-119 § 6.1.2
+    pub fn block_size(&self) -> u16 {
+        self.file.block_size()
     }
 
     /// I'm pretty sure this doesn't need to be public and IsoFuse should just use `contents()` instead.
+    ///
+    /// Files recorded in multiple extents are returned as a single [`DirectoryEntry::File`], and
+    /// the returned offset points past the last of its directory records.  An associated file
+    /// (e.g. a Macintosh resource fork) is returned as part of the file it precedes, if that file
+    /// has the same name.
     pub fn read_entry_at(
         &self,
         block: &mut BlockBuffer,
         buf_block_num: &mut Option<u64>,
         offset: u64,
     ) -> Result<(DirectoryEntry<T>, Option<u64>)> {
-        let blksize = u64::from(BLOCK_SIZE);
+        let (entry, next_offset) = self.read_file_at(block, buf_block_num, offset)?;
+
+        let associated = match entry {
+            DirectoryEntry::File(ref file)
+                if file.header.file_flags.contains(FileFlags::ASSOCIATED_FILE) =>
+            {
+                file
+            }
+            _ => return Ok((entry, next_offset)),
+        };
+        let offset = match next_offset {
+            Some(offset) => offset,
+            None => return Ok((entry, next_offset)),
+        };
+
+        // ISO-9660 / ECMA-119 § 9.3: an associated file's record immediately precedes the record
+        // of the file it's associated with.
+        match self.read_file_at(block, buf_block_num, offset) {
+            Ok((DirectoryEntry::File(mut file), next_offset))
+                if !file.header.file_flags.contains(FileFlags::ASSOCIATED_FILE)
+                    && file.identifier == associated.identifier =>
+            {
+                file.set_associated(associated);
+                Ok((DirectoryEntry::File(file), next_offset))
+            }
+            Ok(_) => Ok((entry, Some(offset))),
+            // The error is met again when the following record is read on its own.
+            Err(err) => {
+                self.file
+                    .tolerance()
+                    .tolerate(Anomaly::UnpairedAssociatedFile)
+                    .map_err(|_| err)?;
+                Ok((entry, Some(offset)))
+            }
+        }
+    }
+
+    /// Reads a directory record, along with any further records of a multi-extent file.
+    fn read_file_at(
+        &self,
+        block: &mut BlockBuffer,
+        buf_block_num: &mut Option<u64>,
+        offset: u64,
+    ) -> Result<(DirectoryEntry<T>, Option<u64>)> {
+        let (mut entry, mut next_offset) = self.read_record_at(block, buf_block_num, offset)?;
+
+        // Every directory record but the last of a multi-extent file has the multi-extent flag
+        // set.  The records are required to be consecutive.
+        if let DirectoryEntry::File(ref mut file) = entry {
+            let mut more = file.header.file_flags.contains(FileFlags::MULTIEXTENT);
+            while more {
+                let offset =
+                    next_offset.ok_or(ISOError::InvalidFs("Truncated multi-extent file"))?;
+                let (record, offset) = self.read_record_at(block, buf_block_num, offset)?;
+                let header = record.header();
+
+                if record.identifier() != file.identifier {
+                    warn!(
+                        "Multi-extent identifier mismatch: {:?} != {:?}",
+                        record.identifier(),
+                        file.identifier
+                    );
+                }
+
+                more = header.file_flags.contains(FileFlags::MULTIEXTENT);
+                file.push_extent(header);
+                next_offset = offset;
+            }
+        }
+
+        Ok((entry, next_offset))
+    }
+
+    /// Reads a single directory record.
+    fn read_record_at(
+        &self,
+        block: &mut BlockBuffer,
+        buf_block_num: &mut Option<u64>,
+        offset: u64,
+    ) -> Result<(DirectoryEntry<T>, Option<u64>)> {
+        let blksize = u64::from(self.block_size());
         let mut block_num = offset / blksize;
         let mut block_pos = (offset % blksize) as usize;
+        let block = &mut block[..blksize as usize];
 
         if buf_block_num != &Some(block_num) {
             let lba = self.header.extent_loc as u64 + block_num;
             let count = self.file.read_at(block, lba)?;
 
-            if count != 2048 {
+            if count != block.len() {
                 *buf_block_num = None;
                 return Err(ISOError::ReadSize(count));
             }
@@ -128,8 +277,13 @@ impl<T: ISO9660Reader> ISODirectory<T> {
             *buf_block_num = Some(block_num);
         }
 
-        let (header, identifier, susp) =
-            DirectoryEntryHeader::parse(&block[block_pos..], self.header.character_encoding)?;
+        let (header, identifier, susp) = DirectoryEntryHeader::parse(
+            &block[block_pos..],
+            self.header.character_encoding,
+            self.file.system_use(),
+            self.file.decoding(),
+            self.file.tolerance(),
+        )?;
 
         let (susp, cont) = match susp {
             Some(susp) => {
@@ -149,34 +303,39 @@ impl<T: ISO9660Reader> ISODirectory<T> {
         // Pull in all the continuations
         let mut cont = cont;
         let mut susp = susp;
+        let mut visited = Vec::new();
         while let Some(current_cont) = cont {
+            let location = (current_cont.block_location, current_cont.offset);
+            if visited.contains(&location) {
+                self.file.tolerance().tolerate(Anomaly::ContinuationLoop)?;
+                break;
+            }
+            visited.push(location);
+
             let lba = current_cont.block_location as u64;
-            let block = &mut BlockBuffer::new();
+            let block = &mut BlockBuffer::new()[..blksize as usize];
             let count = self.file.read_at(block, lba)?;
+            if count != block.len() && count != usize::try_from(current_cont.length)? {
+                return Err(ISOError::ReadSize(count));
+            }
 
-            #[cfg(feature = "assertions")]
-            assert!(count == 2048 || count == usize::try_from(current_cont.length)?);
-
-            let data = &block[0..usize::try_from(current_cont.length)?];
-            let (_, mut cont_susp) = system_use_entries(data)?;
+            let start = usize::try_from(current_cont.offset)?;
+            let end = start + usize::try_from(current_cont.length)?;
+            let data = block.get(start..end).ok_or(ISOError::InvalidFs(
+                "Continuation area extends past its block",
+            ))?;
+            let (_, mut cont_susp) = system_use_entries(data, self.file.tolerance())?;
 
             susp.append(&mut cont_susp);
 
-            let continuation_index = susp
+            // Order is important because we might have e.g. multiple NM entries
+            cont = susp
                 .iter()
-                .position(|entry| matches!(entry, SystemUseEntry::ContinuationArea(_)));
-
-            cont = match continuation_index {
-                Some(index) => {
-                    // Order is important because we might have e.g. multiple NM entries
-                    if let SystemUseEntry::ContinuationArea(new_cont) = susp.remove(index) {
-                        Some(new_cont)
-                    } else {
-                        unreachable!()
-                    }
-                }
-                None => None,
-            }
+                .position(|entry| matches!(entry, SystemUseEntry::ContinuationArea(_)))
+                .and_then(|index| match susp.remove(index) {
+                    SystemUseEntry::ContinuationArea(new_cont) => Some(new_cont),
+                    _ => None,
+                });
         }
 
         trace!("id={identifier:?}");
@@ -184,15 +343,30 @@ impl<T: ISO9660Reader> ISODirectory<T> {
             trace!("{entry:?}");
         }
 
-        let extensions: HashSet<SuspExtension> = susp
+        let extensions: Vec<SuspExtension> = susp
             .iter()
             .filter_map(|entry| match entry {
                 SystemUseEntry::ExtensionsReference(er) => Some(er.extensions.clone()),
                 _ => None,
             })
-            .next()
-            .unwrap_or_else(Vec::new)
+            .flatten()
+            .collect();
+
+        // The root's `.` entry registers the extensions for the whole hierarchy.
+        let origins = attribute(
+            &susp,
+            match extensions.is_empty() {
+                true => self.file.extensions(),
+                false => &extensions,
+            },
+        );
+
+        // Entries belonging to an extension we don't know may reuse a signature we do.
+        let susp: Vec<SystemUseEntry> = susp
             .into_iter()
+            .zip(&origins)
+            .filter(|(_, origin)| !matches!(origin.extension, Some(SuspExtension::Other { .. })))
+            .map(|(entry, _)| entry)
             .collect();
 
         // BEGIN:ROCKRIDGE
@@ -214,7 +388,7 @@ impl<T: ISO9660Reader> ISODirectory<T> {
             })
             .next();
 
-        let alt_name: Option<String> = susp
+        let raw_alt_name: Option<Vec<u8>> = susp
             .iter()
             .filter_map(|entry| match entry {
                 SystemUseEntry::AlternateName(name) => Some(name),
@@ -225,10 +399,28 @@ impl<T: ISO9660Reader> ISODirectory<T> {
             })
             .fold(None, |acc, name| {
                 let mut acc = acc.unwrap_or_default();
-                acc += &name.name;
+                acc.extend_from_slice(&name.name);
                 Some(acc)
             });
 
+        // A multibyte character may be split across `NM` entries, so the name is decoded whole.
+        let alt_name = match &raw_alt_name {
+            Some(raw) => {
+                let (name, had_errors) = self
+                    .file
+                    .decoding()
+                    .code_page
+                    .decode_without_bom_handling(raw);
+                if had_errors {
+                    self.file
+                        .tolerance()
+                        .tolerate(Anomaly::InvalidRockRidgeName)?;
+                }
+                Some(name.into_owned())
+            }
+            None => None,
+        };
+
         let timestamps: PosixTimestamp = susp
             .iter()
             .filter_map(|entry| match entry {
@@ -267,45 +459,71 @@ impl<T: ISO9660Reader> ISODirectory<T> {
                 acc
             });
 
-        let symlink_target: Option<String> = susp
+        // The root, current and parent directory components have no content of their own.
+        let special_components = SymbolicLinkRecordFlags::ROOT
+            | SymbolicLinkRecordFlags::CURRENT
+            | SymbolicLinkRecordFlags::PARENT;
+        let malformed_symlink = susp.iter().any(|entry| match entry {
+            SystemUseEntry::SymbolicLink(symlink) => symlink.records.iter().any(|component| {
+                component.flags.intersects(special_components.clone())
+                    && !component.component.is_empty()
+            }),
+            _ => false,
+        });
+        if malformed_symlink {
+            self.file.tolerance().tolerate(Anomaly::SymlinkComponent)?;
+        }
+
+        // Components are joined as recorded, since a multibyte character may be split across
+        // them, then the target is decoded whole.
+        let raw_symlink_target: Option<Vec<u8>> = susp
             .iter()
             .filter_map(|entry| match entry {
-                SystemUseEntry::SymbolicLink(symlink) => Some(symlink.clone()),
+                SystemUseEntry::SymbolicLink(symlink) => Some(symlink),
                 _ => None,
             })
             .take_while_inclusive(|meta| meta.should_continue)
-            .map(|symlink| {
-                symlink
-                    .records
-                    .into_iter()
-                    .map(|component| {
-                        if component.flags.contains(SymbolicLinkRecordFlags::ROOT) {
-                            #[cfg(feature = "assertions")]
-                            assert!(component.component.is_empty());
-
-                            (&PathComponent::RootDir).into()
-                        } else if component.flags.contains(SymbolicLinkRecordFlags::CURRENT) {
-                            #[cfg(feature = "assertions")]
-                            assert!(component.component.is_empty());
-
-                            (&PathComponent::CurDir).into()
-                        } else if component.flags.contains(SymbolicLinkRecordFlags::PARENT) {
-                            #[cfg(feature = "assertions")]
-                            assert!(component.component.is_empty());
-
-                            (&PathComponent::ParentDir).into()
-                        } else {
-                            PathBuf::from(component.component)
-                        }
-                    })
-                    .fold(PathBuf::new(), |acc: PathBuf, component: PathBuf| {
-                        acc.join(component)
-                    })
-            })
-            .fold(None, |acc: Option<PathBuf>, component: PathBuf| {
-                Some(acc.unwrap_or_default().join(component))
+            .flat_map(|symlink| &symlink.records)
+            .fold(None, |acc, component| {
+                // A component recorded with `CONTINUE` runs on into the next one.
+                let (mut acc, continued): (Vec<u8>, bool) = acc.unwrap_or_default();
+                if component.flags.contains(SymbolicLinkRecordFlags::ROOT) {
+                    acc.clear();
+                }
+                if !continued && !acc.is_empty() && !acc.ends_with(b"/") {
+                    acc.push(b'/');
+                }
+
+                if component.flags.contains(SymbolicLinkRecordFlags::ROOT) {
+                    acc.push(b'/');
+                } else if component.flags.contains(SymbolicLinkRecordFlags::CURRENT) {
+                    acc.push(b'.');
+                } else if component.flags.contains(SymbolicLinkRecordFlags::PARENT) {
+                    acc.extend_from_slice(b"..");
+                } else {
+                    acc.extend_from_slice(&component.component);
+                }
+                let continued = component.flags.contains(SymbolicLinkRecordFlags::CONTINUE);
+                Some((acc, continued))
             })
-            .map(|target| target.to_str().unwrap().into());
+            .map(|(target, _)| target);
+
+        let symlink_target = match &raw_symlink_target {
+            Some(raw) => {
+                let (target, had_errors) = self
+                    .file
+                    .decoding()
+                    .code_page
+                    .decode_without_bom_handling(raw);
+                if had_errors {
+                    self.file
+                        .tolerance()
+                        .tolerate(Anomaly::InvalidSymlinkTarget)?;
+                }
+                Some(target.into_owned())
+            }
+            None => None,
+        };
 
         let attributes: Option<PosixAttributes> = susp
             .iter()
@@ -315,6 +533,87 @@ impl<T: ISO9660Reader> ISODirectory<T> {
             })
             .next();
 
+        let device: Option<DeviceNumber> = susp
+            .iter()
+            .filter_map(|entry| match entry {
+                SystemUseEntry::DeviceNumber(device) => Some(*device),
+                _ => None,
+            })
+            .next();
+
+        let sparse: Option<SparseFile> = susp
+            .iter()
+            .filter_map(|entry| match entry {
+                SystemUseEntry::SparseFile(sparse) => Some(*sparse),
+                _ => None,
+            })
+            .next();
+
+        let zisofs: Option<ZisofsFile> = susp
+            .iter()
+            .filter_map(|entry| match entry {
+                SystemUseEntry::Zisofs(zisofs) => Some(*zisofs),
+                _ => None,
+            })
+            .next();
+
+        let apple: Option<AppleInfo> = susp
+            .iter()
+            .filter_map(|entry| match entry {
+                SystemUseEntry::Apple(info) | SystemUseEntry::AppleLegacy(info) => Some(*info),
+                _ => None,
+            })
+            .next();
+
+        let amiga: Option<AmigaInfo> = susp
+            .iter()
+            .filter_map(|entry| match entry {
+                SystemUseEntry::Amiga(amiga) => Some(amiga),
+                _ => None,
+            })
+            .fold(None, |acc: Option<(AmigaInfo, bool)>, entry| {
+                let (mut info, continued) = acc.unwrap_or_default();
+                info.protection = info.protection.or(entry.protection);
+                if let Some(comment) = &entry.comment {
+                    match (&mut info.comment, continued) {
+                        (Some(acc), true) => acc.push_str(comment),
+                        (acc, _) => *acc = Some(comment.clone()),
+                    }
+                }
+                Some((info, entry.should_continue))
+            })
+            .map(|(info, _)| info);
+
+        let xa: Option<XaRecord> = susp
+            .iter()
+            .filter_map(|entry| match entry {
+                SystemUseEntry::Xa(xa) => Some(*xa),
+                _ => None,
+            })
+            .next();
+
+        let components: Vec<u8> = susp
+            .iter()
+            .filter_map(|entry| match entry {
+                SystemUseEntry::AttributeList(list) => Some(list.components.as_slice()),
+                _ => None,
+            })
+            .flatten()
+            .copied()
+            .collect();
+        let (xattrs, acl) = match components.is_empty() {
+            true => Default::default(),
+            false => match aaip::attributes(&components) {
+                Ok((_, attributes)) => attributes,
+                Err(_) => {
+                    self.file
+                        .tolerance()
+                        .tolerate(Anomaly::MalformedAttributes)?;
+                    Default::default()
+                }
+            },
+        };
+
         if !extensions.is_empty() {
             trace!("Found the following extensions for {identifier:?}:");
             for extension in extensions.iter() {
@@ -323,14 +622,23 @@ impl<T: ISO9660Reader> ISODirectory<T> {
         }
         // END:ROCKRIDGE
 
-        // Theoretically this should/could contain e.g. Amiga or Apple specific extensions munged into a more generic representation
         let extra_meta = ExtraMeta {
             alt_name,
+            raw_alt_name,
             symlink_target,
             attributes,
+            device,
             extensions,
+            origins,
             timestamps,
             relocated,
+            sparse,
+            zisofs,
+            xattrs,
+            acl,
+            apple,
+            amiga,
+            xa,
         };
 
         block_pos += header.length as usize;
@@ -338,13 +646,13 @@ impl<T: ISO9660Reader> ISODirectory<T> {
         let entry = DirectoryEntry::new(header, extra_meta, identifier, self.file.clone())?;
 
         // All bytes after the last directory entry are zero.
-        if block_pos >= (2048 - 33) || block[block_pos] == 0 {
+        if block_pos >= block.len() - 33 || block[block_pos] == 0 {
             block_num += 1;
             block_pos = 0;
         }
 
         let next_offset = if block_num < self.block_count() as u64 {
-            Some(2048 * block_num + u64::try_from(block_pos)?)
+            Some(blksize * block_num + u64::try_from(block_pos)?)
         } else {
             None
         };
@@ -354,33 +662,57 @@ impl<T: ISO9660Reader> ISODirectory<T> {
         if let Some(child_link) = child_link {
             match entry {
                 DirectoryEntry::File(file_entry) => {
-                    let mut header = file_entry.header;
-                    let ext = file_entry.ext;
-                    let identifier = file_entry.identifier;
-
-                    header.file_flags = header.file_flags.union(FileFlags::DIRECTORY);
-                    header.extent_loc = child_link.0;
-                    // Lazy…
-                    header.extent_length = 2048;
+                    // The relocated directory's own `.` record knows how long it is.
+                    let mut header = self
+                        .directory_at(child_link.0, file_entry.identifier.clone())?
+                        .header;
+                    header.raw_identifier = file_entry.header.raw_identifier;
 
                     let new_entry = DirectoryEntry::Directory(ISODirectory::new(
                         header,
-                        ext,
-                        identifier,
+                        file_entry.ext,
+                        file_entry.identifier,
                         self.file.clone(),
                     ));
 
                     Ok((new_entry, next_offset))
                 }
-                _ => unimplemented!("We shouldn't have a child link for a not-regular-file entry"),
+                _ => {
+                    self.file.tolerance().tolerate(Anomaly::ChildLink)?;
+                    Ok((entry, next_offset))
+                }
             }
         } else {
             Ok((entry, next_offset))
         }
     }
 
+    /// Returns the directory recorded at `extent_loc` on the same volume, e.g. as listed by a
+    /// path table.  The directory's own `.` record supplies its header.
+    pub(crate) fn directory_at(&self, extent_loc: u32, identifier: String) -> Result<Self> {
+        let mut header = self.header.clone();
+        header.extent_loc = extent_loc;
+        header.extent_length = u32::from(self.block_size());
+
+        let stub = ISODirectory::new(header, ExtraMeta::default(), identifier, self.file.clone());
+        match stub
+            .read_record_at(&mut BlockBuffer::new(), &mut None, 0)?
+            .0
+        {
+            DirectoryEntry::Directory(dot) => Ok(ISODirectory::new(
+                dot.header,
+                dot.ext,
+                stub.identifier,
+                self.file.clone(),
+            )),
+            _ => Err(ISOError::InvalidFs(
+                "Path table does not point at a directory",
+            )),
+        }
+    }
+
     /// Returns a [`ISODirectoryIterator`], akin to POSIX.1's `readdir`.
-    pub fn contents(&self) -> ISODirectoryIterator<T> {
+    pub fn contents(&self) -> ISODirectoryIterator<'_, T> {
         ISODirectoryIterator {
             directory: self,
             block: BlockBuffer::new(),
@@ -446,6 +778,8 @@ impl<T: ISO9660Reader> ISODirectory<T> {
 }
 
 /// Iterator for the contents of [`ISODirectory`] constructed by [`contents()`](ISODirectory::contents()).  Similar to POSIX.1's `readdir`.
+///
+/// Iteration ends after the first error.
 pub struct ISODirectoryIterator<'a, T: ISO9660Reader> {
     directory: &'a ISODirectory<T>,
     next_offset: Option<u64>,
@@ -466,7 +800,11 @@ impl<'a, T: ISO9660Reader> Iterator for ISODirectoryIterator<'a, T> {
                 self.next_offset = next_offset;
                 Some(Ok(entry))
             }
-            Err(err) => Some(Err(err)),
+            Err(err) => {
+                // The next record can't be trusted to be found, so an error ends the iteration.
+                self.next_offset = None;
+                Some(Err(err))
+            }
         }
     }
 }
@@ -4,15 +4,27 @@
 use log::{debug, error, info, trace, warn};
 
 use std::{
-    cmp::min,
-    convert::TryFrom,
+    cmp::{max, min},
     fmt,
     io::{self, Read, Seek, SeekFrom, Write},
-    str::FromStr,
 };
 
-use super::{DirectoryEntryHeader, ExtraAttributes, ExtraMeta};
-use crate::{BlockBuffer, BlockBufferCtor, FileRef, ISO9660Reader, Result, BLOCK_SIZE};
+use miniz_oxide::inflate::decompress_to_vec_zlib_with_limit;
+
+use super::{
+    split_version, DirectoryEntryHeader, ExtraAttributes, ExtraMeta, SparseFile, XaAttributes,
+    ZisofsFile,
+};
+use crate::{
+    raw_sector::FORM2_DATA_SIZE, BlockBuffer, BlockBufferCtor, FileRef, ISO9660Reader, ISOError,
+    Result, BLOCK_SIZE,
+};
+
+/// Holes are read from here.
+static ZEROS: BlockBuffer = [0; BLOCK_SIZE as usize];
+
+/// The start of every zisofs file header.
+const ZISOFS_MAGIC: [u8; 8] = [0x37, 0xE4, 0x53, 0x96, 0xC9, 0xDB, 0xD6, 0x07];
 
 /// [`DirectoryEntry`](crate::DirectoryEntry) for regular files.
 ///
@@ -31,9 +43,34 @@ pub struct ISOFile<T: ISO9660Reader> {
 
     pub(super) ext: ExtraMeta,
 
+    extents: Vec<Extent>,
+
+    /// The extents of the associated file, if there is one.
+    associated: Vec<Extent>,
+
     file: FileRef<T>,
 }
 
+/// A contiguous run of logical blocks holding (part of) a file's data.
+///
+/// # See Also
+///
+/// ISO-9660 / ECMA-119 § 6.5.1
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) struct Extent {
+    pub lba: u32,
+    pub length: u32,
+}
+
+impl From<&DirectoryEntryHeader> for Extent {
+    fn from(header: &DirectoryEntryHeader) -> Self {
+        Extent {
+            lba: header.extent_loc,
+            length: header.extent_length,
+        }
+    }
+}
+
 impl<T: ISO9660Reader> ExtraAttributes for ISOFile<T> {
     fn ext(&self) -> &ExtraMeta {
         &self.ext
@@ -51,6 +88,8 @@ impl<T: ISO9660Reader> fmt::Debug for ISOFile<T> {
             .field("identifier", &self.identifier)
             .field("version", &self.version)
             .field("ext", &self.ext)
+            .field("extents", &self.extents)
+            .field("associated", &self.associated)
             .finish()
     }
 }
@@ -62,29 +101,11 @@ impl<T: ISO9660Reader> ISOFile<T> {
         identifier: String,
         file: FileRef<T>,
     ) -> Result<Self> {
-        let mut identifier = match ext.alt_name.as_ref() {
-            Some(alt_name) => alt_name.clone(),
-            None => identifier,
-        };
-
-        // Files (not directories) in ISO 9660 have a version number, which is
-        // provided at the end of the identifier, seperated by ';'.
-        // If not, assume 1.
-        let version = match identifier.rfind(';') {
-            Some(idx) => {
-                let version = u16::from_str(&identifier[idx + 1..])?;
-                identifier.truncate(idx);
-                version
-            }
-            None => 1,
-        };
-
-        // Files without an extension have a '.' at the end
-        if identifier.ends_with('.') {
-            identifier.pop();
-        }
+        let (identifier, version) = split_version(identifier, &ext, file.is_enhanced())?;
 
         Ok(ISOFile {
+            extents: vec![Extent::from(&header)],
+            associated: Vec::new(),
             header,
             identifier,
             version,
@@ -93,53 +114,597 @@ impl<T: ISO9660Reader> ISOFile<T> {
         })
     }
 
-    /// Returns the size of the file in bytes.
-    pub fn size(&self) -> u32 {
-        self.header.extent_length
+    /// Appends the extent described by a subsequent directory record of a multi-extent file.
+    ///
+    /// # See Also
+    ///
+    /// ISO-9660 / ECMA-119 § 9.1.6
+    pub(crate) fn push_extent(&mut self, header: &DirectoryEntryHeader) {
+        self.extents.push(Extent::from(header));
     }
 
-    /// Returns an [`ISOFileReader`] for this file.
+    /// Attaches the associated file recorded just before this one.
+    ///
+    /// # See Also
+    ///
+    /// ISO-9660 / ECMA-119 § 9.3
+    pub(crate) fn set_associated(&mut self, associated: &ISOFile<T>) {
+        self.associated = associated.extents.clone();
+    }
+
+    /// Returns the size of the file in bytes.  Files recorded in multiple extents may exceed 4 GiB.
+    /// For sparse files this is the logical size, holes included, and for compressed files it's the
+    /// uncompressed size.
+    pub fn size(&self) -> u64 {
+        if let Some(zisofs) = self.compression() {
+            u64::from(zisofs.uncompressed_size)
+        } else if let Some(sparse) = self.ext.sparse {
+            sparse.virtual_size
+        } else {
+            self.recorded_size()
+        }
+    }
+
+    /// Returns the number of bytes recorded on the volume for this file.  This is the same as
+    /// [`size()`](Self::size) unless the file is sparse, in which case it includes the sparse tables
+    /// and excludes the holes, or compressed.
+    ///
+    /// # See Also
+    ///
+    /// Rock Ridge Interchange Protocol § 4.1.7
+    pub fn recorded_size(&self) -> u64 {
+        self.extents
+            .iter()
+            .map(|extent| u64::from(extent.length))
+            .sum()
+    }
+
+    /// Returns true if the file has holes that aren't recorded on the volume.
+    ///
+    /// # See Also
+    ///
+    /// Rock Ridge Interchange Protocol § 4.1.7
+    pub fn is_sparse(&self) -> bool {
+        self.ext.sparse.is_some()
+    }
+
+    /// Returns true if the file is zisofs compressed.  Files compressed with an algorithm other than
+    /// zlib are read as recorded, and this returns false for them.
+    pub fn is_compressed(&self) -> bool {
+        self.compression().is_some()
+    }
+
+    fn compression(&self) -> Option<ZisofsFile> {
+        self.ext
+            .zisofs
+            .filter(|zisofs| zisofs.algorithm == ZisofsFile::ZLIB)
+    }
+
+    /// Returns true if the file is recorded in Mode 2 Form 2 sectors, according to its CD-ROM XA
+    /// record.  Video CD `.DAT` and PlayStation `.STR` streams usually are.
+    pub fn is_form2(&self) -> bool {
+        self.ext
+            .xa
+            .is_some_and(|xa| xa.attributes.contains(XaAttributes::MODE2_FORM2))
+    }
+
+    /// Returns the number of extents the file is recorded in.  This is 1 unless the file has the
+    /// multi-extent flag set.
+    pub fn extent_count(&self) -> usize {
+        self.extents.len()
+    }
+
+    /// Returns an [`ISOFileReader`] for this file.  Compressed files are decompressed as they're
+    /// read.
+    ///
+    /// Mode 2 Form 2 files are read as the 2324 bytes of user data in each sector, if the
+    /// underlying reader has raw sectors (e.g. a [`RawSectorReader`](crate::RawSectorReader)), in
+    /// which case the reader is longer than [`size()`](Self::size).  Otherwise only the first
+    /// 2048 bytes of each sector are available.
     pub fn read(&self) -> ISOFileReader<T> {
-        ISOFileReader {
-            buf: BlockBuffer::new(),
-            buf_lba: None,
-            seek: 0,
-            start_lba: self.header.extent_loc,
-            size: self.size() as usize,
-            file: self.file.clone(),
+        let reader = self.read_recorded();
+        if let Some(zisofs) = self.compression() {
+            reader.zisofs(zisofs)
+        } else if let Some(sparse) = self.ext.sparse {
+            reader.sparse(sparse)
+        } else if self.is_form2() {
+            reader.form2()
+        } else {
+            reader
+        }
+    }
+
+    /// Returns an [`ISOFileReader`] for the data recorded on the volume, without decompressing it
+    /// or filling in holes.
+    pub fn read_recorded(&self) -> ISOFileReader<T> {
+        let reader = ISOFileReader::new(self.file.clone(), self.extents.clone());
+        match self.header.file_unit_size {
+            0 => reader,
+            unit => reader.interleaved(unit, self.header.interleave_gap_size),
+        }
+    }
+
+    /// Returns an [`ISOFileReader`] for the file's resource fork, if it has one.  On Apple discs
+    /// the resource fork is recorded as an associated file with the same name.
+    ///
+    /// # See Also
+    ///
+    /// ISO-9660 / ECMA-119 § 9.3
+    pub fn resource_fork(&self) -> Option<ISOFileReader<T>> {
+        match self.associated.is_empty() {
+            true => None,
+            false => Some(ISOFileReader::new(
+                self.file.clone(),
+                self.associated.clone(),
+            )),
         }
     }
 }
 
 /// A struct providing read-only access to a file on the filesystem.
+///
+/// Files recorded in multiple extents are presented as a single stream; the extents need not be
+/// contiguous.
+///
+/// Sparse files are presented at their logical size, with holes read as zeros.  Compressed files
+/// are inflated a block at a time, so seeking only costs the block that's read next.
 pub struct ISOFileReader<T: ISO9660Reader> {
     buf: BlockBuffer,
     buf_lba: Option<u64>,
-    seek: usize,
-    start_lba: u32,
-    size: usize,
+    seek: u64,
+    extents: Vec<Extent>,
+    size: u64,
+    sparse: Option<SparseFile>,
+    zisofs: Option<Zisofs>,
+    form2: Option<Form2>,
+    interleave: Option<Interleave>,
     file: FileRef<T>,
 }
 
+/// The layout of a file recorded in interleaved mode, in logical blocks.
+#[derive(Clone, Copy)]
+struct Interleave {
+    unit: u64,
+    gap: u64,
+}
+
+/// The block pointers and most recently inflated block of a zisofs file.
+struct Zisofs {
+    entry: ZisofsFile,
+    pointers: Option<Vec<u32>>,
+    block: Option<(u64, Vec<u8>)>,
+}
+
+/// The most recently read sector of a Mode 2 Form 2 file.
+struct Form2 {
+    lba: Option<u64>,
+    data: Vec<u8>,
+}
+
+impl<T: ISO9660Reader> ISOFileReader<T> {
+    pub(crate) fn new(file: FileRef<T>, extents: Vec<Extent>) -> Self {
+        let size = extents.iter().map(|extent| u64::from(extent.length)).sum();
+
+        ISOFileReader {
+            buf: BlockBuffer::new(),
+            buf_lba: None,
+            seek: 0,
+            extents,
+            size,
+            sparse: None,
+            zisofs: None,
+            form2: None,
+            interleave: None,
+            file,
+        }
+    }
+
+    /// Reads a file recorded in interleaved mode, in file units of `unit` logical blocks, each
+    /// followed by a gap of `gap` logical blocks that belongs to something else.
+    ///
+    /// # See Also
+    ///
+    /// ISO-9660 / ECMA-119 § 9.1.6 and 9.1.7
+    fn interleaved(mut self, unit: u8, gap: u8) -> Self {
+        self.interleave = Some(Interleave {
+            unit: u64::from(unit),
+            gap: u64::from(gap),
+        });
+        self
+    }
+
+    /// Inflates the recorded data.
+    ///
+    /// The recorded data starts with a header holding a magic number, followed by a table of
+    /// 32-bit little-endian pointers to each compressed block, plus one to the end of the last.
+    /// Each block is compressed on its own with zlib, and a block with no data is all zeros.
+    ///
+    /// # See Also
+    ///
+    /// `libisofs`' `doc/zisofs_format.txt`
+    fn zisofs(mut self, entry: ZisofsFile) -> Self {
+        self.size = u64::from(entry.uncompressed_size);
+        self.zisofs = Some(Zisofs {
+            entry,
+            pointers: None,
+            block: None,
+        });
+        self
+    }
+
+    /// Presents the recorded data through the file's sparse tables.
+    ///
+    /// The recorded data starts with the top-level table.  Each table fills one logical block with
+    /// 32-bit little-endian entries, and each entry gives the block, relative to the start of the
+    /// recorded data, of either the next-level table or, in the lowest level, the data itself.  An
+    /// entry of zero, or with the high bit set, is a hole.  A table depth of zero means there are no
+    /// tables: the recorded data is the start of the file and the rest is a hole.
+    ///
+    /// # See Also
+    ///
+    /// Rock Ridge Interchange Protocol § 4.1.7
+    fn sparse(mut self, sparse: SparseFile) -> Self {
+        self.size = sparse.virtual_size;
+        self.sparse = Some(sparse);
+        self
+    }
+
+    /// Reads the full user data of each sector, if the underlying reader has it.  The directory
+    /// record counts 2048 bytes for each sector of the file.
+    ///
+    /// # See Also
+    ///
+    /// System Description CD-ROM XA (the "White Book")
+    fn form2(mut self) -> Self {
+        let blksize = u64::from(BLOCK_SIZE);
+        if self.file.block_size() != BLOCK_SIZE || self.size == 0 {
+            return self;
+        }
+
+        let lba = u64::from(self.extents[0].lba);
+        let mut data = vec![0; FORM2_DATA_SIZE];
+        match self.file.read_sector_at(&mut data, lba) {
+            Ok(Some(_)) => {
+                self.size = self.size.div_ceil(blksize) * FORM2_DATA_SIZE as u64;
+                self.form2 = Some(Form2 {
+                    lba: Some(lba),
+                    data,
+                });
+            }
+            Ok(None) => debug!("Form 2 file read from cooked sectors"),
+            Err(err) => warn!("Unable to read Form 2 sector {lba}: {err}"),
+        }
+
+        self
+    }
+
+    fn read_form2(&mut self, mut buf: &mut [u8]) -> io::Result<usize> {
+        let sector_size = FORM2_DATA_SIZE as u64;
+        let mut seek = self.seek;
+        while !buf.is_empty() && seek < self.size {
+            let sector = seek / sector_size;
+            let start = (seek % sector_size) as usize;
+            let (extent, extent_pos) = match self.locate(sector * u64::from(BLOCK_SIZE)) {
+                Some(location) => location,
+                None => break,
+            };
+
+            let lba = self.lba(extent, extent_pos / u64::from(BLOCK_SIZE));
+            let form2 = self.form2.as_mut().unwrap();
+            if form2.lba != Some(lba) {
+                form2.lba = None;
+                match self.file.read_sector_at(&mut form2.data, lba)? {
+                    // Form 1 sectors interleaved with the Form 2 ones are shorter.
+                    Some(count) => form2.data[count..].fill(0),
+                    None => {
+                        return Err(io::Error::new(
+                            io::ErrorKind::Unsupported,
+                            "Raw sectors are no longer available",
+                        ))
+                    }
+                }
+                form2.lba = Some(lba);
+            }
+
+            seek += buf.write(&form2.data[start..]).unwrap() as u64;
+        }
+
+        let bytes = seek - self.seek;
+        self.seek = seek;
+        Ok(bytes as usize)
+    }
+
+    /// Returns the extent containing the byte at `pos` and the offset of `pos` within it.
+    fn locate(&self, mut pos: u64) -> Option<(Extent, u64)> {
+        for extent in self.extents.iter() {
+            let length = u64::from(extent.length);
+            if pos < length {
+                return Some((*extent, pos));
+            }
+            pos -= length;
+        }
+
+        None
+    }
+
+    /// Returns the logical block recording the `block`th block of the file's data in `extent`,
+    /// stepping over any interleave gaps before it.
+    fn lba(&self, extent: Extent, block: u64) -> u64 {
+        let gaps = match self.interleave {
+            Some(Interleave { unit, gap }) => block / unit * gap,
+            None => 0,
+        };
+        u64::from(extent.lba) + block + gaps
+    }
+
+    fn has_tables(&self) -> bool {
+        self.sparse.is_some_and(|sparse| sparse.table_depth > 0)
+    }
+
+    /// Reads the `block`th block of the recorded data into the buffer, and returns how many of its
+    /// bytes belong to the file.  Returns `None` past the end of the recorded data.
+    fn load(&mut self, block: u64) -> io::Result<Option<usize>> {
+        let blksize = u64::from(self.file.block_size());
+        let (extent, extent_pos) = match self.locate(block * blksize) {
+            Some(location) => location,
+            None => return Ok(None),
+        };
+
+        let lba = self.lba(extent, extent_pos / blksize);
+        if self.buf_lba != Some(lba) {
+            self.file.read_at(&mut self.buf[..blksize as usize], lba)?;
+            self.buf_lba = Some(lba);
+        }
+
+        Ok(Some(
+            min(u64::from(extent.length) - extent_pos, blksize) as usize
+        ))
+    }
+
+    /// Returns the block of the recorded data holding the `block`th logical block of the file, or
+    /// `None` if it's a hole.
+    fn recorded_block(&mut self, block: u64) -> io::Result<Option<u64>> {
+        const HOLE: u32 = 1 << 31;
+
+        let depth = match self.sparse {
+            None => return Ok(Some(block)),
+            Some(SparseFile { table_depth: 0, .. }) => {
+                let blksize = u64::from(self.file.block_size());
+                return Ok(self.locate(block * blksize).map(|_| block));
+            }
+            Some(sparse) => u32::from(sparse.table_depth),
+        };
+
+        let entries = u64::from(self.file.block_size()) / 4;
+        let mut span = entries.checked_pow(depth - 1).unwrap_or(u64::MAX);
+        let mut index = block;
+        let mut table = 0;
+
+        for _ in 0..depth {
+            let slot = index / span;
+            index %= span;
+            if slot >= entries {
+                return Ok(None);
+            }
+
+            if self.load(table)?.is_none() {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    "Sparse table extends past the end of the file",
+                ));
+            }
+            let slot = slot as usize * 4;
+            let entry = u32::from_le_bytes(self.buf[slot..slot + 4].try_into().unwrap());
+            if entry == 0 || entry & HOLE != 0 {
+                return Ok(None);
+            }
+
+            table = u64::from(entry);
+            span = max(span / entries, 1);
+        }
+
+        Ok(Some(table))
+    }
+
+    /// Fills `out` from the recorded data starting at byte `pos`.
+    fn read_recorded_at(&mut self, mut pos: u64, mut out: &mut [u8]) -> io::Result<()> {
+        let blksize = u64::from(self.file.block_size());
+        while !out.is_empty() {
+            let start = (pos % blksize) as usize;
+            let valid = match self.load(pos / blksize)? {
+                Some(valid) if valid > start => valid,
+                _ => {
+                    return Err(io::Error::new(
+                        io::ErrorKind::UnexpectedEof,
+                        "Compressed data extends past the end of the file",
+                    ))
+                }
+            };
+            pos += out.write(&self.buf[start..valid]).unwrap() as u64;
+        }
+
+        Ok(())
+    }
+
+    /// Reads the zisofs header and block pointers, if they haven't been already.
+    fn zisofs_pointers(&mut self) -> io::Result<()> {
+        let entry = match &self.zisofs {
+            Some(Zisofs {
+                pointers: None,
+                entry,
+                ..
+            }) => *entry,
+            _ => return Ok(()),
+        };
+
+        // zisofs only allows 32, 64, and 128 KiB blocks.
+        if !(15..=17).contains(&entry.block_size_log2) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "Invalid zisofs block size",
+            ));
+        }
+
+        let mut magic = [0; ZISOFS_MAGIC.len()];
+        self.read_recorded_at(0, &mut magic)?;
+        if magic != ZISOFS_MAGIC {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "Missing zisofs header",
+            ));
+        }
+
+        let blocks = self.size.div_ceil(1 << entry.block_size_log2);
+        let mut table = vec![0; (blocks as usize + 1) * 4];
+        self.read_recorded_at(u64::from(entry.header_size), &mut table)?;
+
+        let pointers = table
+            .chunks_exact(4)
+            .map(|pointer| u32::from_le_bytes(pointer.try_into().unwrap()))
+            .collect();
+        self.zisofs.as_mut().unwrap().pointers = Some(pointers);
+
+        Ok(())
+    }
+
+    /// Inflates the `block`th compressed block, unless it's the one inflated last.
+    fn inflate(&mut self, block: u64) -> io::Result<()> {
+        self.zisofs_pointers()?;
+
+        let zisofs = self.zisofs.as_ref().unwrap();
+        if zisofs
+            .block
+            .as_ref()
+            .is_some_and(|(index, _)| *index == block)
+        {
+            return Ok(());
+        }
+
+        let block_size = 1_u64 << zisofs.entry.block_size_log2;
+        let length = min(block_size, self.size - block * block_size) as usize;
+        let pointers = zisofs.pointers.as_ref().unwrap();
+        let (start, end) = (pointers[block as usize], pointers[block as usize + 1]);
+
+        // The pointers come straight from the image, so are checked before anything is
+        // allocated.  zlib adds at most 5 bytes per 16 KiB stored block and a 6 byte wrapper.
+        let recorded: u64 = self
+            .extents
+            .iter()
+            .map(|extent| u64::from(extent.length))
+            .sum();
+        let max_compressed = block_size + 5 * (block_size / 16384 + 1) + 6;
+        if start > end {
+            return Err(corrupt_zisofs("zisofs block pointers are out of order"));
+        }
+        if u64::from(end) > recorded {
+            return Err(corrupt_zisofs(
+                "zisofs block pointer is past the end of the file",
+            ));
+        }
+        if u64::from(end - start) > max_compressed {
+            return Err(corrupt_zisofs("zisofs block is too large"));
+        }
+
+        let data = if start == end {
+            vec![0; length]
+        } else {
+            let mut compressed = vec![0; (end - start) as usize];
+            self.read_recorded_at(u64::from(start), &mut compressed)?;
+
+            let mut data = decompress_to_vec_zlib_with_limit(&compressed, block_size as usize)
+                .map_err(|err| {
+                    io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        format!("Corrupt zisofs block {block}: {:?}", err.status),
+                    )
+                })?;
+            data.truncate(length);
+            data
+        };
+
+        self.zisofs.as_mut().unwrap().block = Some((block, data));
+        Ok(())
+    }
+
+    fn read_zisofs(&mut self, mut buf: &mut [u8]) -> io::Result<usize> {
+        let mut seek = self.seek;
+        while !buf.is_empty() && seek < self.size {
+            // Validates the block size before it's used.
+            self.zisofs_pointers()?;
+
+            let block_size_log2 = self.zisofs.as_ref().unwrap().entry.block_size_log2;
+            let block = seek >> block_size_log2;
+            self.inflate(block)?;
+
+            let (_, data) = self.zisofs.as_ref().unwrap().block.as_ref().unwrap();
+            let start = (seek - (block << block_size_log2)) as usize;
+            if start >= data.len() {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("zisofs block {block} is short"),
+                ));
+            }
+            seek += buf.write(&data[start..]).unwrap() as u64;
+        }
+
+        let bytes = seek - self.seek;
+        self.seek = seek;
+        Ok(bytes as usize)
+    }
+}
+
+/// Describes a corrupt zisofs file as an I/O error, since it's found while reading.
+fn corrupt_zisofs(reason: &'static str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, ISOError::InvalidFs(reason))
+}
+
 impl<T: ISO9660Reader> Read for ISOFileReader<T> {
     fn read(&mut self, mut buf: &mut [u8]) -> io::Result<usize> {
-        let blksize = usize::from(BLOCK_SIZE);
+        if self.zisofs.is_some() {
+            return self.read_zisofs(buf);
+        }
+        if self.form2.is_some() {
+            return self.read_form2(buf);
+        }
+
+        let blksize = u64::from(self.file.block_size());
         let mut seek = self.seek;
         while !buf.is_empty() && seek < self.size {
-            let lba = u64::from(self.start_lba) + u64::try_from(seek / blksize).unwrap();
-            if self.buf_lba != Some(lba) {
-                self.file.read_at(&mut self.buf, lba)?;
-                self.buf_lba = Some(lba);
-            }
+            let block = seek / blksize;
+            let start = (seek % blksize) as usize;
+            let end = min(self.size - block * blksize, blksize) as usize;
+
+            let written = match self.recorded_block(block)? {
+                Some(recorded) => {
+                    let valid = match self.load(recorded)? {
+                        // Blocks named by a sparse table are always whole.
+                        Some(_) if self.has_tables() => blksize as usize,
+                        Some(valid) => valid,
+                        None if self.sparse.is_some() => {
+                            return Err(io::Error::new(
+                                io::ErrorKind::InvalidData,
+                                "Sparse table points past the end of the file",
+                            ))
+                        }
+                        None => break,
+                    };
 
-            let start = seek % blksize;
-            let end = min(self.size - (seek / blksize) * blksize, blksize);
-            seek += buf.write(&self.buf[start..end]).unwrap();
+                    if start < valid {
+                        buf.write(&self.buf[start..min(end, valid)])
+                    } else if self.sparse.is_some() {
+                        buf.write(&ZEROS[start..end])
+                    } else {
+                        break;
+                    }
+                }
+                None => buf.write(&ZEROS[start..end]),
+            };
+            seek += written.unwrap() as u64;
         }
 
         let bytes = seek - self.seek;
         self.seek = seek;
-        Ok(bytes)
+        Ok(bytes as usize)
     }
 }
 
@@ -154,7 +719,7 @@ impl<T: ISO9660Reader> Seek for ISOFileReader<T> {
         if seek < 0 {
             Err(io::Error::new(io::ErrorKind::InvalidInput, "Invalid seek"))
         } else {
-            self.seek = seek as usize;
+            self.seek = seek as u64;
             Ok(seek as u64)
         }
     }
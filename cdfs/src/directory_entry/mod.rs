@@ -3,14 +3,24 @@
 mod extra_meta;
 mod isodirectory;
 mod isofile;
+mod special;
 mod symlink;
 
-pub use crate::parse::susp::{PosixAttributes, PosixFileMode, PosixTimestamp, SuspExtension};
+pub use crate::parse::aaip::{Acl, AclEntry, AclPermissions, AclTag};
+pub use crate::parse::susp::{
+    AmigaInfo, AmigaProtection, AppleInfo, DeviceNumber, FinderFlags, PosixAttributes,
+    PosixFileMode, PosixTimestamp, SparseFile, SuspExtension, SystemUseOrigin, ZisofsFile,
+};
+pub use crate::parse::xa::{XaAttributes, XaRecord};
 pub use extra_meta::{ExtraAttributes, ExtraMeta};
 pub use isodirectory::{ISODirectory, ISODirectoryIterator};
+pub(crate) use isofile::Extent;
 pub use isofile::{ISOFile, ISOFileReader};
+pub use special::{Special, SpecialType};
 pub use symlink::Symlink;
 
+use std::str::FromStr;
+
 use crate::parse::directory_entry::{DirectoryEntryHeader, FileFlags};
 use crate::{FileRef, ISO9660Reader, Result};
 
@@ -18,8 +28,8 @@ use crate::{FileRef, ISO9660Reader, Result};
 ///
 /// # Notes
 ///
-/// ISO 9660 / ECMA-119 define other common unix types such as sockets, pipes, and devices.
-/// Currently only regular files, directories, and symbolic links are supported.
+/// Symbolic links, devices, named pipes, and sockets are only distinguished from regular files by
+/// Rock Ridge entries.
 #[derive(Clone)]
 pub enum DirectoryEntry<T: ISO9660Reader> {
     /// Directory entry.
@@ -30,6 +40,9 @@ pub enum DirectoryEntry<T: ISO9660Reader> {
 
     /// Symbolic link entry.
     Symlink(Symlink),
+
+    /// Device, named pipe, or socket entry.
+    Special(Special),
 }
 
 impl<T: ISO9660Reader> std::fmt::Debug for DirectoryEntry<T> {
@@ -38,6 +51,7 @@ impl<T: ISO9660Reader> std::fmt::Debug for DirectoryEntry<T> {
             Self::Directory(dir) => write!(fmt, "{dir:?}"),
             Self::File(file) => write!(fmt, "{file:?}"),
             Self::Symlink(link) => write!(fmt, "{link:?}"),
+            Self::Special(special) => write!(fmt, "{special:?}"),
         }
     }
 }
@@ -54,14 +68,29 @@ impl<T: ISO9660Reader> DirectoryEntry<T> {
             Some(ref attributes) => attributes.mode.contains(PosixFileMode::TYPE_SYMLINK),
             None => false,
         };
+        let special_type = ext
+            .attributes
+            .as_ref()
+            .and_then(|attributes| SpecialType::from_mode(attributes.mode));
 
         if is_dir {
             Ok(DirectoryEntry::Directory(ISODirectory::new(
                 header, ext, identifier, file,
             )))
+        } else if let Some(special_type) = special_type {
+            Ok(DirectoryEntry::Special(Special::new(
+                header,
+                ext,
+                identifier,
+                special_type,
+                file.is_enhanced(),
+            )?))
         } else if is_symlink {
             Ok(DirectoryEntry::Symlink(Symlink::new(
-                header, ext, identifier,
+                header,
+                ext,
+                identifier,
+                file.is_enhanced(),
             )?))
         } else {
             Ok(DirectoryEntry::File(ISOFile::new(
@@ -77,16 +106,29 @@ impl<T: ISO9660Reader> DirectoryEntry<T> {
     /// ISO-9660 / ECMA-119 specify various restrictions on what characters are allowed in an
     /// identifier, and how long they can be.  Joliet specifies UTF-16BE encoding for its
     /// alternative directory hierarchies.  Finally, in Rust [`String`]s use UTF-8.  The way this
-    /// works in practice is that we're okay with identifiers that are UTF-8 or a subset thereof,
+    /// works in practice is that identifiers and Rock Ridge names are decoded with the code page
+    /// set by [`OpenOptions::code_page()`](crate::OpenOptions::code_page), UTF-8 by default,
     /// unless a character encoding is explicitly specified (e.g. Joliet).  If UTF-16 is specified,
-    /// it is assumed to be *big endian*.
+    /// it is assumed to be *big endian*.  See [`DirectoryEntry::raw_identifier()`] for the name
+    /// as recorded.
     pub fn identifier(&self) -> &str {
         match *self {
             DirectoryEntry::Directory(ref dir) => &dir.identifier,
             DirectoryEntry::File(ref file) => &file.identifier,
             DirectoryEntry::Symlink(ref link) => &link.identifier,
+            DirectoryEntry::Special(ref special) => &special.identifier,
         }
     }
+
+    /// Returns the bytes [`DirectoryEntry::identifier()`] was decoded from, exactly as recorded:
+    /// the Rock Ridge name if there is one, or else the file identifier of the directory record,
+    /// including any version number.
+    pub fn raw_identifier(&self) -> &[u8] {
+        self.ext()
+            .raw_alt_name
+            .as_deref()
+            .unwrap_or(&self.header().raw_identifier)
+    }
 }
 
 impl<T: ISO9660Reader> ExtraAttributes for DirectoryEntry<T> {
@@ -95,6 +137,7 @@ impl<T: ISO9660Reader> ExtraAttributes for DirectoryEntry<T> {
             DirectoryEntry::Directory(ref dir) => &dir.ext,
             DirectoryEntry::File(ref file) => &file.ext,
             DirectoryEntry::Symlink(ref link) => &link.ext,
+            DirectoryEntry::Special(ref special) => &special.ext,
         }
     }
 
@@ -103,6 +146,42 @@ impl<T: ISO9660Reader> ExtraAttributes for DirectoryEntry<T> {
             DirectoryEntry::Directory(ref dir) => &dir.header,
             DirectoryEntry::File(ref file) => &file.header,
             DirectoryEntry::Symlink(ref link) => &link.header,
+            DirectoryEntry::Special(ref special) => &special.header,
         }
     }
 }
+
+/// Returns the name of a non-directory entry, the Rock Ridge name if there is one, along with its
+/// version number.
+///
+/// Files (not directories) in ISO 9660 have a version number, which is provided at the end of the
+/// identifier, separated by ';'.  If not, assume 1.  Files without an extension have a '.' at the
+/// end, which is dropped.  Rock Ridge names are POSIX names, and identifiers in an enhanced
+/// hierarchy have no such structure either, so both are taken as they are.
+pub(crate) fn split_version(
+    mut identifier: String,
+    ext: &ExtraMeta,
+    enhanced: bool,
+) -> Result<(String, u16)> {
+    if let Some(alt_name) = ext.alt_name.as_ref() {
+        return Ok((alt_name.clone(), 1));
+    }
+    if enhanced {
+        return Ok((identifier, 1));
+    }
+
+    let version = match identifier.rfind(';') {
+        Some(idx) => {
+            let version = u16::from_str(&identifier[idx + 1..])?;
+            identifier.truncate(idx);
+            version
+        }
+        None => 1,
+    };
+
+    if identifier.ends_with('.') {
+        identifier.pop();
+    }
+
+    Ok((identifier, version))
+}
@@ -0,0 +1,117 @@
+// SPDX-License-Identifier: (MIT OR Apache-2.0)
+
+use std::fmt;
+
+use super::{
+    split_version, DeviceNumber, DirectoryEntryHeader, ExtraAttributes, ExtraMeta, PosixFileMode,
+};
+use crate::Result;
+
+/// The POSIX.1 file type bits of `st_mode`.  Equivalent to `S_IFMT`.
+const TYPE_MASK: u32 = 0o0170000;
+
+/// The kind of a [`Special`] file.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum SpecialType {
+    /// A block device.  Equivalent to `S_IFBLK`.
+    BlockDevice,
+
+    /// A character device.  Equivalent to `S_IFCHR`.
+    CharDevice,
+
+    /// A named pipe.  Equivalent to `S_IFIFO`.
+    Fifo,
+
+    /// An `AF_LOCAL` socket.  Equivalent to `S_IFSOCK`.
+    Socket,
+}
+
+impl SpecialType {
+    /// Returns the kind of special file `mode` describes, if any.
+    pub(crate) fn from_mode(mode: PosixFileMode) -> Option<Self> {
+        let file_type = mode.bits() & TYPE_MASK;
+        [
+            (PosixFileMode::TYPE_BLOCK_DEV, Self::BlockDevice),
+            (PosixFileMode::TYPE_CHAR_DEV, Self::CharDevice),
+            (PosixFileMode::TYPE_PIPE, Self::Fifo),
+            (PosixFileMode::TYPE_SOCKET, Self::Socket),
+        ]
+        .into_iter()
+        .find(|(mode, _)| mode.bits() == file_type)
+        .map(|(_, special)| special)
+    }
+}
+
+/// [`DirectoryEntry`](crate::DirectoryEntry) for devices, named pipes, and sockets, as described
+/// by a Rock Ridge `PX` entry.  Devices also have a `PN` entry holding their device number.
+///
+/// # See Also
+///
+/// * ISO-9660 / ECMA-119 § 9
+/// * Rock Ridge Interchange Protocol §§ 4.1.1, 4.1.2
+#[derive(Clone)]
+pub struct Special {
+    pub(crate) header: DirectoryEntryHeader,
+
+    /// The name encoded with UTF-8.
+    pub identifier: String,
+
+    /// File version; ranges from 1 to 32767
+    pub version: u16,
+
+    special_type: SpecialType,
+
+    pub(super) ext: ExtraMeta,
+}
+
+impl fmt::Debug for Special {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.debug_struct("Special")
+            .field("header", &self.header)
+            .field("identifier", &self.identifier)
+            .field("version", &self.version)
+            .field("special_type", &self.special_type)
+            .field("ext", &self.ext)
+            .finish()
+    }
+}
+
+impl ExtraAttributes for Special {
+    fn ext(&self) -> &ExtraMeta {
+        &self.ext
+    }
+
+    fn header(&self) -> &DirectoryEntryHeader {
+        &self.header
+    }
+}
+
+impl Special {
+    pub(crate) fn new(
+        header: DirectoryEntryHeader,
+        ext: ExtraMeta,
+        identifier: String,
+        special_type: SpecialType,
+        enhanced: bool,
+    ) -> Result<Self> {
+        let (identifier, version) = split_version(identifier, &ext, enhanced)?;
+
+        Ok(Self {
+            header,
+            identifier,
+            version,
+            special_type,
+            ext,
+        })
+    }
+
+    /// Returns what kind of special file this is.
+    pub fn special_type(&self) -> SpecialType {
+        self.special_type
+    }
+
+    /// Returns the device number, if this is a device with a `PN` entry.
+    pub fn device(&self) -> Option<DeviceNumber> {
+        self.ext.device
+    }
+}
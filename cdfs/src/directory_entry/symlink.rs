@@ -1,8 +1,8 @@
 // SPDX-License-Identifier: (MIT OR Apache-2.0)
 
-use std::{fmt, str::FromStr};
+use std::fmt;
 
-use super::{DirectoryEntryHeader, ExtraAttributes, ExtraMeta};
+use super::{split_version, DirectoryEntryHeader, ExtraAttributes, ExtraMeta};
 use crate::Result;
 
 /// [`DirectoryEntry`](crate::DirectoryEntry) for symbolic links. Typically generated from `SL` entries.
@@ -50,28 +50,9 @@ impl Symlink {
         header: DirectoryEntryHeader,
         ext: ExtraMeta,
         identifier: String,
+        enhanced: bool,
     ) -> Result<Self> {
-        let mut identifier = match ext.alt_name.as_ref() {
-            Some(alt_name) => alt_name.clone(),
-            None => identifier,
-        };
-
-        // Files (not directories) in ISO 9660 have a version number, which is
-        // provided at the end of the identifier, seperated by ';'.
-        // If not, assume 1.
-        let version = match identifier.rfind(';') {
-            Some(idx) => {
-                let version = u16::from_str(&identifier[idx + 1..])?;
-                identifier.truncate(idx);
-                version
-            }
-            None => 1,
-        };
-
-        // Files without an extension have a '.' at the end
-        if identifier.ends_with('.') {
-            identifier.pop();
-        }
+        let (identifier, version) = split_version(identifier, &ext, enhanced)?;
 
         Ok(Self {
             header,
@@ -6,8 +6,11 @@ use std::{
     str,
 };
 
+use nom::error::{ContextError, ErrorKind, FromExternalError, ParseError};
 use thiserror::Error;
 
+use crate::Anomaly;
+
 /// The master error structure.
 #[derive(Error, Debug)]
 pub enum ISOError {
@@ -15,8 +18,9 @@ pub enum ISOError {
     #[error("IO error: {0}")]
     Io(#[from] io::Error),
 
-    /// String value that was expected to fit into a UTF-8 shape, in fact did not.  This is not the
-    /// greatest design decision as nothing in ISO 9660 / ECMA-119 should be encoded in UTF-8.
+    /// String value that was expected to fit into a UTF-8 shape, in fact did not.  Identifiers are
+    /// instead decoded with the code page set by [`OpenOptions::code_page()`](crate::OpenOptions),
+    /// so this isn't returned for them.
     #[error("UTF-8 error: {0}")]
     Utf8(#[from] str::Utf8Error),
 
@@ -28,6 +32,32 @@ pub enum ISOError {
     #[error("Invalid ISO9660: {0}")]
     InvalidFs(&'static str),
 
+    /// The filesystem doesn't follow the standard, and [`ParseMode::Strict`](crate::ParseMode)
+    /// rejected it.
+    #[error("Invalid ISO9660: {0}")]
+    Anomaly(Anomaly),
+
+    /// A CUE sheet could not be parsed or did not describe a usable track.  `line` is 0 if the
+    /// problem isn't tied to a specific line.
+    #[error("Invalid CUE sheet (line {line}): {reason}")]
+    InvalidCue {
+        /// The line of the CUE sheet containing the problem.
+        line: usize,
+
+        /// What went wrong.
+        reason: &'static str,
+    },
+
+    /// An [`IsoBuilder`](crate::IsoBuilder) was asked to record something it can't.
+    #[error("Unable to add {path:?}: {reason}")]
+    Build {
+        /// The path within the image.
+        path: String,
+
+        /// What went wrong.
+        reason: &'static str,
+    },
+
     /// A [`String`] that was supposed to contain a numeric value did not.  Currently this error only occurs in the file identifier parsing code.
     #[error("Int parse error: {0}")]
     ParseInt(#[from] ParseIntError),
@@ -40,10 +70,15 @@ pub enum ISOError {
     ///
     /// # See Also
     ///
-    /// [`BLOCK_SIZE`](crate::BLOCK_SIZE)
-    #[error("Wanted to read '{}' bytes, got '{0}' bytes", crate::BLOCK_SIZE)]
+    /// [`ISO9660::block_size()`](crate::ISO9660::block_size)
+    #[error("Short read, got '{0}' bytes")]
     ReadSize(usize),
 
+    /// A `nom` parser needed more data than the buffer it was given.  Presumably the filesystem is
+    /// corrupt.
+    #[error("Parse error: incomplete data")]
+    Incomplete,
+
     /// A `nom` parser failed. Most likely the filesystem is either corrupt.  Enabling the
     /// `verbose-error` feature will replace this variant with the `VerboseNom` variant and take
     /// advantage of nom's `VerboseError` type.
@@ -59,37 +94,96 @@ pub enum ISOError {
     VerboseNom(nom::error::VerboseError<Vec<u8>>),
 }
 
+impl From<nom::Err<OurNomError<&[u8]>>> for ISOError {
+    fn from(err: nom::Err<OurNomError<&[u8]>>) -> ISOError {
+        match err {
+            nom::Err::Error(e) | nom::Err::Failure(e) => match e.anomaly {
+                Some(anomaly) => ISOError::Anomaly(anomaly),
+                None => e.inner.into(),
+            },
+            nom::Err::Incomplete(_) => ISOError::Incomplete,
+        }
+    }
+}
+
 #[cfg(not(feature = "verbose-error"))]
-impl From<nom::Err<nom::error::Error<&[u8]>>> for ISOError {
-    fn from(err: nom::Err<nom::error::Error<&[u8]>>) -> ISOError {
-        ISOError::Nom(match err {
-            nom::Err::Error(e) | nom::Err::Failure(e) => e.code,
-            nom::Err::Incomplete(_) => panic!(), // XXX
-        })
+impl From<nom::error::Error<&[u8]>> for ISOError {
+    fn from(e: nom::error::Error<&[u8]>) -> ISOError {
+        ISOError::Nom(e.code)
     }
 }
 
 #[cfg(feature = "verbose-error")]
-impl From<nom::Err<nom::error::VerboseError<&[u8]>>> for ISOError {
-    fn from(err: nom::Err<nom::error::VerboseError<&[u8]>>) -> ISOError {
-        ISOError::VerboseNom(match err {
-            nom::Err::Error(e) | nom::Err::Failure(e) => nom::error::VerboseError {
-                errors: e
-                    .errors
-                    .into_iter()
-                    .map(|(i, e)| (i.to_owned(), e))
-                    .collect(),
-            },
-            nom::Err::Incomplete(_) => panic!(), // XXX
+impl From<nom::error::VerboseError<&[u8]>> for ISOError {
+    fn from(e: nom::error::VerboseError<&[u8]>) -> ISOError {
+        ISOError::VerboseNom(nom::error::VerboseError {
+            errors: e
+                .errors
+                .into_iter()
+                .map(|(i, e)| (i.to_owned(), e))
+                .collect(),
         })
     }
 }
 
 cfg_if::cfg_if! {
     if #[cfg(feature = "verbose-error")] {
-        pub(crate) type OurNomError<T> = nom::error::VerboseError<T>;
+        type InnerNomError<T> = nom::error::VerboseError<T>;
     } else {
-        pub(crate) type OurNomError<T> = nom::error::Error<T>;
+        type InnerNomError<T> = nom::error::Error<T>;
+    }
+}
+
+/// The error type of every parser: nom's own, along with the [`Anomaly`] that made a strict parse
+/// fail, if any.
+#[derive(Debug)]
+pub(crate) struct OurNomError<T> {
+    inner: InnerNomError<T>,
+    anomaly: Option<Anomaly>,
+}
+
+impl<T> OurNomError<T> {
+    /// A failure to parse `input` because [`ParseMode::Strict`](crate::ParseMode) rejected
+    /// `anomaly`.
+    pub fn anomaly(input: T, anomaly: Anomaly) -> Self {
+        Self {
+            inner: InnerNomError::from_error_kind(input, ErrorKind::Verify),
+            anomaly: Some(anomaly),
+        }
+    }
+}
+
+impl<T> ParseError<T> for OurNomError<T> {
+    fn from_error_kind(input: T, kind: ErrorKind) -> Self {
+        Self {
+            inner: InnerNomError::from_error_kind(input, kind),
+            anomaly: None,
+        }
+    }
+
+    fn append(input: T, kind: ErrorKind, other: Self) -> Self {
+        Self {
+            inner: InnerNomError::append(input, kind, other.inner),
+            anomaly: other.anomaly,
+        }
+    }
+}
+
+impl<T> ContextError<T> for OurNomError<T> {
+    fn add_context(input: T, ctx: &'static str, other: Self) -> Self {
+        Self {
+            inner: InnerNomError::add_context(input, ctx, other.inner),
+            anomaly: other.anomaly,
+        }
+    }
+}
+
+impl<T, E> FromExternalError<T, E> for OurNomError<T> {
+    fn from_external_error(input: T, kind: ErrorKind, e: E) -> Self {
+        Self {
+            inner: InnerNomError::from_external_error(input, kind, e),
+            anomaly: None,
+        }
     }
 }
 
@@ -1,17 +1,31 @@
 // SPDX-License-Identifier: (MIT OR Apache-2.0)
 
 use std::{
-    cell::RefCell,
-    io::{Read, Result, Seek, SeekFrom},
-    rc::Rc,
+    cmp::min,
+    io::{self, Read, Result, Seek, SeekFrom},
+    sync::{Arc, Mutex, MutexGuard},
 };
 
-use crate::BLOCK_SIZE;
+use crate::{
+    options::Warnings, parse::susp::SystemUseLayout, Anomaly, BlockBuffer, BlockBufferCtor,
+    Decoding, OpenOptions, ParseMode, SuspExtension, Tolerance, BLOCK_SIZE,
+};
 
 /// A trait for objects which can be read by logical block addresses.
+///
+/// Addresses passed to `read_at` are always in units of [`BLOCK_SIZE`] byte logical sectors,
+/// regardless of the logical block size of the volume being read.
 pub trait ISO9660Reader {
     /// Read the block(s) at a given LBA (logical block address)
     fn read_at(&mut self, buf: &mut [u8], lba: u64) -> Result<usize>;
+
+    /// Read the user data of the single sector at a given LBA as recorded, which is 2324 bytes
+    /// rather than 2048 for a Mode 2 Form 2 sector.  Returns `None` if only the 2048 bytes of each
+    /// sector are available, as with a typical `.iso` file.
+    fn read_sector_at(&mut self, buf: &mut [u8], lba: u64) -> Result<Option<usize>> {
+        let _ = (buf, lba);
+        Ok(None)
+    }
 }
 
 impl<T: Read + Seek> ISO9660Reader for T {
@@ -21,22 +35,200 @@ impl<T: Read + Seek> ISO9660Reader for T {
     }
 }
 
-// TODO: Figure out if sane API possible without Rc/RefCell
-pub(crate) struct FileRef<T: ISO9660Reader>(Rc<RefCell<T>>);
+/// A shared handle to the underlying reader.
+///
+/// The reader is kept behind an [`Arc`]/[`Mutex`] pair so that an [`ISO9660`](crate::ISO9660) and
+/// everything handed out by it are [`Send`] + [`Sync`] whenever the reader is [`Send`].  Each
+/// `read_at` call holds the lock only for the duration of a single seek + read, so concurrent
+/// readers interleave at block granularity.
+///
+/// Each volume may use its own logical block size, so the block size travels with the handle
+/// rather than the reader.  So do the system use layout and extensions announced at the root of
+/// the directory hierarchy, which apply to entries anywhere in it.  The [`ParseMode`] the image was
+/// opened with is shared by every handle, as are the anomalies it has tolerated.
+pub(crate) struct FileRef<T: ISO9660Reader> {
+    reader: Arc<Mutex<T>>,
+    mode: ParseMode,
+    warnings: Arc<Warnings>,
+    decoding: Decoding,
+    block_size: u16,
+    system_use: SystemUseLayout,
+    extensions: Arc<[SuspExtension]>,
+
+    /// Whether identifiers are recorded as in an enhanced volume descriptor's hierarchy, with no
+    /// version numbers or separators.
+    enhanced: bool,
+}
 
 impl<T: ISO9660Reader> Clone for FileRef<T> {
     fn clone(&self) -> FileRef<T> {
-        FileRef(self.0.clone())
+        FileRef {
+            reader: self.reader.clone(),
+            mode: self.mode,
+            warnings: self.warnings.clone(),
+            decoding: self.decoding,
+            block_size: self.block_size,
+            system_use: self.system_use,
+            extensions: self.extensions.clone(),
+            enhanced: self.enhanced,
+        }
     }
 }
 
 impl<T: ISO9660Reader> FileRef<T> {
-    pub fn new(reader: T) -> FileRef<T> {
-        FileRef(Rc::new(RefCell::new(reader)))
+    /// Tolerated anomalies are added to `warnings`, which may already hold those found before the
+    /// handle was created.
+    pub fn new(reader: T, options: &OpenOptions, warnings: Arc<Warnings>) -> FileRef<T> {
+        FileRef {
+            reader: Arc::new(Mutex::new(reader)),
+            mode: options.mode(),
+            warnings,
+            decoding: options.decoding(),
+            block_size: BLOCK_SIZE,
+            system_use: SystemUseLayout::Unknown,
+            extensions: Arc::new([]),
+            enhanced: false,
+        }
+    }
+
+    /// Returns a handle to the same reader that addresses blocks of `block_size` bytes, for a
+    /// directory hierarchy whose root hasn't been read yet.
+    pub fn with_block_size(&self, block_size: u16) -> FileRef<T> {
+        FileRef {
+            reader: self.reader.clone(),
+            mode: self.mode,
+            warnings: self.warnings.clone(),
+            decoding: self.decoding,
+            block_size,
+            system_use: SystemUseLayout::Unknown,
+            extensions: Arc::new([]),
+            enhanced: false,
+        }
+    }
+
+    /// Returns a handle to the same volume for a directory hierarchy whose root announces
+    /// `system_use` and registers `extensions`.
+    pub fn with_susp(
+        &self,
+        system_use: SystemUseLayout,
+        extensions: Vec<SuspExtension>,
+    ) -> FileRef<T> {
+        FileRef {
+            reader: self.reader.clone(),
+            mode: self.mode,
+            warnings: self.warnings.clone(),
+            decoding: self.decoding,
+            block_size: self.block_size,
+            system_use,
+            extensions: extensions.into(),
+            enhanced: self.enhanced,
+        }
+    }
+
+    /// Returns a handle to the same volume for an enhanced volume descriptor's hierarchy.
+    pub fn with_enhanced_identifiers(&self) -> FileRef<T> {
+        FileRef {
+            enhanced: true,
+            ..self.clone()
+        }
+    }
+
+    /// Whether identifiers are to be taken verbatim, as in an enhanced volume descriptor's
+    /// hierarchy.
+    pub fn is_enhanced(&self) -> bool {
+        self.enhanced
+    }
+
+    /// How anomalies found while parsing the image are handled.
+    pub fn mode(&self) -> ParseMode {
+        self.mode
+    }
+
+    /// Handles anomalies according to the [`ParseMode`], recording those tolerated.
+    pub fn tolerance(&self) -> Tolerance<'_> {
+        Tolerance::new(self.mode, Some(&self.warnings))
+    }
+
+    /// The anomalies tolerated so far.
+    pub fn warnings(&self) -> Vec<Anomaly> {
+        self.warnings
+            .lock()
+            .map(|warnings| warnings.clone())
+            .unwrap_or_default()
+    }
+
+    /// How identifiers are decoded.
+    pub fn decoding(&self) -> Decoding {
+        self.decoding
+    }
+
+    /// The logical block size used to address this volume.
+    pub fn block_size(&self) -> u16 {
+        self.block_size
+    }
+
+    /// Where the system use fields of the directory hierarchy hold their entries.
+    ///
+    /// # See Also
+    ///
+    /// System Use Sharing Protocol § 5.3
+    pub fn system_use(&self) -> SystemUseLayout {
+        self.system_use
+    }
+
+    /// The system use extensions registered at the root of the directory hierarchy, in order.
+    ///
+    /// # See Also
+    ///
+    /// System Use Sharing Protocol § 5.5
+    pub fn extensions(&self) -> &[SuspExtension] {
+        &self.extensions
+    }
+
+    fn lock(&self) -> Result<MutexGuard<'_, T>> {
+        self.reader
+            .lock()
+            .map_err(|_| io::Error::other("ISO9660Reader lock poisoned"))
+    }
+
+    /// Read the user data of a single sector as recorded, if the reader has it.  Addressed in
+    /// logical sectors regardless of the block size.
+    pub fn read_sector_at(&self, buf: &mut [u8], lba: u64) -> Result<Option<usize>> {
+        self.lock()?.read_sector_at(buf, lba)
     }
 
     /// Read the block(s) at a given LBA (logical block address)
     pub fn read_at(&self, buf: &mut [u8], lba: u64) -> Result<usize> {
-        (*self.0).borrow_mut().read_at(buf, lba)
+        let mut reader = self.lock()?;
+
+        if self.block_size == BLOCK_SIZE {
+            return reader.read_at(buf, lba);
+        }
+
+        // Logical blocks smaller than a logical sector: read whole sectors and copy out the
+        // requested range.
+        let sector_size = u64::from(BLOCK_SIZE);
+        let mut pos = lba * u64::from(self.block_size);
+        let mut sector = BlockBuffer::new();
+        let mut count = 0;
+
+        while count < buf.len() {
+            let start = (pos % sector_size) as usize;
+            let read = reader.read_at(&mut sector, pos / sector_size)?;
+            if read <= start {
+                break;
+            }
+
+            let len = min(read - start, buf.len() - count);
+            buf[count..count + len].copy_from_slice(&sector[start..start + len]);
+            count += len;
+            pos += len as u64;
+
+            if read < sector.len() {
+                break;
+            }
+        }
+
+        Ok(count)
     }
 }
@@ -68,33 +68,81 @@
 /// [`Result`](std::result::Result) that returns an [`ISOError`].
 pub type Result<T> = std::result::Result<T, ISOError>;
 
+#[allow(unused)]
+use log::{debug, error, info, trace, warn};
+
+use std::sync::{Arc, OnceLock};
+
+mod cue;
 mod directory_entry;
 mod error;
 mod fileref;
+mod options;
 mod parse;
+mod raw_sector;
+mod session;
+mod tree;
+mod write;
 
+use directory_entry::Extent;
 use fileref::FileRef;
-use parse::volume_descriptor::VolumeDescriptor;
+use options::{Decoding, Tolerance, Warnings};
+use parse::{el_torito::EL_TORITO_IDENTIFIER, susp::SystemUseLayout};
+use tree::Hierarchy;
+
+/// Re-exported for [`OpenOptions::code_page()`], so that callers needn't depend on a matching
+/// version of `encoding_rs`.
+pub use encoding_rs;
 
+pub use cue::{CueSheet, Track, TrackIndex, TrackMode};
 pub use directory_entry::{
-    DirectoryEntry, ExtraAttributes, ExtraMeta, ISODirectory, ISODirectoryIterator, ISOFile,
-    ISOFileReader, PosixAttributes, PosixFileMode, PosixTimestamp, SuspExtension, Symlink,
+    Acl, AclEntry, AclPermissions, AclTag, AmigaInfo, AmigaProtection, AppleInfo, DeviceNumber,
+    DirectoryEntry, ExtraAttributes, ExtraMeta, FinderFlags, ISODirectory, ISODirectoryIterator,
+    ISOFile, ISOFileReader, PosixAttributes, PosixFileMode, PosixTimestamp, SparseFile, Special,
+    SpecialType, SuspExtension, Symlink, SystemUseOrigin, XaAttributes, XaRecord, ZisofsFile,
 };
 pub use error::ISOError;
 pub use fileref::ISO9660Reader;
+pub use options::{Anomaly, OpenOptions, ParseMode, UnpairedSurrogates};
+pub use parse::el_torito::{
+    BootCatalog, BootEntry, BootMediaType, BootPlatform, BootSection, ValidationEntry,
+};
+pub use parse::path_table::{PathTable, PathTableRecord, PathTableType};
+pub use parse::volume_descriptor::{PartitionDescriptor, VolumeDescriptor, VolumeDescriptorTable};
+pub use raw_sector::{RawSectorReader, SectorFormat, SectorMode};
+pub use session::Session;
+pub use tree::Tree;
+pub use write::IsoBuilder;
 
 /// Struct representing an ISO 9660 / ECMA-119 filesystem.
+///
+/// If the underlying reader is [`Send`], then the filesystem and every [`DirectoryEntry`],
+/// [`ISOFileReader`], etc. derived from it are [`Send`] + [`Sync`].  This allows a single image to
+/// be shared (e.g. via [`Arc`](std::sync::Arc)) across threads, with each thread reading different
+/// files concurrently.
 pub struct ISO9660<T: ISO9660Reader> {
-    _file: FileRef<T>,
+    file: FileRef<T>,
     root: ISODirectory<T>,
-    sup_root: Option<ISODirectory<T>>,
-    primary: VolumeDescriptor,
+    hierarchies: Vec<Hierarchy<T>>,
+    selected: Tree,
+    descriptors: Vec<VolumeDescriptor>,
+    primary: VolumeDescriptorTable,
+    supplementary: Vec<VolumeDescriptorTable>,
+    block_size: u16,
+    boot_catalog_lba: Option<u32>,
+    session_start: u32,
+    path_table_lookup: bool,
+    path_table: OnceLock<Option<PathTable>>,
 }
 
-/// The size of a filesystem block, currently hardcoded to 2048 although the ISO spec allows for other sizes.
+/// The size of a logical sector.  This is also the largest logical block size permitted by the
+/// standard; the actual block size of a volume is reported by [`ISO9660::block_size()`].
+///
+/// # See Also
+/// ISO-9660 / ECMA-119 §§ 6.1.2, 6.2.1
 pub const BLOCK_SIZE: u16 = 2048;
 
-/// A `u8` array big enough to hold an entire filesystem block.
+/// A `u8` array big enough to hold an entire logical sector, and thus any filesystem block.
 pub type BlockBuffer = [u8; BLOCK_SIZE as usize];
 
 /// A quick hack to allow for a constructor even though blocks are defined as a primitive type.
@@ -114,11 +162,7 @@ macro_rules! primary_prop_str {
     ($(#[$attr:meta])* $name:ident) => {
         $(#[$attr])*
         pub fn $name(&self) -> &str {
-            if let VolumeDescriptor::Primary(table) = &self.primary {
-                &table.$name
-            } else {
-                unreachable!()
-            }
+            &self.primary.$name
         }
     };
 }
@@ -144,7 +188,33 @@ impl<T: ISO9660Reader> ISO9660<T> {
     /// let iso = ISO9660::new(file)?;
     /// # Ok::<(), cdfs::ISOError>(())
     /// ```
-    pub fn new(mut reader: T) -> Result<ISO9660<T>> {
+    pub fn new(reader: T) -> Result<ISO9660<T>> {
+        Self::new_at_session(reader, 0)
+    }
+
+    /// Returns a new [`ISO9660`] instance for the session of a multisession image beginning at
+    /// logical sector `session_start`.  The volume descriptors are read from 16 sectors past the
+    /// start of the session, while the addresses they contain remain relative to the start of the
+    /// image.  [`ISO9660::sessions()`] can be used to find where each session begins.
+    ///
+    /// # Errors
+    ///
+    /// Upon encountering an error parsing the filesystem image or an I/O error, an error variant
+    /// will be returned.
+    ///
+    /// # See Also
+    /// ISO-9660 / ECMA-119 § 6.2.1
+    pub fn new_at_session(reader: T, session_start: u32) -> Result<ISO9660<T>> {
+        Self::open_with(reader, session_start, &OpenOptions::default())
+    }
+
+    pub(crate) fn open_with(
+        mut reader: T,
+        session_start: u32,
+        options: &OpenOptions,
+    ) -> Result<ISO9660<T>> {
+        let warnings = Arc::new(Warnings::default());
+        let mode = Tolerance::new(options.mode(), Some(&warnings));
         let blksize = usize::from(BLOCK_SIZE);
 
         let mut buf = BlockBuffer::new();
@@ -152,10 +222,12 @@ impl<T: ISO9660Reader> ISO9660<T> {
         let mut root = None;
         let mut primary = None;
 
-        let mut sup_root = None;
+        let mut supplementary = Vec::new();
+        let mut boot_catalog_lba = None;
+        let mut descriptors = Vec::new();
 
         // Skip the "system area"
-        let mut lba = 16;
+        let mut lba = u64::from(session_start) + 16;
 
         // Read volume descriptors
         loop {
@@ -165,34 +237,32 @@ impl<T: ISO9660Reader> ISO9660<T> {
                 return Err(ISOError::ReadSize(count));
             }
 
-            let descriptor = VolumeDescriptor::parse(&buf)?;
-            match &descriptor {
+            let descriptor = VolumeDescriptor::parse(&buf, options.decoding(), mode)?;
+            descriptors.extend(descriptor.clone());
+
+            match descriptor {
                 Some(VolumeDescriptor::Primary(table)) => {
-                    if usize::from(table.logical_block_size) != blksize {
-                        // This is almost always the case, but technically
-                        // not guaranteed by the standard.
-                        // TODO: Implement this
-                        return Err(ISOError::InvalidFs("Block size not 2048"));
-                    }
+                    check_block_size(table.logical_block_size)?;
 
                     root = Some((
                         table.root_directory_entry.clone(),
                         table.root_directory_entry_identifier.clone(),
+                        table.logical_block_size,
                     ));
-                    primary = descriptor;
+                    primary = Some(table);
                 }
                 Some(VolumeDescriptor::Supplementary(table)) => {
-                    if usize::from(table.logical_block_size) != blksize {
-                        // This is almost always the case, but technically
-                        // not guaranteed by the standard.
-                        // TODO: Implement this
-                        return Err(ISOError::InvalidFs("Block size not 2048"));
-                    }
-
-                    sup_root = Some((
-                        table.root_directory_entry.clone(),
-                        table.root_directory_entry_identifier.clone(),
-                    ));
+                    check_block_size(table.logical_block_size)?;
+                    supplementary.push(table);
+                }
+                Some(VolumeDescriptor::BootRecord {
+                    boot_system_identifier,
+                    data,
+                    ..
+                }) if boot_system_identifier.starts_with(EL_TORITO_IDENTIFIER) => {
+                    boot_catalog_lba = data
+                        .get(0..4)
+                        .map(|lba| u32::from_le_bytes([lba[0], lba[1], lba[2], lba[3]]));
                 }
                 Some(VolumeDescriptor::VolumeDescriptorSetTerminator) => break,
                 _ => {}
@@ -201,9 +271,7 @@ impl<T: ISO9660Reader> ISO9660<T> {
             lba += 1;
         }
 
-        let file = FileRef::new(reader);
-        let file2 = file.clone();
-        let file3 = file.clone();
+        let file = FileRef::new(reader, options, warnings.clone());
 
         let (root, primary) = match (root, primary) {
             (Some(root), Some(primary)) => (root, primary),
@@ -212,18 +280,154 @@ impl<T: ISO9660Reader> ISO9660<T> {
             }
         };
 
+        let root = ISODirectory::new(
+            root.0,
+            ExtraMeta::default(),
+            root.1,
+            file.with_block_size(root.2),
+        )
+        .register_susp();
+
+        let mut hierarchies = Vec::new();
+        if is_rock_ridge(&root) {
+            let plain = file
+                .with_block_size(root.block_size())
+                .with_susp(SystemUseLayout::Absent, Vec::new());
+            hierarchies.push(Hierarchy {
+                tree: Tree::Primary,
+                root: ISODirectory::new(
+                    root.header.clone(),
+                    ExtraMeta::default(),
+                    root.identifier.clone(),
+                    plain,
+                ),
+                index: 0,
+            });
+            hierarchies.push(Hierarchy {
+                tree: Tree::RockRidge,
+                root: root.clone(),
+                index: 0,
+            });
+        } else {
+            hierarchies.push(Hierarchy {
+                tree: Tree::Primary,
+                root: root.clone(),
+                index: 0,
+            });
+        }
+
+        for (index, table) in (1..).zip(&supplementary) {
+            let tree = Tree::supplementary(index, table);
+            let mut sup_file = file.with_block_size(table.logical_block_size);
+            if tree == Tree::Enhanced {
+                sup_file = sup_file.with_enhanced_identifiers();
+            }
+            hierarchies.push(Hierarchy {
+                tree,
+                root: ISODirectory::new(
+                    table.root_directory_entry.clone(),
+                    ExtraMeta::default(),
+                    table.root_directory_entry_identifier.clone(),
+                    sup_file,
+                )
+                .register_susp(),
+                index,
+            });
+        }
+
         Ok(ISO9660 {
-            _file: file,
-            root: ISODirectory::new(root.0, ExtraMeta::default(), root.1, file2),
-            sup_root: sup_root.map(|sup_root| {
-                ISODirectory::new(sup_root.0, ExtraMeta::default(), sup_root.1, file3)
-            }),
+            block_size: root.block_size(),
+            root,
+            selected: tree::preferred(&hierarchies),
+            hierarchies,
+            descriptors,
             primary,
+            supplementary,
+            boot_catalog_lba,
+            session_start,
+            path_table_lookup: false,
+            path_table: OnceLock::new(),
+            file,
         })
     }
 
+    /// Returns a new [`ISO9660`] instance for the last session of a multisession image, which
+    /// describes the most recent state of the filesystem.  This matches what most operating
+    /// systems mount by default.
+    ///
+    /// # Errors
+    ///
+    /// Upon encountering an error parsing the filesystem image or an I/O error, an error variant
+    /// will be returned.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # std::env::set_current_dir(concat!(env!("CARGO_MANIFEST_DIR"), "/.."));
+    /// # use std::fs::File;
+    /// # use cdfs::ISO9660;
+    /// let file = File::open("images/test.iso")?;
+    /// let iso = ISO9660::new_last_session(file)?;
+    /// # Ok::<(), cdfs::ISOError>(())
+    /// ```
+    pub fn new_last_session(mut reader: T) -> Result<ISO9660<T>> {
+        let sessions = Self::sessions(&mut reader)?;
+        let start = sessions.last().map_or(0, |session| session.start);
+        Self::new_at_session(reader, start)
+    }
+
+    /// Scans an image for sessions, in the order they were recorded.  The first session always
+    /// starts at sector 0, and each subsequent one is looked for after the end of the volume
+    /// described by the session preceding it.
+    ///
+    /// # Errors
+    ///
+    /// Upon encountering an I/O error, or if the first session has no primary volume descriptor,
+    /// an error variant will be returned.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # std::env::set_current_dir(concat!(env!("CARGO_MANIFEST_DIR"), "/.."));
+    /// # use std::fs::File;
+    /// # use cdfs::ISO9660;
+    /// let mut file = File::open("images/test.iso")?;
+    /// for session in ISO9660::sessions(&mut file)? {
+    ///     println!("Session at sector {}", session.start);
+    /// }
+    /// # Ok::<(), cdfs::ISOError>(())
+    /// ```
+    pub fn sessions(reader: &mut T) -> Result<Vec<Session>> {
+        session::find_sessions(reader)
+    }
+
+    /// Returns how anomalies found while parsing the image are handled.
+    ///
+    /// # See Also
+    ///
+    /// [`OpenOptions::parse_mode()`]
+    pub fn parse_mode(&self) -> ParseMode {
+        self.file.mode()
+    }
+
+    /// Returns the anomalies tolerated so far in [`ParseMode::Lenient`], in the order they were
+    /// found.  Those in the volume descriptors are found on opening the image, while those in
+    /// directories and files are only found once they're read, so listing a directory may add more.
+    pub fn warnings(&self) -> Vec<Anomaly> {
+        self.file.warnings()
+    }
+
+    /// Returns the logical sector at which the session this instance was opened from begins.
+    pub fn session_start(&self) -> u32 {
+        self.session_start
+    }
+
     /// Returns a [`DirectoryEntry`] for a given path.
     ///
+    /// If path table lookup has been enabled with [`ISO9660::set_path_table_lookup()`], the
+    /// directory portion of the path is resolved through the path table, and only the directory
+    /// containing the final path segment is searched.
+    ///
     /// # Arguments
     ///
     /// * `path` - Path to the object on the filesystem
@@ -245,35 +449,152 @@ impl<T: ISO9660Reader> ISO9660<T> {
     /// # Ok::<(), cdfs::ISOError>(())
     /// ```
     pub fn open(&self, path: &str) -> Result<Option<DirectoryEntry<T>>> {
-        self.root().find_recursive(path)
+        let root = self.root();
+
+        let (parent, name) = match path.trim_end_matches('/').rsplit_once('/') {
+            Some((parent, name)) => (parent, name),
+            None => ("", path),
+        };
+
+        // Path tables only carry the names recorded in the directory records themselves, so
+        // Rock Ridge names can't be looked up, and they don't list `.` or `..` either.
+        let usable = self.path_table_lookup
+            && self.selected != Tree::RockRidge
+            && !parent
+                .split('/')
+                .any(|segment| segment == "." || segment == "..");
+
+        let table = match usable.then(|| self.cached_path_table()).flatten() {
+            Some(table) => table,
+            None => return root.find_recursive(path),
+        };
+
+        match table.find(parent) {
+            Some(1) => root.find_recursive(name),
+            Some(number) => {
+                let record = &table.records()[usize::from(number) - 1];
+                root.directory_at(record.extent_loc, record.identifier.clone())?
+                    .find_recursive(name)
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// Enables or disables resolving paths passed to [`ISO9660::open()`] through the path table
+    /// rather than by walking each directory along the way.  This is considerably faster for
+    /// deep trees and directories with many entries.  It is disabled by default, and has no
+    /// effect when [`Tree::RockRidge`] is selected.
+    pub fn set_path_table_lookup(&mut self, enabled: bool) {
+        self.path_table_lookup = enabled;
+    }
+
+    /// Returns the path table of the tree returned by [`ISO9660::root()`], reading it on first
+    /// use.  A table that can't be read is logged and treated as absent.
+    fn cached_path_table(&self) -> Option<&PathTable> {
+        self.path_table
+            .get_or_init(|| {
+                let index = self.selected_hierarchy().index;
+
+                match self.path_table_at(index, PathTableType::L) {
+                    Ok(table) => table,
+                    Err(err) => {
+                        warn!("Unable to read the path table: {err}");
+                        None
+                    }
+                }
+            })
+            .as_ref()
+    }
+
+    /// Returns the system use extensions registered at the root of the primary directory hierarchy,
+    /// in the order of their `ER` entries.
+    ///
+    /// # See Also
+    ///
+    /// System Use Sharing Protocol § 5.5
+    pub fn extensions(&self) -> &[SuspExtension] {
+        self.root.file.extensions()
     }
 
     /// Returns true if Rock Ridge extensions are present
     pub fn is_rr(&self) -> bool {
-        match self.root.contents().next() {
-            Some(Ok(DirectoryEntry::Directory(dirent))) => dirent.is_rock_ridge(),
-            _ => false, // again…
+        self.trees().contains(&Tree::RockRidge)
+    }
+
+    /// Returns the directory hierarchies recorded on the volume: the primary hierarchy, with and
+    /// without Rock Ridge, followed by one for each supplementary volume descriptor.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # std::env::set_current_dir(concat!(env!("CARGO_MANIFEST_DIR"), "/.."));
+    /// # use std::fs::File;
+    /// use cdfs::{Tree, ISO9660};
+    /// # let file = File::open("images/joliet.iso")?;
+    /// let mut iso = ISO9660::new(file)?;
+    /// if iso.trees().contains(&Tree::Joliet { level: 3 }) {
+    ///     iso.select_tree(Tree::Joliet { level: 3 });
+    /// }
+    /// # Ok::<(), cdfs::ISOError>(())
+    /// ```
+    ///
+    /// # See Also
+    /// ISO-9660 / ECMA-119 §§ 8.4, 8.5
+    pub fn trees(&self) -> Vec<Tree> {
+        self.hierarchies
+            .iter()
+            .map(|hierarchy| hierarchy.tree)
+            .collect()
+    }
+
+    /// Returns the root directory of a directory hierarchy, if it's present.  If there's more than
+    /// one hierarchy of the same kind, the first is returned.
+    pub fn tree(&self, tree: Tree) -> Option<&ISODirectory<T>> {
+        self.hierarchies
+            .iter()
+            .find(|hierarchy| hierarchy.tree == tree)
+            .map(|hierarchy| &hierarchy.root)
+    }
+
+    /// Returns the directory hierarchy used by [`ISO9660::root()`] and [`ISO9660::open()`].
+    pub fn selected_tree(&self) -> Tree {
+        self.selected
+    }
+
+    /// Selects the directory hierarchy used by [`ISO9660::root()`] and [`ISO9660::open()`], and
+    /// returns its root directory.  If the hierarchy isn't present, `None` is returned and the
+    /// selection is left unchanged.
+    pub fn select_tree(&mut self, tree: Tree) -> Option<&ISODirectory<T>> {
+        if !self.trees().contains(&tree) {
+            return None;
         }
+
+        self.selected = tree;
+        self.path_table = OnceLock::new();
+        self.tree(tree)
     }
 
-    /// Returns the most featureful root directory.
+    fn selected_hierarchy(&self) -> &Hierarchy<T> {
+        self.hierarchies
+            .iter()
+            .find(|hierarchy| hierarchy.tree == self.selected)
+            .unwrap_or(&self.hierarchies[0])
+    }
+
+    /// Returns the root directory of the selected directory hierarchy.
     ///
     /// # Root selection
+    /// Unless another has been selected with [`ISO9660::select_tree()`], the most featureful
+    /// hierarchy is used:
     /// * If the primary volume descriptor has Rock Ridge SUSP entries, use it
-    /// * ElseIf a supplementary volume descriptor (e.g. Joliet) exists, use it
+    /// * ElseIf a Joliet supplementary volume descriptor exists, use the highest level
+    /// * ElseIf an enhanced volume descriptor exists, use it
     /// * Else fall back on the primary volume descriptor with short filenames
     ///
     /// # See Also
     /// ISO-9660 / ECMA-119 §§ 8.4, 8.5
     pub fn root(&self) -> &ISODirectory<T> {
-        if self.is_rr() {
-            &self.root
-        } else {
-            match self.sup_root.as_ref() {
-                Some(sup_root) => sup_root,
-                None => &self.root,
-            }
-        }
+        &self.selected_hierarchy().root
     }
 
     /// Returns the root directory entry.
@@ -283,26 +604,151 @@ impl<T: ISO9660Reader> ISO9660<T> {
     ///
     /// * `index` - An integer indicating which root entry to return
     ///   * 0 = primary
-    ///   * 1 = secondary (if not present, `None` is returned)
+    ///   * n = the nth supplementary volume descriptor (if not present, `None` is returned)
     ///
     /// # See Also
     /// ISO-9660 / ECMA-119 §§ 8.4, 8.5
     pub fn root_at(&self, index: usize) -> Option<&ISODirectory<T>> {
         match index {
             0 => Some(&self.root),
-            1 => self.sup_root.as_ref(),
-            _ => unimplemented!(),
+            index => self
+                .hierarchies
+                .iter()
+                .find(|hierarchy| hierarchy.index == index)
+                .map(|hierarchy| &hierarchy.root),
         }
     }
 
-    /// Returns [`BLOCK_SIZE`].
+    /// Returns the path table of a volume.
+    ///
+    /// # Arguments
     ///
-    /// This implementation hardcodes the block size to 2048.
+    /// * `index` - An integer indicating which volume's path table to return, as with
+    ///   [`ISO9660::root_at()`]
+    ///   * 0 = primary
+    ///   * n = the nth supplementary volume descriptor (if not present, `None` is returned)
+    /// * `table_type` - Which of the two copies of the path table to read
+    ///
+    /// # Errors
+    ///
+    /// Upon encountering an I/O error or a malformed path table, an error variant is returned.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # std::env::set_current_dir(concat!(env!("CARGO_MANIFEST_DIR"), "/.."));
+    /// # use std::fs::File;
+    /// use cdfs::{PathTableType, ISO9660};
+    /// # let file = File::open("images/test.iso")?;
+    /// # let iso = ISO9660::new(file)?;
+    /// if let Some(table) = iso.path_table_at(0, PathTableType::L)? {
+    ///     for number in 1..=table.records().len() as u16 {
+    ///         println!("{:?}", table.path(number));
+    ///     }
+    /// }
+    /// # Ok::<(), cdfs::ISOError>(())
+    /// ```
+    ///
+    /// # See Also
+    /// ISO-9660 / ECMA-119 §§ 6.9, 9.4
+    pub fn path_table_at(
+        &self,
+        index: usize,
+        table_type: PathTableType,
+    ) -> Result<Option<PathTable>> {
+        let table = match index {
+            0 => &self.primary,
+            index => match self.supplementary.get(index - 1) {
+                Some(table) => table,
+                None => return Ok(None),
+            },
+        };
+
+        read_path_table(&self.file, table, table_type).map(Some)
+    }
+
+    /// Returns the El Torito boot catalog, if the image has a boot record pointing to one.
+    ///
+    /// # Errors
+    ///
+    /// Upon encountering an I/O error or a malformed boot catalog, an error variant is returned.
+    ///
+    /// # See Also
+    /// El Torito Bootable CD-ROM Format Specification § 2
+    pub fn boot_catalog(&self) -> Result<Option<BootCatalog>> {
+        self.boot_catalog_lba
+            .map(|lba| BootCatalog::read(&self.file, lba))
+            .transpose()
+    }
+
+    /// Returns an [`ISOFileReader`] for the boot image described by a [`BootEntry`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # std::env::set_current_dir(concat!(env!("CARGO_MANIFEST_DIR"), "/.."));
+    /// # use std::{fs::File, io::Read};
+    /// # use cdfs::ISO9660;
+    /// # let file = File::open("images/test.iso")?;
+    /// # let iso = ISO9660::new(file)?;
+    /// if let Some(catalog) = iso.boot_catalog()? {
+    ///     for entry in catalog.entries() {
+    ///         let mut image = Vec::new();
+    ///         iso.boot_image(entry).read_to_end(&mut image)?;
+    ///     }
+    /// }
+    /// # Ok::<(), cdfs::ISOError>(())
+    /// ```
+    pub fn boot_image(&self, entry: &BootEntry) -> ISOFileReader<T> {
+        let extent = Extent {
+            lba: entry.load_rba,
+            length: u32::try_from(entry.image_size()).unwrap_or(u32::MAX),
+        };
+
+        ISOFileReader::new(self.file.clone(), vec![extent])
+    }
+
+    /// Returns every volume descriptor in the volume descriptor set, in the order they're
+    /// recorded, ending with the terminator.  Descriptors of a type not defined by the standard are
+    /// left out.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # std::env::set_current_dir(concat!(env!("CARGO_MANIFEST_DIR"), "/.."));
+    /// # use std::fs::File;
+    /// use cdfs::{VolumeDescriptor, ISO9660};
+    /// # let file = File::open("images/test.iso")?;
+    /// # let iso = ISO9660::new(file)?;
+    /// for descriptor in iso.volume_descriptors() {
+    ///     if let VolumeDescriptor::Supplementary(table) = descriptor {
+    ///         println!("{}: {:?}", table.volume_identifier, table.escape_sequences);
+    ///     }
+    /// }
+    /// # Ok::<(), cdfs::ISOError>(())
+    /// ```
+    ///
+    /// # See Also
+    /// ISO-9660 / ECMA-119 § 8
+    pub fn volume_descriptors(&self) -> &[VolumeDescriptor] {
+        &self.descriptors
+    }
+
+    /// Returns the primary volume descriptor.
+    ///
+    /// # See Also
+    /// ISO-9660 / ECMA-119 § 8.4
+    pub fn primary_descriptor(&self) -> &VolumeDescriptorTable {
+        &self.primary
+    }
+
+    /// Returns the logical block size of the primary volume.  This is 2048 bytes for nearly every
+    /// image, but 512 and 1024 byte blocks are permitted as well.
     ///
     /// # See Also
     /// ISO-9660 / ECMA-119 § 6.1.2
     pub fn block_size(&self) -> u16 {
-        BLOCK_SIZE
+        self.block_size
     }
 
     primary_prop_str! {
@@ -347,3 +793,49 @@ impl<T: ISO9660Reader> ISO9660<T> {
         bibliographic_file_identifier
     }
 }
+
+fn read_path_table<T: ISO9660Reader>(
+    file: &FileRef<T>,
+    table: &VolumeDescriptorTable,
+    table_type: PathTableType,
+) -> Result<PathTable> {
+    let lba = match table_type {
+        PathTableType::L => table.path_table_loc,
+        PathTableType::M => table.path_table_loc_be,
+    };
+
+    // The size comes straight from the descriptor, so is checked before anything is allocated.
+    let volume_size = u64::from(table.volume_space_size) * u64::from(table.logical_block_size);
+    if u64::from(table.path_table_size) > volume_size {
+        return Err(ISOError::InvalidFs("Path table is larger than the volume"));
+    }
+
+    PathTable::read(
+        &file.with_block_size(table.logical_block_size),
+        lba,
+        table.path_table_size,
+        table_type,
+        table.character_encoding,
+    )
+}
+
+/// Rock Ridge is in use if the `.` entry of the root directory says so.
+fn is_rock_ridge<T: ISO9660Reader>(root: &ISODirectory<T>) -> bool {
+    match root.contents().next() {
+        Some(Ok(DirectoryEntry::Directory(dirent))) => dirent.is_rock_ridge(),
+        _ => false, // again…
+    }
+}
+
+/// Logical blocks must be a power of two no smaller than 512 bytes, and no larger than a logical
+/// sector.
+///
+/// # See Also
+/// ISO-9660 / ECMA-119 § 6.1.2
+fn check_block_size(block_size: u16) -> Result<()> {
+    if block_size.is_power_of_two() && (512..=BLOCK_SIZE).contains(&block_size) {
+        Ok(())
+    } else {
+        Err(ISOError::InvalidFs("Invalid logical block size"))
+    }
+}
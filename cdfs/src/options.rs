@@ -0,0 +1,304 @@
+// SPDX-License-Identifier: (MIT OR Apache-2.0)
+
+#[allow(unused)]
+use log::{debug, error, info, trace, warn};
+
+use std::{fmt, sync::Mutex};
+
+use encoding_rs::Encoding;
+
+use crate::{
+    error::{NomRes, OurNomError},
+    ISO9660Reader, ISOError, Result, ISO9660,
+};
+
+/// What to do upon finding something in an image that doesn't follow the standard, but that could
+/// still be made sense of, e.g. an unexpected system use entry version or a Joliet name that isn't
+/// valid UCS-2.  Structures that can't be parsed at all are errors either way.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum ParseMode {
+    /// Reject the image by returning [`ISOError::Anomaly`].
+    Strict,
+
+    /// Log a warning, record it for [`ISO9660::warnings()`], and carry on with whatever could be
+    /// made of the data.
+    #[default]
+    Lenient,
+}
+
+/// Something found in an image that doesn't follow the standard.  [`ParseMode::Strict`] rejects
+/// it with [`ISOError::Anomaly`], while [`ParseMode::Lenient`] lists it in
+/// [`ISO9660::warnings()`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Anomaly {
+    /// A Joliet identifier isn't valid UCS-2, e.g. it holds an unpaired surrogate.
+    InvalidUcs2,
+
+    /// An identifier isn't valid in the code page set by [`OpenOptions::code_page()`].
+    InvalidCodePage,
+
+    /// A Rock Ridge name isn't valid in the code page set by [`OpenOptions::code_page()`].
+    InvalidRockRidgeName,
+
+    /// A Rock Ridge symbolic link target isn't valid in the code page set by
+    /// [`OpenOptions::code_page()`].
+    InvalidSymlinkTarget,
+
+    /// A file has an interleave gap size but no file unit size.  The gap is ignored.
+    InterleaveGap,
+
+    /// A system use entry has a version other than the one its specification defines.
+    SuspVersion,
+
+    /// A system use entry has a length other than those its specification defines.
+    SuspLength,
+
+    /// Continuation areas lead back to one already read.
+    ContinuationLoop,
+
+    /// A symbolic link component is flagged as the root, current or parent directory, but also
+    /// has content.
+    SymlinkComponent,
+
+    /// A Rock Ridge child link is on an entry that isn't a regular file.
+    ChildLink,
+
+    /// The record following an associated file can't be read, so the associated file is listed
+    /// on its own.
+    UnpairedAssociatedFile,
+
+    /// AAIP attributes from `AL` entries don't form name and value pairs, or hold a malformed
+    /// ACL.  The attributes are left empty.
+    MalformedAttributes,
+}
+
+impl fmt::Display for Anomaly {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            Self::InvalidUcs2 => "Identifier isn't valid UCS-2",
+            Self::InvalidCodePage => "Identifier isn't valid in the code page",
+            Self::InvalidRockRidgeName => "Rock Ridge name isn't valid in the code page",
+            Self::InvalidSymlinkTarget => "Symbolic link target isn't valid in the code page",
+            Self::InterleaveGap => "Interleave gap without a file unit size",
+            Self::SuspVersion => "Unexpected system use entry version",
+            Self::SuspLength => "Unexpected system use entry length",
+            Self::ContinuationLoop => "Continuation areas form a loop",
+            Self::SymlinkComponent => "Symbolic link component has both a flag and content",
+            Self::ChildLink => "Child link on an entry that isn't a regular file",
+            Self::UnpairedAssociatedFile => "Associated file followed by an unreadable record",
+            Self::MalformedAttributes => "Malformed AAIP attributes",
+        })
+    }
+}
+
+/// The anomalies tolerated while reading an image, shared by every handle to it.
+pub(crate) type Warnings = Mutex<Vec<Anomaly>>;
+
+/// A [`ParseMode`], with somewhere to record the anomalies it tolerates.
+#[derive(Copy, Clone)]
+pub(crate) struct Tolerance<'w> {
+    mode: ParseMode,
+    warnings: Option<&'w Warnings>,
+}
+
+impl<'w> Tolerance<'w> {
+    /// Records tolerated anomalies in `warnings`, if given, as well as logging them.
+    pub fn new(mode: ParseMode, warnings: Option<&'w Warnings>) -> Self {
+        Self { mode, warnings }
+    }
+
+    /// Rejects or tolerates `anomaly`.
+    pub fn tolerate(self, anomaly: Anomaly) -> Result<()> {
+        match self.mode {
+            ParseMode::Strict => Err(ISOError::Anomaly(anomaly)),
+            ParseMode::Lenient => {
+                warn!("{anomaly}");
+                if let Some(warnings) = self.warnings {
+                    if let Ok(mut warnings) = warnings.lock() {
+                        warnings.push(anomaly);
+                    }
+                }
+                Ok(())
+            }
+        }
+    }
+
+    /// The same as [`Tolerance::tolerate()`], for use within a parser.  A rejection is a
+    /// [`nom::Err::Failure`] so that no alternatives are tried instead.
+    pub fn tolerate_nom<I: Clone>(self, input: I, anomaly: Anomaly) -> NomRes<I, ()> {
+        match self.tolerate(anomaly) {
+            Ok(()) => Ok((input, ())),
+            Err(_) => Err(nom::Err::Failure(OurNomError::anomaly(input, anomaly))),
+        }
+    }
+}
+
+/// What to make of an unpaired surrogate in a Joliet name, which UTF-16 can't decode.  Windows
+/// burning tools are known to record them, as Windows filenames needn't be valid UTF-16.
+///
+/// Names holding one are still an anomaly, which [`ParseMode::Strict`] rejects.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum UnpairedSurrogates {
+    /// Map each unpaired surrogate `0xD800..=0xDFFF` onto the private use code point
+    /// `U+10F800..=U+10FFFF`, so that no information is lost.  See
+    /// [`UnpairedSurrogates::unescape()`].
+    #[default]
+    Escape,
+
+    /// Replace each unpaired surrogate with U+FFFD.
+    Replace,
+}
+
+impl UnpairedSurrogates {
+    const ESCAPE_BASE: u32 = 0x10_F800 - 0xD800;
+
+    /// Returns the character standing in for an unpaired surrogate.
+    pub(crate) fn substitute(self, surrogate: u16) -> char {
+        match self {
+            Self::Escape => char::from_u32(Self::ESCAPE_BASE + u32::from(surrogate))
+                .unwrap_or(char::REPLACEMENT_CHARACTER),
+            Self::Replace => char::REPLACEMENT_CHARACTER,
+        }
+    }
+
+    /// Returns the UTF-16 code units of a name decoded with [`UnpairedSurrogates::Escape`], with
+    /// the escaped surrogates restored.  Names that genuinely hold characters in
+    /// `U+10F800..=U+10FFFF` can't be told apart from escaped ones.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cdfs::UnpairedSurrogates;
+    ///
+    /// assert_eq!(
+    ///     UnpairedSurrogates::unescape("a\u{10F800}"),
+    ///     [u16::from(b'a'), 0xD800]
+    /// );
+    /// ```
+    pub fn unescape(name: &str) -> Vec<u16> {
+        let mut units = Vec::with_capacity(name.len());
+        for c in name.chars() {
+            match u32::from(c).checked_sub(Self::ESCAPE_BASE) {
+                Some(surrogate @ 0xD800..=0xDFFF) => units.push(surrogate as u16),
+                _ => units.extend(c.encode_utf16(&mut [0; 2]).iter()),
+            }
+        }
+        units
+    }
+}
+
+/// How identifiers are decoded, as chosen with [`OpenOptions`].
+#[derive(Copy, Clone, Debug)]
+pub(crate) struct Decoding {
+    /// The code page of identifiers recorded in the ISO 9660 character set, and of Rock Ridge
+    /// names.
+    pub code_page: &'static Encoding,
+    pub surrogates: UnpairedSurrogates,
+}
+
+impl Default for Decoding {
+    fn default() -> Self {
+        Self {
+            code_page: encoding_rs::UTF_8,
+            surrogates: UnpairedSurrogates::default(),
+        }
+    }
+}
+
+/// Options for opening an [`ISO9660`] filesystem, for when the defaults used by
+/// [`ISO9660::new()`] aren't wanted.
+///
+/// # Example
+///
+/// ```rust
+/// # std::env::set_current_dir(concat!(env!("CARGO_MANIFEST_DIR"), "/.."));
+/// # use std::fs::File;
+/// use cdfs::{OpenOptions, ParseMode};
+///
+/// let file = File::open("images/test.iso")?;
+/// let iso = OpenOptions::new().parse_mode(ParseMode::Strict).open(file)?;
+/// # Ok::<(), cdfs::ISOError>(())
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct OpenOptions {
+    parse_mode: ParseMode,
+    decoding: Decoding,
+}
+
+impl OpenOptions {
+    /// Returns the default options.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets how anomalies in the image are handled.  [`ParseMode::Lenient`] by default.
+    pub fn parse_mode(&mut self, mode: ParseMode) -> &mut Self {
+        self.parse_mode = mode;
+        self
+    }
+
+    /// Sets how unpaired surrogates in Joliet names are decoded.  [`UnpairedSurrogates::Escape`]
+    /// by default.
+    pub fn unpaired_surrogates(&mut self, surrogates: UnpairedSurrogates) -> &mut Self {
+        self.decoding.surrogates = surrogates;
+        self
+    }
+
+    /// Sets the code page of names in the primary volume descriptor's hierarchy, and of Rock
+    /// Ridge names.  ISO 9660 only permits a subset of ASCII, but discs are often recorded with
+    /// names in a local code page, e.g. Shift-JIS or Latin-1.  UTF-8 by default.
+    ///
+    /// The same code page is used for the identifiers of the primary volume descriptor, and of
+    /// any supplementary volume descriptor that isn't Joliet.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # std::env::set_current_dir(concat!(env!("CARGO_MANIFEST_DIR"), "/.."));
+    /// # use std::fs::File;
+    /// use cdfs::OpenOptions;
+    ///
+    /// let file = File::open("images/test.iso")?;
+    /// let iso = OpenOptions::new()
+    ///     .code_page(cdfs::encoding_rs::SHIFT_JIS)
+    ///     .open(file)?;
+    /// # Ok::<(), cdfs::ISOError>(())
+    /// ```
+    pub fn code_page(&mut self, code_page: &'static Encoding) -> &mut Self {
+        self.decoding.code_page = code_page;
+        self
+    }
+
+    /// Opens the first session of an image, as with [`ISO9660::new()`].
+    ///
+    /// # Errors
+    ///
+    /// Upon encountering an error parsing the filesystem image or an I/O error, an error variant
+    /// will be returned.
+    pub fn open<T: ISO9660Reader>(&self, reader: T) -> Result<ISO9660<T>> {
+        self.open_at_session(reader, 0)
+    }
+
+    /// Opens the session beginning at logical sector `session_start`, as with
+    /// [`ISO9660::new_at_session()`].
+    ///
+    /// # Errors
+    ///
+    /// Upon encountering an error parsing the filesystem image or an I/O error, an error variant
+    /// will be returned.
+    pub fn open_at_session<T: ISO9660Reader>(
+        &self,
+        reader: T,
+        session_start: u32,
+    ) -> Result<ISO9660<T>> {
+        ISO9660::open_with(reader, session_start, self)
+    }
+
+    pub(crate) fn mode(&self) -> ParseMode {
+        self.parse_mode
+    }
+
+    pub(crate) fn decoding(&self) -> Decoding {
+        self.decoding
+    }
+}
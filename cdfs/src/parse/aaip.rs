@@ -0,0 +1,212 @@
+// SPDX-License-Identifier: (MIT OR Apache-2.0)
+
+//! Arbitrary Attribute Interchange Protocol (AAIP), the extended attributes and ACLs `libisofs`
+//! records in `AL` entries.
+
+#[allow(unused)]
+use log::{debug, error, info, trace, warn};
+
+use std::collections::BTreeMap;
+
+use bitflags::bitflags;
+use nom::{
+    combinator::{all_consuming, map},
+    error::ParseError,
+    multi::{length_data, many0},
+    number::complete::le_u8,
+    sequence::pair,
+};
+
+use crate::error::{NomRes, OurNomError};
+
+/// The name the ACL is recorded under.
+const ACL_NAME: &[u8] = b"";
+
+/// A POSIX.1e access control list, as recorded by `libisofs`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Acl {
+    /// Entries governing access to the file itself.
+    pub access: Vec<AclEntry>,
+
+    /// Entries new files in this directory inherit.  Empty for files.
+    pub default: Vec<AclEntry>,
+}
+
+/// A single entry of an [`Acl`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct AclEntry {
+    /// Who the entry applies to.
+    pub tag: AclTag,
+
+    /// What they may do.
+    pub permissions: AclPermissions,
+}
+
+/// Who an [`AclEntry`] applies to.
+#[derive(Clone, Debug, PartialEq)]
+pub enum AclTag {
+    /// The file's owner.  Equivalent to `ACL_USER_OBJ`.
+    UserObj,
+
+    /// A user, by ID.  Equivalent to `ACL_USER`.
+    User(u32),
+
+    /// A user, by name.
+    UserName(String),
+
+    /// The file's group.  Equivalent to `ACL_GROUP_OBJ`.
+    GroupObj,
+
+    /// A group, by ID.  Equivalent to `ACL_GROUP`.
+    Group(u32),
+
+    /// A group, by name.
+    GroupName(String),
+
+    /// The most permissions any user or group entry grants.  Equivalent to `ACL_MASK`.
+    Mask,
+
+    /// Everyone else.  Equivalent to `ACL_OTHER`.
+    Other,
+}
+
+bitflags! {
+    /// The permissions granted by an [`AclEntry`].
+    #[derive(Clone, Copy, Debug, PartialEq)]
+    pub struct AclPermissions: u8 {
+        /// Equivalent to `ACL_READ`.
+        const READ = 1 << 2;
+
+        /// Equivalent to `ACL_WRITE`.
+        const WRITE = 1 << 1;
+
+        /// Equivalent to `ACL_EXECUTE`.
+        const EXECUTE = 1 << 0;
+    }
+}
+
+fn error(input: &[u8]) -> nom::Err<OurNomError<&[u8]>> {
+    nom::Err::Error(OurNomError::from_error_kind(
+        input,
+        nom::error::ErrorKind::Fail,
+    ))
+}
+
+/// A name or value: one or more components, each but the last with its CONTINUE flag set.
+fn field(mut input: &[u8]) -> NomRes<&[u8], Vec<u8>> {
+    const CONTINUE: u8 = 1 << 0;
+
+    let mut field = Vec::new();
+    loop {
+        let (rest, flags) = le_u8(input)?;
+        let (rest, data) = length_data(le_u8)(rest)?;
+        field.extend(data);
+        input = rest;
+
+        if flags & CONTINUE == 0 {
+            return Ok((input, field));
+        }
+    }
+}
+
+/// Names start with a byte abbreviating their namespace.
+fn attribute_name(name: &[u8]) -> String {
+    let (namespace, rest) = match name.split_first() {
+        Some((1, rest)) => ("", rest), // escapes a name starting with a reserved byte
+        Some((2, rest)) => ("system.", rest),
+        Some((3, rest)) => ("user.", rest),
+        Some((4, rest)) => ("isofs.", rest),
+        Some((5, rest)) => ("trusted.", rest),
+        Some((6, rest)) => ("security.", rest),
+        _ => ("", name),
+    };
+
+    format!("{namespace}{}", String::from_utf8_lossy(rest))
+}
+
+/// Each entry starts with a byte holding the tag type in the high nibble, a flag for a following
+/// qualifier in bit 3, and the permissions in the low three bits.  A qualifier is a length byte
+/// followed by a name, or by a big-endian ID.  A switch mark separates the access and default
+/// entries.
+fn acl(mut input: &[u8]) -> NomRes<&[u8], Acl> {
+    const HAS_QUALIFIER: u8 = 1 << 3;
+
+    let mut acl = Acl::default();
+    let mut default = false;
+
+    while !input.is_empty() {
+        let (rest, byte) = le_u8(input)?;
+        let (rest, qualifier) = match byte & HAS_QUALIFIER {
+            0 => (rest, None),
+            _ => map(length_data(le_u8), Some)(rest)?,
+        };
+
+        let id = |qualifier: Option<&[u8]>| match qualifier {
+            Some(bytes) if bytes.len() <= 4 => {
+                Ok(bytes.iter().fold(0, |id, &byte| id << 8 | u32::from(byte)))
+            }
+            _ => Err(error(input)),
+        };
+        let name = |qualifier: Option<&[u8]>| match qualifier {
+            Some(bytes) => Ok(String::from_utf8_lossy(bytes).into_owned()),
+            None => Err(error(input)),
+        };
+
+        let tag = match byte >> 4 {
+            1 => AclTag::UserObj,
+            2 => AclTag::UserName(name(qualifier)?),
+            3 => AclTag::GroupObj,
+            4 => AclTag::GroupName(name(qualifier)?),
+            5 => AclTag::Mask,
+            6 => AclTag::Other,
+            8 => {
+                default = true;
+                input = rest;
+                continue;
+            }
+            10 => AclTag::User(id(qualifier)?),
+            12 => AclTag::Group(id(qualifier)?),
+            _ => return Err(error(input)),
+        };
+
+        let entry = AclEntry {
+            tag,
+            permissions: AclPermissions::from_bits_truncate(byte),
+        };
+        match default {
+            false => acl.access.push(entry),
+            true => acl.default.push(entry),
+        }
+        input = rest;
+    }
+
+    Ok((input, acl))
+}
+
+/// Extended attributes keyed by their full names, and the ACL.
+pub(crate) type Attributes = (BTreeMap<String, Vec<u8>>, Option<Acl>);
+
+/// Decodes the component fields of a directory record's `AL` entries into extended attributes,
+/// keyed by their full names, and the ACL.
+///
+/// # See Also
+///
+/// `libisofs`' `doc/susp_aaip_2_0.txt`
+pub(crate) fn attributes(input: &[u8]) -> NomRes<&[u8], Attributes> {
+    let (input, pairs) = all_consuming(many0(pair(field, field)))(input)?;
+
+    let mut attributes = BTreeMap::new();
+    let mut access_control = None;
+    for (name, value) in pairs {
+        if name == ACL_NAME {
+            access_control = match acl(&value) {
+                Ok((_, acl)) => Some(acl),
+                Err(_) => return Err(error(input)),
+            };
+        } else {
+            attributes.insert(attribute_name(&name), value);
+        }
+    }
+
+    Ok((input, (attributes, access_control)))
+}
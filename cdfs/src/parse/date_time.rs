@@ -4,7 +4,7 @@ use nom::branch::alt;
 use nom::bytes::complete::{tag, take};
 use nom::combinator::{map_res, value};
 use nom::multi::count;
-use nom::number::complete::le_u8;
+use nom::number::complete::{le_i8, le_u8};
 use nom::sequence::tuple;
 use std::convert::TryFrom;
 use std::str;
@@ -14,7 +14,7 @@ use crate::error::NomRes;
 
 pub fn date_time(i: &[u8]) -> NomRes<&[u8], OffsetDateTime> {
     let (i, (year, month, day, hour, minute, second, gmt_offset)) =
-        tuple((le_u8, le_u8, le_u8, le_u8, le_u8, le_u8, le_u8))(i)?;
+        tuple((le_u8, le_u8, le_u8, le_u8, le_u8, le_u8, le_i8))(i)?;
 
     // Create Date and Time from parsed values. Since those values can be 0,
     // creating Date and Time struct can fail, in this case assume default
@@ -29,7 +29,7 @@ pub fn date_time(i: &[u8]) -> NomRes<&[u8], OffsetDateTime> {
     let time =
         Time::from_hms(hour, minute, second).unwrap_or_else(|_| Time::from_hms(0, 0, 0).unwrap());
 
-    // gmt_offset represents 15 minutes intervals from GMT.
+    // gmt_offset represents 15 minutes intervals from GMT, west of it negative.
     let offset =
         UtcOffset::from_whole_seconds((gmt_offset as i32) * 15 * 60).unwrap_or(UtcOffset::UTC);
 
@@ -55,11 +55,12 @@ pub fn date_time_ascii(i: &[u8]) -> NomRes<&[u8], OffsetDateTime> {
             ascii_i32(2),
             ascii_i32(2),
             ascii_i32(2),
-            le_u8,
+            le_i8,
         ))(i)?;
 
+    // Unlike in directory records, the year is recorded in full.
     let date = Date::from_calendar_date(
-        1900 + tm_year,
+        tm_year,
         time::Month::try_from(tm_mon as u8).unwrap_or(time::Month::January),
         tm_mday as u8,
     )
@@ -6,7 +6,8 @@ use bitflags::bitflags;
 use nom::{
     branch::alt,
     bytes::complete::{tag, take},
-    combinator::{map_parser, opt, value},
+    combinator::{map_parser, value},
+    error::{ErrorKind, ParseError},
     multi::length_data,
     number::complete::le_u8,
 };
@@ -15,12 +16,15 @@ use time::OffsetDateTime;
 use super::{
     both_endian::{both_endian16, both_endian32},
     date_time::date_time,
-    decode_string,
-    susp::SystemUseEntry,
+    decode_identifier,
+    susp::{system_use_field, SystemUseEntry, SystemUseLayout},
     CharacterEncoding, Result,
 };
 
-use crate::error::NomRes;
+use crate::{
+    error::{NomRes, OurNomError},
+    Anomaly, Decoding, Tolerance,
+};
 
 bitflags! {
     #[derive(Clone, Debug)]
@@ -47,21 +51,33 @@ pub struct DirectoryEntryHeader {
     pub interleave_gap_size: u8,
     pub volume_sequence_number: u16,
     pub character_encoding: CharacterEncoding,
+
+    /// The file identifier as recorded, before decoding.
+    pub raw_identifier: Vec<u8>,
 }
 
+/// A parsed directory record: the fixed header, the decoded identifier, and any system use entries.
+pub(crate) type DirectoryEntryParts = (DirectoryEntryHeader, String, Option<Vec<SystemUseEntry>>);
+
 impl DirectoryEntryHeader {
     pub(crate) fn parse(
         input: &[u8],
         character_encoding: CharacterEncoding,
-    ) -> Result<(DirectoryEntryHeader, String, Option<Vec<SystemUseEntry>>)> {
-        Ok(directory_entry(input, character_encoding)?.1)
+        system_use: SystemUseLayout,
+        decoding: Decoding,
+        mode: Tolerance,
+    ) -> Result<DirectoryEntryParts> {
+        Ok(directory_entry(input, character_encoding, system_use, decoding, mode)?.1)
     }
 }
 
 pub(crate) fn directory_entry<'a>(
     i: &'a [u8],
     character_encoding: CharacterEncoding,
-) -> NomRes<&[u8], (DirectoryEntryHeader, String, Option<Vec<SystemUseEntry>>)> {
+    system_use: SystemUseLayout,
+    decoding: Decoding,
+    mode: Tolerance,
+) -> NomRes<&'a [u8], DirectoryEntryParts> {
     let orig_len = i.len();
     let (i, length) = le_u8(i)?;
     let (i, extended_attribute_record_length) = le_u8(i)?;
@@ -74,27 +90,22 @@ pub(crate) fn directory_entry<'a>(
     let (i, interleave_gap_size) = le_u8(i)?;
     let (i, volume_sequence_number) = both_endian16(i)?;
 
-    #[cfg(feature = "assertions")]
-    {
-        assert_eq!(interleave_gap_size, 0);
-        assert_eq!(file_unit_size, 0);
+    // Without file units there's nothing to interleave, so the gap is ignored.
+    if file_unit_size == 0 && interleave_gap_size != 0 {
+        mode.tolerate_nom(i, Anomaly::InterleaveGap)?;
     }
 
     let identifier_len = i.len();
-    let (i, identifier) = map_parser(
-        length_data(le_u8),
-        |bytes: &'a [u8]| -> NomRes<&'a [u8], String> {
-            if bytes.len() == 1 {
-                alt((
-                    value(String::from("\u{0}"), tag(&[0])),
-                    value(String::from("\u{1}"), tag(&[1])),
-                    decode_string(character_encoding),
-                ))(bytes)
-            } else {
-                decode_string(character_encoding)(bytes)
-            }
-        },
-    )(i)?;
+    let (i, raw_identifier) = length_data(le_u8)(i)?;
+    let (_, identifier) = if raw_identifier.len() == 1 {
+        alt((
+            value(String::from("\u{0}"), tag(&[0])),
+            value(String::from("\u{1}"), tag(&[1])),
+            decode_identifier(character_encoding, decoding, mode),
+        ))(raw_identifier)?
+    } else {
+        decode_identifier(character_encoding, decoding, mode)(raw_identifier)?
+    };
     let identifier_len = identifier_len - i.len();
 
     // Padding
@@ -105,8 +116,10 @@ pub(crate) fn directory_entry<'a>(
     };
 
     let offset = orig_len - i.len();
-    let remainder = usize::from(length) - offset;
-    let (i, susp) = map_parser(take(remainder), opt(crate::parse::susp::system_use_entries))(i)?;
+    let remainder = usize::from(length)
+        .checked_sub(offset)
+        .ok_or_else(|| nom::Err::Error(OurNomError::from_error_kind(i, ErrorKind::LengthValue)))?;
+    let (i, susp) = map_parser(take(remainder), system_use_field(system_use, mode))(i)?;
 
     Ok((
         i,
@@ -122,6 +135,7 @@ pub(crate) fn directory_entry<'a>(
                 interleave_gap_size,
                 volume_sequence_number,
                 character_encoding,
+                raw_identifier: raw_identifier.to_vec(),
             },
             identifier,
             susp,
@@ -0,0 +1,446 @@
+// SPDX-License-Identifier: (MIT OR Apache-2.0)
+
+use nom::{
+    bytes::complete::{tag, take},
+    combinator::map,
+    number::complete::{le_u16, le_u32, le_u8},
+};
+
+use super::{decode_string, CharacterEncoding};
+use crate::{
+    error::NomRes, BlockBuffer, BlockBufferCtor, Decoding, FileRef, ISO9660Reader, ISOError,
+    Result, Tolerance, BLOCK_SIZE,
+};
+
+/// The boot system identifier used by El Torito boot records.
+pub(crate) const EL_TORITO_IDENTIFIER: &str = "EL TORITO SPECIFICATION";
+
+/// The size of a single boot catalog entry.
+const ENTRY_SIZE: usize = 32;
+
+/// The size of the virtual sectors used by [`BootEntry::sector_count`].
+const VIRTUAL_SECTOR_SIZE: u64 = 512;
+
+/// The platform a boot image (or section of boot images) is intended for.
+///
+/// ## See Also
+///
+/// El Torito Bootable CD-ROM Format Specification § 2.1
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum BootPlatform {
+    /// 80x86 / BIOS
+    X86,
+
+    /// PowerPC
+    PowerPC,
+
+    /// Classic Mac OS
+    Mac,
+
+    /// UEFI, as registered by the UEFI specification.
+    Efi,
+
+    /// A platform ID not defined by the specification.
+    Unknown(u8),
+}
+
+impl From<u8> for BootPlatform {
+    fn from(id: u8) -> Self {
+        match id {
+            0x00 => Self::X86,
+            0x01 => Self::PowerPC,
+            0x02 => Self::Mac,
+            0xEF => Self::Efi,
+            id => Self::Unknown(id),
+        }
+    }
+}
+
+/// The type of media a boot image emulates.
+///
+/// ## See Also
+///
+/// El Torito Bootable CD-ROM Format Specification § 2.2
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum BootMediaType {
+    /// The image is loaded as-is without any emulation.
+    NoEmulation,
+
+    /// 1.2 MB floppy diskette emulation.
+    Floppy1_2M,
+
+    /// 1.44 MB floppy diskette emulation.
+    Floppy1_44M,
+
+    /// 2.88 MB floppy diskette emulation.
+    Floppy2_88M,
+
+    /// Hard disk emulation.
+    HardDisk,
+
+    /// A media type not defined by the specification.
+    Unknown(u8),
+}
+
+impl From<u8> for BootMediaType {
+    fn from(media: u8) -> Self {
+        match media & 0x0F {
+            0 => Self::NoEmulation,
+            1 => Self::Floppy1_2M,
+            2 => Self::Floppy1_44M,
+            3 => Self::Floppy2_88M,
+            4 => Self::HardDisk,
+            media => Self::Unknown(media),
+        }
+    }
+}
+
+/// The validation entry found at the start of every boot catalog.
+///
+/// ## See Also
+///
+/// El Torito Bootable CD-ROM Format Specification § 2.1
+#[derive(Clone, Debug, PartialEq)]
+pub struct ValidationEntry {
+    /// The platform of the default entry.
+    pub platform: BootPlatform,
+
+    /// Identifies the manufacturer or developer of the CD-ROM.
+    pub identifier: String,
+}
+
+/// An initial / default or section entry describing a single boot image.
+///
+/// ## See Also
+///
+/// El Torito Bootable CD-ROM Format Specification §§ 2.2, 2.4
+#[derive(Clone, Debug, PartialEq)]
+pub struct BootEntry {
+    /// The platform this image is intended for.
+    pub platform: BootPlatform,
+
+    /// True if the image is marked as bootable.
+    pub bootable: bool,
+
+    /// The kind of media the BIOS should emulate.
+    pub media_type: BootMediaType,
+
+    /// The segment the image is loaded into.  Zero means the traditional `0x7C0`.
+    pub load_segment: u16,
+
+    /// A copy of the partition table system type byte.  Only meaningful for hard disk emulation.
+    pub system_type: u8,
+
+    /// The number of 512 byte virtual sectors loaded at boot time.
+    pub sector_count: u16,
+
+    /// The start of the image, in 2048 byte logical sectors.
+    pub load_rba: u32,
+
+    /// The selection criteria type.  Always zero for the initial / default entry.
+    pub selection_criteria_type: u8,
+
+    /// Vendor unique selection criteria, including any data from extension entries.
+    pub selection_criteria: Vec<u8>,
+}
+
+impl BootEntry {
+    /// Returns the size in bytes of the boot image.
+    ///
+    /// For floppy emulation this is the size of the emulated diskette, otherwise it is the number
+    /// of bytes loaded by the BIOS as specified by [`sector_count`](Self::sector_count).
+    pub fn image_size(&self) -> u64 {
+        match self.media_type {
+            BootMediaType::Floppy1_2M => 1_228_800,
+            BootMediaType::Floppy1_44M => 1_474_560,
+            BootMediaType::Floppy2_88M => 2_949_120,
+            _ => u64::from(self.sector_count) * VIRTUAL_SECTOR_SIZE,
+        }
+    }
+}
+
+/// A section of boot entries sharing a platform.
+///
+/// ## See Also
+///
+/// El Torito Bootable CD-ROM Format Specification § 2.3
+#[derive(Clone, Debug, PartialEq)]
+pub struct BootSection {
+    /// The platform the entries in this section are intended for.
+    pub platform: BootPlatform,
+
+    /// Identifies the section to e.g. a boot menu.
+    pub identifier: String,
+
+    /// The boot entries in the section.
+    pub entries: Vec<BootEntry>,
+}
+
+/// A parsed El Torito boot catalog.
+///
+/// ## See Also
+///
+/// El Torito Bootable CD-ROM Format Specification § 2
+#[derive(Clone, Debug, PartialEq)]
+pub struct BootCatalog {
+    /// The validation entry.
+    pub validation: ValidationEntry,
+
+    /// The initial / default entry.
+    pub default_entry: BootEntry,
+
+    /// Any additional sections.
+    pub sections: Vec<BootSection>,
+}
+
+impl BootCatalog {
+    /// Reads and parses the boot catalog starting at the logical sector `lba`.
+    pub(crate) fn read<T: ISO9660Reader>(file: &FileRef<T>, lba: u32) -> Result<Self> {
+        let mode = file.tolerance();
+        let mut entries = CatalogReader {
+            file,
+            lba: u64::from(lba),
+            block: BlockBuffer::new(),
+            pos: usize::from(BLOCK_SIZE),
+        };
+
+        let validation = entries.next_entry()?;
+        if !validation_checksum(validation) {
+            return Err(ISOError::InvalidFs("Invalid El Torito validation entry"));
+        }
+        let validation = match CatalogEntry::parse_validation(validation, mode)?.1 {
+            CatalogEntry::Validation(validation) => validation,
+            _ => return Err(ISOError::InvalidFs("Invalid El Torito validation entry")),
+        };
+
+        let mut default_entry =
+            match CatalogEntry::parse(entries.next_entry()?, validation.platform, mode)?.1 {
+                CatalogEntry::Boot(entry, _) => entry,
+                _ => return Err(ISOError::InvalidFs("Missing El Torito default entry")),
+            };
+        default_entry.selection_criteria_type = 0;
+        default_entry.selection_criteria.clear();
+
+        let mut sections = Vec::new();
+        let mut last = false;
+        while !last {
+            let entry = entries.next_entry()?;
+            let (platform, count, identifier) =
+                match CatalogEntry::parse(entry, validation.platform, mode)?.1 {
+                    CatalogEntry::SectionHeader {
+                        last: is_last,
+                        platform,
+                        count,
+                        identifier,
+                    } => {
+                        last = is_last;
+                        (platform, count, identifier)
+                    }
+                    // No (more) section headers.
+                    _ => break,
+                };
+
+            let mut section = BootSection {
+                platform,
+                identifier,
+                entries: Vec::with_capacity(usize::from(count)),
+            };
+
+            for _ in 0..count {
+                let (mut entry, mut more) =
+                    match CatalogEntry::parse(entries.next_entry()?, platform, mode)?.1 {
+                        CatalogEntry::Boot(entry, more) => (entry, more),
+                        _ => return Err(ISOError::InvalidFs("Invalid El Torito section entry")),
+                    };
+
+                while more {
+                    match CatalogEntry::parse(entries.next_entry()?, platform, mode)?.1 {
+                        CatalogEntry::Extension {
+                            more: has_more,
+                            data,
+                        } => {
+                            entry.selection_criteria.extend(data);
+                            more = has_more;
+                        }
+                        _ => return Err(ISOError::InvalidFs("Invalid El Torito extension entry")),
+                    }
+                }
+
+                section.entries.push(entry);
+            }
+
+            sections.push(section);
+        }
+
+        Ok(Self {
+            validation,
+            default_entry,
+            sections,
+        })
+    }
+
+    /// Returns the default entry followed by the entries of every section, in catalog order.
+    pub fn entries(&self) -> impl Iterator<Item = &BootEntry> {
+        std::iter::once(&self.default_entry).chain(
+            self.sections
+                .iter()
+                .flat_map(|section| section.entries.iter()),
+        )
+    }
+}
+
+/// Hands out consecutive 32 byte entries, reading catalog sectors as needed.
+struct CatalogReader<'a, T: ISO9660Reader> {
+    file: &'a FileRef<T>,
+    lba: u64,
+    block: BlockBuffer,
+    pos: usize,
+}
+
+impl<T: ISO9660Reader> CatalogReader<'_, T> {
+    fn next_entry(&mut self) -> Result<&[u8]> {
+        if self.pos >= self.block.len() {
+            let count = self.file.read_at(&mut self.block, self.lba)?;
+            if count != self.block.len() {
+                return Err(ISOError::ReadSize(count));
+            }
+            self.lba += 1;
+            self.pos = 0;
+        }
+
+        let entry = &self.block[self.pos..self.pos + ENTRY_SIZE];
+        self.pos += ENTRY_SIZE;
+        Ok(entry)
+    }
+}
+
+/// A single 32 byte boot catalog entry.
+#[derive(Clone, Debug)]
+enum CatalogEntry {
+    Validation(ValidationEntry),
+    Boot(BootEntry, bool),
+    SectionHeader {
+        last: bool,
+        platform: BootPlatform,
+        count: u16,
+        identifier: String,
+    },
+    Extension {
+        more: bool,
+        data: Vec<u8>,
+    },
+}
+
+impl CatalogEntry {
+    fn parse_validation<'a>(input: &'a [u8], mode: Tolerance) -> NomRes<&'a [u8], Self> {
+        validation_entry(input, mode)
+    }
+
+    /// Parses an entry that follows the validation entry.  `platform` is the platform of the
+    /// enclosing section.
+    fn parse<'a>(
+        input: &'a [u8],
+        platform: BootPlatform,
+        mode: Tolerance,
+    ) -> NomRes<&'a [u8], Self> {
+        match input.first() {
+            Some(0x90) | Some(0x91) => section_header(input, mode),
+            Some(0x44) => extension_entry(input),
+            _ => boot_entry(input, platform),
+        }
+    }
+}
+
+/// Returns true if the words of the validation entry sum to zero.
+fn validation_checksum(entry: &[u8]) -> bool {
+    entry.chunks_exact(2).fold(0_u16, |acc, word| {
+        acc.wrapping_add(u16::from_le_bytes([word[0], word[1]]))
+    }) == 0
+}
+
+fn validation_entry<'a>(input: &'a [u8], mode: Tolerance) -> NomRes<&'a [u8], CatalogEntry> {
+    let (input, _) = tag(&[0x01])(input)?;
+    let (input, platform) = map(le_u8, BootPlatform::from)(input)?;
+    let (input, _) = take(2_usize)(input)?;
+    let (input, identifier) = take(24_usize)(input)?;
+    let (input, _checksum) = le_u16(input)?;
+    let (input, _) = tag(&[0x55, 0xAA])(input)?;
+
+    let (_, identifier) =
+        decode_string(CharacterEncoding::Iso9660, Decoding::default(), mode)(identifier)?;
+
+    Ok((
+        input,
+        CatalogEntry::Validation(ValidationEntry {
+            platform,
+            identifier: identifier.trim_end_matches('\0').to_string(),
+        }),
+    ))
+}
+
+fn boot_entry(input: &[u8], platform: BootPlatform) -> NomRes<&[u8], CatalogEntry> {
+    let (input, indicator) = le_u8(input)?;
+    let (input, media) = le_u8(input)?;
+    let (input, load_segment) = le_u16(input)?;
+    let (input, system_type) = le_u8(input)?;
+    let (input, _) = take(1_usize)(input)?;
+    let (input, sector_count) = le_u16(input)?;
+    let (input, load_rba) = le_u32(input)?;
+    let (input, selection_criteria_type) = le_u8(input)?;
+    let (input, selection_criteria) = take(19_usize)(input)?;
+
+    // Bit 5 of the media type indicates that extension entries follow.
+    let extended = media & (1 << 5) != 0;
+
+    Ok((
+        input,
+        CatalogEntry::Boot(
+            BootEntry {
+                platform,
+                bootable: indicator == 0x88,
+                media_type: BootMediaType::from(media),
+                load_segment,
+                system_type,
+                sector_count,
+                load_rba,
+                selection_criteria_type,
+                selection_criteria: selection_criteria.to_vec(),
+            },
+            extended,
+        ),
+    ))
+}
+
+fn section_header<'a>(input: &'a [u8], mode: Tolerance) -> NomRes<&'a [u8], CatalogEntry> {
+    let (input, indicator) = le_u8(input)?;
+    let (input, platform) = map(le_u8, BootPlatform::from)(input)?;
+    let (input, count) = le_u16(input)?;
+    let (input, identifier) = take(28_usize)(input)?;
+
+    let (_, identifier) =
+        decode_string(CharacterEncoding::Iso9660, Decoding::default(), mode)(identifier)?;
+
+    Ok((
+        input,
+        CatalogEntry::SectionHeader {
+            last: indicator == 0x91,
+            platform,
+            count,
+            identifier: identifier.trim_end_matches('\0').to_string(),
+        },
+    ))
+}
+
+fn extension_entry(input: &[u8]) -> NomRes<&[u8], CatalogEntry> {
+    let (input, _) = tag(&[0x44])(input)?;
+    let (input, flags) = le_u8(input)?;
+    let (input, data) = take(30_usize)(input)?;
+
+    Ok((
+        input,
+        CatalogEntry::Extension {
+            more: flags & (1 << 5) != 0,
+            data: data.to_vec(),
+        },
+    ))
+}
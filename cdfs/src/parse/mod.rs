@@ -1,23 +1,24 @@
 // SPDX-License-Identifier: (MIT OR Apache-2.0)
 
-use std::str;
+use std::borrow::Cow;
 
-use nom::{
-    branch::alt,
-    bytes::complete::{tag, take},
-    combinator::{map, map_res, rest, value},
-};
+use nom::{bytes::complete::take, combinator::rest};
 
-use crate::{error::NomRes, Result};
+use crate::{error::NomRes, Anomaly, Decoding, Result, Tolerance, UnpairedSurrogates};
 
+pub(crate) mod aaip;
 mod both_endian;
 mod date_time;
 
 pub(crate) mod directory_entry;
+pub(crate) mod el_torito;
+pub(crate) mod path_table;
 pub(crate) mod susp;
 pub(crate) mod volume_descriptor;
+pub(crate) mod xa;
 
-#[derive(Copy, Clone, Debug, PartialEq)]
+/// Ordered by UCS-2 level, so that the highest level designated can be picked.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
 pub enum CharacterEncoding {
     Iso9660,
     Ucs2Level1,
@@ -33,8 +34,8 @@ impl CharacterEncoding {
 
 pub(crate) fn character_encoding(bytes: &[u8]) -> NomRes<&[u8], CharacterEncoding> {
     // The field is 32 bytes long, and per §8.5.6 ECMA says there can be multiple
-    // encodings listed.  But. Really.  For now let's just check for the standard
-    // ISO 9660 encoding or UCS-2.
+    // encodings listed.  Only UCS-2 is understood; anything else is taken to be
+    // the standard ISO 9660 encoding.
 
     // Per ECMA 35 / ISO 2022 §13.2.2:
     // I byte 0x25 (02/05) = Designate Other Coding System
@@ -47,41 +48,95 @@ pub(crate) fn character_encoding(bytes: &[u8]) -> NomRes<&[u8], CharacterEncodin
     // #162 UCS-2 Level 1 F byte is 0x40 (04/00)
     // #175 UCS-2 Level 2 F byte is 0x43 (04/03)
     // #175 UCS-2 Level 3 F byte is 0x45 (04/05)
-    let orig_len = bytes.len();
-
-    let (bytes, encoding) = alt((
-        value(CharacterEncoding::Ucs2Level1, tag(&[0x25, 0x2F, 0x40])),
-        value(CharacterEncoding::Ucs2Level2, tag(&[0x25, 0x2F, 0x43])),
-        value(CharacterEncoding::Ucs2Level3, tag(&[0x25, 0x2F, 0x45])),
-        value(CharacterEncoding::Iso9660, tag(&[0_u8; 32])),
-    ))(bytes)?;
+    //
+    // The sequence needn't come first, nor be followed only by zeros.
+    let (bytes, field) = take(32_usize)(bytes)?;
 
-    let bytes = match orig_len - bytes.len() {
-        len if len < 32 => take(32 - len)(bytes)?.0,
-        _ => bytes,
-    };
+    let encoding = field
+        .windows(3)
+        .filter_map(|sequence| match sequence {
+            [0x25, 0x2F, 0x40] => Some(CharacterEncoding::Ucs2Level1),
+            [0x25, 0x2F, 0x43] => Some(CharacterEncoding::Ucs2Level2),
+            [0x25, 0x2F, 0x45] => Some(CharacterEncoding::Ucs2Level3),
+            _ => None,
+        })
+        .max()
+        .unwrap_or(CharacterEncoding::Iso9660);
 
     Ok((bytes, encoding))
 }
 
-pub(crate) fn decode_string(
+/// Decodes an identifier as recorded, e.g. a file identifier.  Identifiers in the ISO 9660
+/// character set are decoded with the code page of `decoding`.  Bytes that aren't valid are an
+/// anomaly, handled according to `mode`.  If tolerated, they're replaced with U+FFFD, except for
+/// unpaired surrogates, which are handled according to `decoding`.
+pub(crate) fn decode_identifier<'w>(
     encoding: CharacterEncoding,
-) -> impl Fn(&[u8]) -> NomRes<&[u8], String> {
-    move |i: &[u8]| match encoding {
-        CharacterEncoding::Ucs2Level1
-        | CharacterEncoding::Ucs2Level2
-        | CharacterEncoding::Ucs2Level3 => map(rest, |input| {
-            let (cow, _encoding_used, had_errors) = encoding_rs::UTF_16BE.decode(input);
-
-            // TODO: Real error handling
-            #[cfg(feature = "assertions")]
-            assert!(!had_errors);
-
-            cow.trim_end().to_string()
-        })(i),
-        CharacterEncoding::Iso9660 => map(
-            map(map_res(rest, str::from_utf8), str::trim_end),
-            str::to_string,
-        )(i),
+    decoding: Decoding,
+    mode: Tolerance<'w>,
+) -> impl Fn(&[u8]) -> NomRes<&[u8], String> + 'w {
+    move |i: &[u8]| {
+        let (i, bytes) = rest(i)?;
+
+        let (decoded, anomaly) = match encoding {
+            CharacterEncoding::Ucs2Level1
+            | CharacterEncoding::Ucs2Level2
+            | CharacterEncoding::Ucs2Level3 => {
+                let (decoded, had_errors) = decode_utf16(bytes, decoding.surrogates);
+                (
+                    Cow::Owned(decoded),
+                    had_errors.then_some(Anomaly::InvalidUcs2),
+                )
+            }
+            CharacterEncoding::Iso9660 => {
+                let (decoded, had_errors) = decoding.code_page.decode_without_bom_handling(bytes);
+                (decoded, had_errors.then_some(Anomaly::InvalidCodePage))
+            }
+        };
+
+        if let Some(anomaly) = anomaly {
+            mode.tolerate_nom(bytes, anomaly)?;
+        }
+
+        Ok((i, decoded.into_owned()))
     }
 }
+
+/// Decodes a fixed length field padded with trailing spaces, e.g. a volume identifier, as with
+/// [`decode_identifier()`].
+pub(crate) fn decode_string<'w>(
+    encoding: CharacterEncoding,
+    decoding: Decoding,
+    mode: Tolerance<'w>,
+) -> impl Fn(&[u8]) -> NomRes<&[u8], String> + 'w {
+    move |i: &[u8]| {
+        let (i, decoded) = decode_identifier(encoding, decoding, mode)(i)?;
+        Ok((i, decoded.trim_end().to_string()))
+    }
+}
+
+/// Decodes big endian UCS-2.  Surrogate pairs are decoded as in UTF-16, as Windows records names
+/// that don't fit in UCS-2 that way.  Returns whether there were unpaired surrogates or a trailing
+/// odd byte, which is replaced with U+FFFD.
+fn decode_utf16(bytes: &[u8], surrogates: UnpairedSurrogates) -> (String, bool) {
+    let units = bytes
+        .chunks_exact(2)
+        .map(|unit| u16::from_be_bytes([unit[0], unit[1]]));
+
+    let mut had_errors = false;
+    let mut decoded: String = char::decode_utf16(units)
+        .map(|c| {
+            c.unwrap_or_else(|err| {
+                had_errors = true;
+                surrogates.substitute(err.unpaired_surrogate())
+            })
+        })
+        .collect();
+
+    if bytes.len() % 2 == 1 {
+        had_errors = true;
+        decoded.push(char::REPLACEMENT_CHARACTER);
+    }
+
+    (decoded, had_errors)
+}
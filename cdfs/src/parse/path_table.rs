@@ -0,0 +1,225 @@
+// SPDX-License-Identifier: (MIT OR Apache-2.0)
+
+#[allow(unused)]
+use log::{debug, error, info, trace, warn};
+
+use nom::{
+    bytes::complete::take,
+    combinator::map_parser,
+    number::{
+        complete::{le_u8, u16, u32},
+        Endianness,
+    },
+};
+
+use super::{decode_identifier, CharacterEncoding};
+use crate::{error::NomRes, Decoding, FileRef, ISO9660Reader, ISOError, Result, Tolerance};
+
+/// Which of the two recorded copies of a path table to read.  Both copies are required to hold
+/// the same information; they differ only in the byte order of their numeric fields.
+///
+/// ## See Also
+///
+/// ISO-9660 / ECMA-119 §§ 8.4.14 - 8.4.17, 9.4
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum PathTableType {
+    /// The Type L path table, with little-endian fields.
+    L,
+
+    /// The Type M path table, with big-endian fields.
+    M,
+}
+
+impl PathTableType {
+    fn endianness(&self) -> Endianness {
+        match self {
+            Self::L => Endianness::Little,
+            Self::M => Endianness::Big,
+        }
+    }
+}
+
+/// A single directory listed in a [`PathTable`].
+///
+/// ## See Also
+///
+/// ISO-9660 / ECMA-119 § 9.4
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PathTableRecord {
+    /// The directory's name.  The root directory's name is empty.
+    pub identifier: String,
+
+    /// The length of the extended attribute record preceding the directory's extent.
+    pub extended_attribute_record_length: u8,
+
+    /// The logical block number of the first block of the directory's extent.
+    pub extent_loc: u32,
+
+    /// The number of the parent directory's record.  The root directory is its own parent.
+    pub parent: u16,
+}
+
+/// A path table, listing every directory of a volume in a single, compact structure.
+///
+/// Directories are numbered from 1 in the order they appear, which is breadth first, with the
+/// root directory as number 1.
+///
+/// ## See Also
+///
+/// ISO-9660 / ECMA-119 §§ 6.9, 9.4
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PathTable {
+    records: Vec<PathTableRecord>,
+}
+
+impl PathTable {
+    pub(crate) fn read<T: ISO9660Reader>(
+        file: &FileRef<T>,
+        lba: u32,
+        size: u32,
+        table_type: PathTableType,
+        character_encoding: CharacterEncoding,
+    ) -> Result<Self> {
+        let blksize = usize::from(file.block_size());
+        let size = usize::try_from(size)?;
+
+        let mut data = vec![0; size.div_ceil(blksize) * blksize];
+        for (lba, block) in (u64::from(lba)..).zip(data.chunks_mut(blksize)) {
+            let count = file.read_at(block, lba)?;
+            if count != block.len() {
+                return Err(ISOError::ReadSize(count));
+            }
+        }
+
+        Self::parse(
+            &data[..size],
+            table_type,
+            character_encoding,
+            file.decoding(),
+            file.tolerance(),
+        )
+    }
+
+    pub(crate) fn parse(
+        mut input: &[u8],
+        table_type: PathTableType,
+        character_encoding: CharacterEncoding,
+        decoding: Decoding,
+        mode: Tolerance,
+    ) -> Result<Self> {
+        let mut records = Vec::new();
+
+        // Trailing bytes too short to hold a record are padding.
+        while input.len() >= 8 && input[0] != 0 {
+            let (rest, record) = path_table_record(
+                input,
+                table_type.endianness(),
+                character_encoding,
+                decoding,
+                mode,
+            )?;
+            records.push(record);
+            input = rest;
+        }
+
+        if records.is_empty() {
+            return Err(ISOError::InvalidFs("Empty path table"));
+        }
+
+        Ok(Self { records })
+    }
+
+    /// Returns every record in the table.  The record for directory number `n` is at index
+    /// `n - 1`.
+    pub fn records(&self) -> &[PathTableRecord] {
+        &self.records
+    }
+
+    /// Returns the record for a directory number.
+    pub fn get(&self, number: u16) -> Option<&PathTableRecord> {
+        self.records.get(usize::from(number).checked_sub(1)?)
+    }
+
+    /// Returns the numbers of the directories whose parent is directory number `parent`.
+    pub fn children(&self, parent: u16) -> impl Iterator<Item = u16> + '_ {
+        (2..=u16::try_from(self.records.len()).unwrap_or(u16::MAX))
+            .zip(self.records.iter().skip(1))
+            .filter(move |(_, record)| record.parent == parent)
+            .map(|(number, _)| number)
+    }
+
+    /// Returns the absolute path of a directory number, e.g. `/A/B`.
+    pub fn path(&self, number: u16) -> Option<String> {
+        let mut segments = Vec::new();
+        let mut number = number;
+
+        // Bound the walk in case the table contains a cycle.
+        for _ in 0..self.records.len() {
+            if number == 1 {
+                segments.reverse();
+                return Some(format!("/{}", segments.join("/")));
+            }
+
+            let record = self.get(number)?;
+            segments.push(record.identifier.as_str());
+            number = record.parent;
+        }
+
+        None
+    }
+
+    /// Returns the number of the directory at `path`.  Like
+    /// [`ISODirectory::find()`](crate::ISODirectory::find), matching is ASCII case insensitive.
+    pub fn find(&self, path: &str) -> Option<u16> {
+        let mut number = 1;
+
+        for segment in path.split('/').filter(|x| !x.is_empty()) {
+            number = self.children(number).find(|&child| {
+                self.records[usize::from(child) - 1]
+                    .identifier
+                    .eq_ignore_ascii_case(segment)
+            })?;
+        }
+
+        Some(number)
+    }
+}
+
+fn path_table_record<'a>(
+    i: &'a [u8],
+    endianness: Endianness,
+    character_encoding: CharacterEncoding,
+    decoding: Decoding,
+    mode: Tolerance,
+) -> NomRes<&'a [u8], PathTableRecord> {
+    let (i, identifier_len) = le_u8(i)?;
+    let (i, extended_attribute_record_length) = le_u8(i)?;
+    let (i, extent_loc) = u32(endianness)(i)?;
+    let (i, parent) = u16(endianness)(i)?;
+    let (i, identifier) = if identifier_len == 1 && i.first() == Some(&0) {
+        // The root directory
+        (take(1usize)(i)?.0, String::new())
+    } else {
+        map_parser(
+            take(identifier_len),
+            decode_identifier(character_encoding, decoding, mode),
+        )(i)?
+    };
+
+    // Padding
+    let i = if identifier_len % 2 == 1 {
+        take(1usize)(i)?.0
+    } else {
+        i
+    };
+
+    Ok((
+        i,
+        PathTableRecord {
+            identifier,
+            extended_attribute_record_length,
+            extent_loc,
+            parent,
+        },
+    ))
+}
@@ -12,26 +12,49 @@ use nom::{
     branch::alt,
     bytes::complete::{tag, take},
     combinator::{map, map_res, opt, rest, value},
-    multi::{length_data, many1},
-    number::complete::le_u8,
+    multi::{length_data, many0, many1},
+    number::complete::{be_u16, be_u32, le_u8},
 };
 
-use super::{both_endian::both_endian32, date_time::date_time};
-use crate::error::NomRes;
+use super::{
+    both_endian::both_endian32,
+    date_time::date_time,
+    xa::{xa_record, XaRecord},
+};
+use crate::{error::NomRes, Anomaly, Tolerance};
+
+/// The raw pieces of a system use entry: signature, length, version, and data.
+type SuspParts<'a> = (&'a [u8], u8, u8, &'a [u8]);
 
 trait ParseSusp<'a> {
     const SIGNATURE: Option<&'static [u8; 2]>;
 
-    fn parse(input: &'a [u8]) -> NomRes<&'a [u8], Self>
+    /// The version the entry is expected to have, if it's fixed.
+    const VERSION: Option<u8> = Some(1);
+
+    /// The lengths the entry's data may have.  Empty if it varies.
+    const DATA_LENGTHS: &'static [usize] = &[];
+
+    /// Parses an entry.  An unexpected version or data length is an anomaly, handled according to
+    /// `mode`.  If tolerated, the data is parsed regardless.
+    fn parse(input: &'a [u8], mode: Tolerance) -> NomRes<&'a [u8], Self>
     where
         Self: Sized,
     {
         let (input, (sig, _length, version, data)) = Self::parse_sig(input)?;
+
+        if Self::VERSION.is_some_and(|expected| expected != version) {
+            mode.tolerate_nom(data, Anomaly::SuspVersion)?;
+        }
+        if !Self::DATA_LENGTHS.is_empty() && !Self::DATA_LENGTHS.contains(&data.len()) {
+            mode.tolerate_nom(data, Anomaly::SuspLength)?;
+        }
+
         let (_, entry) = Self::parse_data(data, sig.try_into().unwrap(), version)?;
         Ok((input, entry))
     }
 
-    fn parse_sig(input: &'a [u8]) -> NomRes<&'a [u8], (&'a [u8], u8, u8, &'a [u8])> {
+    fn parse_sig(input: &'a [u8]) -> NomRes<&'a [u8], SuspParts<'a>> {
         let (input, sig) = match Self::SIGNATURE {
             Some(signature) => tag(signature)(input)?,
             None => take(2_usize)(input)?,
@@ -58,16 +81,49 @@ trait ParseSusp<'a> {
 }
 
 /// System Use extensions registered as being used in a directory hierarchy
-#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
 pub enum SuspExtension {
     /// Rock Ridge v1.9 and v1.10
     RockRidge1_09,
 
     /// IEEE spec Rock Ridge extensions
     RockRidge1_12,
+
+    /// `libisofs` extended attributes and ACLs
+    Aaip,
+
+    /// An extension this crate doesn't know about.  Its entries are ignored.
+    Other {
+        /// The extension's identifier from its `ER` entry.
+        identifier: String,
+
+        /// The extension's version from its `ER` entry.
+        version: u8,
+    },
+}
+
+const ROCK_RIDGE_1_09: &[[u8; 2]] = &[
+    *b"PX", *b"PN", *b"SL", *b"NM", *b"CL", *b"PL", *b"RE", *b"TF", *b"RR",
+];
+const ROCK_RIDGE_1_12: &[[u8; 2]] = &[
+    *b"PX", *b"PN", *b"SL", *b"NM", *b"CL", *b"PL", *b"RE", *b"TF", *b"SF",
+];
+const AAIP: &[[u8; 2]] = &[*b"AL"];
+
+impl SuspExtension {
+    /// Returns the signatures of the system use entries this extension defines.
+    pub fn signatures(&self) -> &'static [[u8; 2]] {
+        match self {
+            Self::RockRidge1_09 => ROCK_RIDGE_1_09,
+            Self::RockRidge1_12 => ROCK_RIDGE_1_12,
+            Self::Aaip => AAIP,
+            Self::Other { .. } => &[],
+        }
+    }
 }
 
 /// System Use Sharing Protocol (SUSP) entries.  SUSP specifies a method of storing additional data in the [`DirectoryEntry`] structure.
+#[allow(dead_code)]
 #[derive(Clone, Debug)]
 pub(crate) enum SystemUseEntry {
     // CE
@@ -76,11 +132,17 @@ pub(crate) enum SystemUseEntry {
     // ER
     ExtensionsReference(ExtensionsReference),
 
+    // ES
+    ExtensionSelector(ExtensionSelector),
+
     // SP
     SuspIndicator(SuspIndicator),
 
     // ST
-    // SuspTerminator(SuspTerminator),
+    SuspTerminator(SuspTerminator),
+
+    // PD
+    Padding(Padding),
 
     // Rock Ridge variants
 
@@ -90,6 +152,9 @@ pub(crate) enum SystemUseEntry {
     // PX
     PosixAttributes(PosixAttributes),
 
+    // PN
+    DeviceNumber(DeviceNumber),
+
     // TF
     PosixTimestamp(PosixTimestamp),
 
@@ -105,13 +170,78 @@ pub(crate) enum SystemUseEntry {
     // RE
     RelocatedDirectory(RelocatedDirectory),
 
+    // AL
+    AttributeList(AttributeList),
+
+    // SF
+    SparseFile(SparseFile),
+
+    // ZF
+    Zisofs(ZisofsFile),
+
+    // AA
+    Apple(AppleInfo),
+
+    // BA
+    AppleLegacy(AppleInfo),
+
+    // AS
+    Amiga(AmigaEntry),
+
+    // CD-ROM XA, which isn't a SUSP entry but shares the field
+    Xa(XaRecord),
+
     // Catch-all
     Unknown(Unknown),
 }
 
+impl SystemUseEntry {
+    /// The entry's two letter signature.
+    pub fn signature(&self) -> [u8; 2] {
+        match self {
+            Self::ContinuationArea(_) => *b"CE",
+            Self::ExtensionsReference(_) => *b"ER",
+            Self::ExtensionSelector(_) => *b"ES",
+            Self::SuspIndicator(_) => *b"SP",
+            Self::SuspTerminator(_) => *b"ST",
+            Self::Padding(_) => *b"PD",
+            Self::AlternateName(_) => *b"NM",
+            Self::PosixAttributes(_) => *b"PX",
+            Self::DeviceNumber(_) => *b"PN",
+            Self::PosixTimestamp(_) => *b"TF",
+            Self::RockRidge(_) => *b"RR",
+            Self::SymbolicLink(_) => *b"SL",
+            Self::ChildLink(_) => *b"CL",
+            Self::RelocatedDirectory(_) => *b"RE",
+            Self::AttributeList(_) => *b"AL",
+            Self::SparseFile(_) => *b"SF",
+            Self::Zisofs(_) => *b"ZF",
+            Self::Apple(_) => *b"AA",
+            Self::AppleLegacy(_) => *b"BA",
+            Self::Amiga(_) => *b"AS",
+            Self::Xa(_) => *b"XA",
+            Self::Unknown(unknown) => unknown.sig,
+        }
+    }
+
+    /// True for the entries System Use Sharing Protocol itself defines, which don't belong to any
+    /// extension.
+    ///
+    /// # See Also
+    ///
+    /// System Use Sharing Protocol § 5
+    pub fn is_susp(&self) -> bool {
+        matches!(
+            &self.signature(),
+            b"CE" | b"PD" | b"SP" | b"ST" | b"ER" | b"ES"
+        )
+    }
+}
+
 #[derive(Clone, Debug, PartialEq)]
 pub(crate) struct AlternateName {
-    pub name: String,
+    /// The name as recorded, to be decoded once any continuations have been appended.
+    pub name: Vec<u8>,
     pub flags: AlternateNameFlags,
 }
 
@@ -129,6 +259,14 @@ bitflags! {
     }
 }
 
+/// An `AL` entry.  The component fields of every `AL` entry in a directory record together hold
+/// its extended attributes, decoded by [`aaip::attributes`](super::aaip::attributes).
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct AttributeList {
+    pub should_continue: bool,
+    pub components: Vec<u8>,
+}
+
 #[derive(Copy, Clone, Debug, PartialEq)]
 pub struct ChildLink(pub u32);
 
@@ -139,6 +277,32 @@ pub struct ContinuationArea {
     pub length: u32,
 }
 
+/// The extension a system use entry belongs to.
+///
+/// ## See Also
+///
+/// System Use Sharing Protocol § 5.6
+#[derive(Clone, Debug, PartialEq)]
+pub struct SystemUseOrigin {
+    /// The entry's signature, e.g. `NM`.
+    pub signature: [u8; 2],
+
+    /// The extension that defines the entry, or `None` for entries the System Use Sharing Protocol
+    /// defines itself and entries no registered extension claims.
+    pub extension: Option<SuspExtension>,
+}
+
+/// Selects the extension, by its position among the `ER` entries, that the following entries
+/// belong to.
+///
+/// ## See Also
+///
+/// System Use Sharing Protocol § 5.6
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct ExtensionSelector {
+    pub sequence: u8,
+}
+
 #[derive(Clone, Debug)]
 pub struct ExtensionsReference {
     pub extensions: Vec<SuspExtension>,
@@ -304,6 +468,46 @@ pub struct PosixAttributes {
     pub inode: Option<u32>,
 }
 
+/// The device number of a block or character device, from a `PN` entry.
+///
+/// ## See Also
+///
+/// Rock Ridge Interchange Protocol § 4.1.2
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct DeviceNumber {
+    /// The high 32 bits of the device number.
+    pub high: u32,
+
+    /// The low 32 bits of the device number.
+    pub low: u32,
+}
+
+impl DeviceNumber {
+    /// Returns the major device number.
+    ///
+    /// Some writers record the whole device number in the low 32 bits, with the major number in
+    /// bits 8 through 15, as older Linux kernels did.  Like Linux, this is assumed whenever the
+    /// high 32 bits are zero and the low ones don't fit in a byte.
+    pub fn major(&self) -> u32 {
+        match self.is_packed() {
+            true => self.low >> 8,
+            false => self.high,
+        }
+    }
+
+    /// Returns the minor device number.  See [`major()`](Self::major).
+    pub fn minor(&self) -> u32 {
+        match self.is_packed() {
+            true => self.low & 0xFF,
+            false => self.low,
+        }
+    }
+
+    fn is_packed(&self) -> bool {
+        self.high == 0 && self.low & !0xFF != 0
+    }
+}
+
 #[derive(Clone, Debug, PartialEq)]
 pub struct RelocatedDirectory(bool);
 
@@ -326,11 +530,61 @@ bitflags! {
     }
 }
 
+/// Sparse file information from an `SF` entry.
+///
+/// ## See Also
+///
+/// Rock Ridge Interchange Protocol § 4.1.7
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SparseFile {
+    /// The logical size of the file in bytes, including holes.
+    pub virtual_size: u64,
+
+    /// The number of levels of sparse tables at the start of the file's data.  Zero when the entry
+    /// predates Rock Ridge v1.12, in which case the recorded data is the start of the file and the
+    /// remainder is a hole.
+    pub table_depth: u8,
+}
+
 #[derive(Clone, Debug, PartialEq)]
 pub struct SuspIndicator {
     pub skip: u8,
 }
 
+/// Ends the system use entries of a system use field or continuation area.
+///
+/// ## See Also
+///
+/// System Use Sharing Protocol § 5.4
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct SuspTerminator;
+
+/// Padding, to be skipped.
+///
+/// ## See Also
+///
+/// System Use Sharing Protocol § 5.2
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct Padding;
+
+/// Where a directory hierarchy's system use fields hold their entries, as announced by the `SP`
+/// entry of its root's `.` entry.
+///
+/// ## See Also
+///
+/// System Use Sharing Protocol § 5.3
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum SystemUseLayout {
+    /// The root hasn't been read yet.  Entries are parsed from the start of every field.
+    Unknown,
+
+    /// The root has no `SP` entry, so system use fields hold no SUSP entries.
+    Absent,
+
+    /// Entries start `skip` bytes into each system use field.
+    Present { skip: u8 },
+}
+
 #[derive(Clone, Debug, PartialEq)]
 pub struct SymbolicLink {
     pub should_continue: bool,
@@ -352,7 +606,187 @@ bitflags! {
 #[derive(Clone, Debug, PartialEq)]
 pub struct SymbolicLinkRecord {
     pub flags: SymbolicLinkRecordFlags,
-    pub component: String,
+
+    /// The component as recorded, to be decoded once the whole target has been joined.
+    pub component: Vec<u8>,
+}
+
+/// Compression information from a `ZF` entry, as written by `mkzftree` and `xorriso -zisofs`.
+///
+/// ## See Also
+///
+/// `libisofs`' `doc/zisofs_format.txt`
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ZisofsFile {
+    /// The compression algorithm.  Only `pz` (zlib) is understood.
+    pub algorithm: [u8; 2],
+
+    /// The size of the header at the start of the recorded data, in bytes.
+    pub header_size: u16,
+
+    /// The size of each independently compressed block, as a power of two.
+    pub block_size_log2: u8,
+
+    /// The size of the file once decompressed, in bytes.
+    pub uncompressed_size: u32,
+}
+
+impl ZisofsFile {
+    /// The `algorithm` of zlib compressed files.
+    pub const ZLIB: [u8; 2] = *b"pz";
+}
+
+/// Finder information from an Apple `AA` entry, or a `BA` entry on older discs.
+///
+/// ## See Also
+///
+/// Apple ISO 9660 Extensions
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum AppleInfo {
+    /// A ProDOS file.
+    ProDos {
+        /// The ProDOS file type.
+        file_type: u8,
+
+        /// The ProDOS auxiliary type, e.g. the load address of a binary.
+        aux_type: u16,
+    },
+
+    /// A Macintosh (HFS) file.
+    Hfs {
+        /// The four character file type, e.g. `TEXT` or `APPL`.
+        file_type: [u8; 4],
+
+        /// The four character signature of the application that created the file.
+        creator: [u8; 4],
+
+        /// The Finder flags.
+        finder_flags: FinderFlags,
+    },
+}
+
+bitflags! {
+    /// The Finder flags from an Apple `AA` entry.
+    #[derive(Clone, Copy, Debug, PartialEq)]
+    pub struct FinderFlags: u16 {
+        /// The file is on the desktop.  Equivalent to `kIsOnDesk`.
+        const IS_ON_DESK = 1 << 0;
+
+        /// The label color, in three bits.  Equivalent to `kColor`.
+        const COLOR = 0b111 << 1;
+
+        /// The application may be run by several users at once.  Equivalent to `kIsShared`.
+        const IS_SHARED = 1 << 6;
+
+        /// The file contains no `INIT` resources.  Equivalent to `kHasNoINITs`.
+        const HAS_NO_INITS = 1 << 7;
+
+        /// The Finder has recorded the file's bundle information.  Equivalent to
+        /// `kHasBeenInited`.
+        const HAS_BEEN_INITED = 1 << 8;
+
+        /// The file has a custom icon in its resource fork.  Equivalent to `kHasCustomIcon`.
+        const HAS_CUSTOM_ICON = 1 << 10;
+
+        /// The file is a stationery pad.  Equivalent to `kIsStationery`.
+        const IS_STATIONERY = 1 << 11;
+
+        /// The file can't be renamed.  Equivalent to `kNameLocked`.
+        const NAME_LOCKED = 1 << 12;
+
+        /// The file has a `BNDL` resource.  Equivalent to `kHasBundle`.
+        const HAS_BUNDLE = 1 << 13;
+
+        /// The file is hidden.  Equivalent to `kIsInvisible`.
+        const IS_INVISIBLE = 1 << 14;
+
+        /// The file is an alias.  Equivalent to `kIsAlias`.
+        const IS_ALIAS = 1 << 15;
+    }
+}
+
+/// Metadata from Amiga `AS` entries, as written for CD32 and CDTV discs.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct AmigaInfo {
+    /// The protection bits, if recorded.
+    pub protection: Option<AmigaProtection>,
+
+    /// The file comment (a.k.a. filenote), if recorded.  Comments may be split across several
+    /// `AS` entries.
+    pub comment: Option<String>,
+}
+
+/// An Amiga `AS` entry.  A comment too long for one entry continues in the next.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct AmigaEntry {
+    pub protection: Option<AmigaProtection>,
+    pub comment: Option<String>,
+    pub should_continue: bool,
+}
+
+bitflags! {
+    #[derive(Clone, Copy, Debug, PartialEq)]
+    struct AmigaEntryFlags: u8 {
+        const PROTECTION = 1 << 0;
+        const COMMENT = 1 << 1;
+        const COMMENT_CONTINUE = 1 << 2;
+    }
+}
+
+bitflags! {
+    /// The protection bits from an Amiga `AS` entry, as returned by `Examine()` in
+    /// `fib_Protection`.  Note the owner's `READ`, `WRITE`, `EXECUTE`, and `DELETE` bits are set
+    /// when the action is *denied*, while the group and other bits are set when it's allowed.
+    #[derive(Clone, Copy, Debug, PartialEq)]
+    pub struct AmigaProtection: u32 {
+        /// The owner may not delete the file.  Equivalent to `FIBF_DELETE`.
+        const DELETE = 1 << 0;
+
+        /// The owner may not execute the file.  Equivalent to `FIBF_EXECUTE`.
+        const EXECUTE = 1 << 1;
+
+        /// The owner may not write the file.  Equivalent to `FIBF_WRITE`.
+        const WRITE = 1 << 2;
+
+        /// The owner may not read the file.  Equivalent to `FIBF_READ`.
+        const READ = 1 << 3;
+
+        /// The file hasn't changed since it was last archived.  Equivalent to `FIBF_ARCHIVE`.
+        const ARCHIVE = 1 << 4;
+
+        /// The program is re-entrant and may be made resident.  Equivalent to `FIBF_PURE`.
+        const PURE = 1 << 5;
+
+        /// The file is a script.  Equivalent to `FIBF_SCRIPT`.
+        const SCRIPT = 1 << 6;
+
+        /// Resident programs stay resident after they exit.  Equivalent to `FIBF_HOLD`.
+        const HOLD = 1 << 7;
+
+        /// The group may delete the file.  Equivalent to `FIBF_GRP_DELETE`.
+        const GROUP_DELETE = 1 << 8;
+
+        /// The group may execute the file.  Equivalent to `FIBF_GRP_EXECUTE`.
+        const GROUP_EXECUTE = 1 << 9;
+
+        /// The group may write the file.  Equivalent to `FIBF_GRP_WRITE`.
+        const GROUP_WRITE = 1 << 10;
+
+        /// The group may read the file.  Equivalent to `FIBF_GRP_READ`.
+        const GROUP_READ = 1 << 11;
+
+        /// Others may delete the file.  Equivalent to `FIBF_OTR_DELETE`.
+        const OTHER_DELETE = 1 << 12;
+
+        /// Others may execute the file.  Equivalent to `FIBF_OTR_EXECUTE`.
+        const OTHER_EXECUTE = 1 << 13;
+
+        /// Others may write the file.  Equivalent to `FIBF_OTR_WRITE`.
+        const OTHER_WRITE = 1 << 14;
+
+        /// Others may read the file.  Equivalent to `FIBF_OTR_READ`.
+        const OTHER_READ = 1 << 15;
+    }
 }
 
 #[derive(Clone, PartialEq)]
@@ -363,37 +797,44 @@ pub struct Unknown {
 }
 
 impl<'a> ParseSusp<'a> for AlternateName {
-    const SIGNATURE: Option<&'static [u8; 2]> = Some(&[b'N', b'M']);
-
-    fn parse_data(input: &'a [u8], _sig: &'a [u8; 2], version: u8) -> NomRes<&'a [u8], Self> {
-        #[cfg(feature = "assertions")]
-        {
-            assert_eq!(Self::SIGNATURE.unwrap(), _sig);
-            assert_eq!(version, 1);
-        }
+    const SIGNATURE: Option<&'static [u8; 2]> = Some(b"NM");
 
+    fn parse_data(input: &'a [u8], _sig: &'a [u8; 2], _version: u8) -> NomRes<&'a [u8], Self> {
         let (input, flags) = map(le_u8, AlternateNameFlags::from_bits_truncate)(input)?;
-        let (input, name) = map(
-            map(map_res(rest, std::str::from_utf8), |s| {
-                s.trim_matches('\u{0}')
-            }),
-            String::from,
-        )(input)?;
+        let (input, name) = map(rest, |name: &[u8]| {
+            let start = name.iter().position(|&b| b != 0).unwrap_or(name.len());
+            let end = name
+                .iter()
+                .rposition(|&b| b != 0)
+                .map_or(start, |end| end + 1);
+            name[start..end].to_vec()
+        })(input)?;
 
         Ok((input, Self { flags, name }))
     }
 }
 
-impl<'a> ParseSusp<'a> for ChildLink {
-    const SIGNATURE: Option<&'static [u8; 2]> = Some(&[b'C', b'L']);
+impl<'a> ParseSusp<'a> for AttributeList {
+    const SIGNATURE: Option<&'static [u8; 2]> = Some(b"AL");
 
-    fn parse_data(input: &'a [u8], _sig: &'a [u8; 2], version: u8) -> NomRes<&'a [u8], Self> {
-        #[cfg(feature = "assertions")]
-        {
-            assert_eq!(Self::SIGNATURE.unwrap(), _sig);
-            assert_eq!(version, 1);
-        }
+    fn parse_data(input: &'a [u8], _sig: &'a [u8; 2], _version: u8) -> NomRes<&'a [u8], Self> {
+        let (input, flags) = le_u8(input)?;
+        let (input, components) = rest(input)?;
 
+        Ok((
+            input,
+            Self {
+                should_continue: flags & 1 != 0,
+                components: components.to_vec(),
+            },
+        ))
+    }
+}
+
+impl<'a> ParseSusp<'a> for ChildLink {
+    const SIGNATURE: Option<&'static [u8; 2]> = Some(b"CL");
+
+    fn parse_data(input: &'a [u8], _sig: &'a [u8; 2], _version: u8) -> NomRes<&'a [u8], Self> {
         let (input, lba) = both_endian32(input)?;
 
         Ok((input, Self(lba)))
@@ -401,16 +842,10 @@ impl<'a> ParseSusp<'a> for ChildLink {
 }
 
 impl<'a> ParseSusp<'a> for ContinuationArea {
-    const SIGNATURE: Option<&'static [u8; 2]> = Some(&[b'C', b'E']);
-
-    fn parse_data(input: &'a [u8], _sig: &'a [u8; 2], version: u8) -> NomRes<&'a [u8], Self> {
-        #[cfg(feature = "assertions")]
-        {
-            assert_eq!(Self::SIGNATURE.unwrap(), _sig);
-            assert_eq!(version, 1);
-            assert_eq!(input.len(), 24);
-        }
+    const SIGNATURE: Option<&'static [u8; 2]> = Some(b"CE");
+    const DATA_LENGTHS: &'static [usize] = &[24];
 
+    fn parse_data(input: &'a [u8], _sig: &'a [u8; 2], _version: u8) -> NomRes<&'a [u8], Self> {
         let (input, block_location) = both_endian32(input)?;
         let (input, offset) = both_endian32(input)?;
         let (input, length) = both_endian32(input)?;
@@ -458,24 +893,34 @@ fn susp_extension(input: &[u8]) -> NomRes<&[u8], SuspExtension> {
         ("RRIP_1991A", 1) => SuspExtension::RockRidge1_09,
         ("IEEE_P1282", 1) => SuspExtension::RockRidge1_12,
         ("IEEE_1282", 1) => SuspExtension::RockRidge1_12,
-        _ => unimplemented!(
-            "Unknown extension {id:?}, description={description:?}, source={source:?}"
-        ),
+        ("AAIP_0200", 1) => SuspExtension::Aaip,
+        _ => {
+            debug!("Unknown extension {id:?}, description={description:?}, source={source:?}");
+            SuspExtension::Other {
+                identifier: id,
+                version,
+            }
+        }
     };
 
     Ok((input, extension))
 }
 
-impl<'a> ParseSusp<'a> for ExtensionsReference {
-    const SIGNATURE: Option<&'static [u8; 2]> = Some(&[b'E', b'R']);
+impl<'a> ParseSusp<'a> for ExtensionSelector {
+    const SIGNATURE: Option<&'static [u8; 2]> = Some(b"ES");
+    const DATA_LENGTHS: &'static [usize] = &[1];
 
-    fn parse_data(input: &'a [u8], _sig: &'a [u8; 2], version: u8) -> NomRes<&'a [u8], Self> {
-        #[cfg(feature = "assertions")]
-        {
-            assert_eq!(Self::SIGNATURE.unwrap(), _sig);
-            assert_eq!(version, 1);
-        }
+    fn parse_data(input: &'a [u8], _sig: &'a [u8; 2], _version: u8) -> NomRes<&'a [u8], Self> {
+        let (input, sequence) = le_u8(input)?;
+
+        Ok((input, Self { sequence }))
+    }
+}
 
+impl<'a> ParseSusp<'a> for ExtensionsReference {
+    const SIGNATURE: Option<&'static [u8; 2]> = Some(b"ER");
+
+    fn parse_data(input: &'a [u8], _sig: &'a [u8; 2], _version: u8) -> NomRes<&'a [u8], Self> {
         let (input, extensions) = many1(susp_extension)(input)?;
 
         Ok((input, Self { extensions }))
@@ -508,13 +953,7 @@ impl std::fmt::Display for PosixFileMode {
         } else if self.contains(Self::TYPE_PIPE) {
             write!(f, "p")?;
         } else {
-            cfg_if::cfg_if! {
-                if #[cfg(feature = "assertions")] {
-                    unimplemented!("Unknown file type: {self:?}");
-                } else {
-                    write!(f, "?")?;
-                }
-            }
+            write!(f, "?")?;
         }
 
         if self.contains(Self::OWN_READ) {
@@ -588,15 +1027,9 @@ impl std::fmt::Display for PosixFileMode {
 }
 
 impl<'a> ParseSusp<'a> for PosixTimestamp {
-    const SIGNATURE: Option<&'static [u8; 2]> = Some(&[b'T', b'F']);
-
-    fn parse_data(input: &'a [u8], _sig: &'a [u8; 2], version: u8) -> NomRes<&'a [u8], Self> {
-        #[cfg(feature = "assertions")]
-        {
-            assert_eq!(Self::SIGNATURE.unwrap(), _sig);
-            assert_eq!(version, 1);
-        }
+    const SIGNATURE: Option<&'static [u8; 2]> = Some(b"TF");
 
+    fn parse_data(input: &'a [u8], _sig: &'a [u8; 2], _version: u8) -> NomRes<&'a [u8], Self> {
         let (input, flags) = map(le_u8, PosixTimestampFlags::from_bits_truncate)(input)?;
 
         let (input, timestamps) = many1(date_time)(input)?;
@@ -654,19 +1087,13 @@ impl<'a> ParseSusp<'a> for PosixTimestamp {
 }
 
 impl<'a> ParseSusp<'a> for PosixAttributes {
-    const SIGNATURE: Option<&'static [u8; 2]> = Some(&[b'P', b'X']);
+    const SIGNATURE: Option<&'static [u8; 2]> = Some(b"PX");
 
-    fn parse_data(input: &'a [u8], _sig: &'a [u8; 2], version: u8) -> NomRes<&'a [u8], Self> {
-        #[cfg(feature = "assertions")]
-        {
-            assert_eq!(Self::SIGNATURE.unwrap(), _sig);
-            assert_eq!(version, 1);
-
-            // Between v1.09 and v1.12 the spec added a field. What's the point of even versioning this shit?
-            // Meanwhile mksiofs writes the `RR` entry (which was dropped after v1.09) with a v1.12 sized attribute field…
-            assert!(input.len() == 32 || input.len() == 40);
-        }
+    // Between v1.09 and v1.12 the spec added a field. What's the point of even versioning this shit?
+    // Meanwhile mksiofs writes the `RR` entry (which was dropped after v1.09) with a v1.12 sized attribute field…
+    const DATA_LENGTHS: &'static [usize] = &[32, 40];
 
+    fn parse_data(input: &'a [u8], _sig: &'a [u8; 2], _version: u8) -> NomRes<&'a [u8], Self> {
         let (input, mode) = map(both_endian32, PosixFileMode::from_bits_truncate)(input)?;
         let (input, links) = both_endian32(input)?;
         let (input, uid) = both_endian32(input)?;
@@ -686,32 +1113,32 @@ impl<'a> ParseSusp<'a> for PosixAttributes {
     }
 }
 
+impl<'a> ParseSusp<'a> for DeviceNumber {
+    const SIGNATURE: Option<&'static [u8; 2]> = Some(b"PN");
+    const DATA_LENGTHS: &'static [usize] = &[16];
+
+    fn parse_data(input: &'a [u8], _sig: &'a [u8; 2], _version: u8) -> NomRes<&'a [u8], Self> {
+        let (input, high) = both_endian32(input)?;
+        let (input, low) = both_endian32(input)?;
+
+        Ok((input, Self { high, low }))
+    }
+}
+
 impl<'a> ParseSusp<'a> for RelocatedDirectory {
-    const SIGNATURE: Option<&'static [u8; 2]> = Some(&[b'R', b'E']);
-
-    fn parse_data(input: &'a [u8], _sig: &'a [u8; 2], version: u8) -> NomRes<&'a [u8], Self> {
-        #[cfg(feature = "assertions")]
-        {
-            assert_eq!(Self::SIGNATURE.unwrap(), _sig);
-            assert_eq!(version, 1);
-            assert_eq!(input.len(), 0);
-        }
+    const SIGNATURE: Option<&'static [u8; 2]> = Some(b"RE");
+    const DATA_LENGTHS: &'static [usize] = &[0];
 
+    fn parse_data(input: &'a [u8], _sig: &'a [u8; 2], _version: u8) -> NomRes<&'a [u8], Self> {
         Ok((input, Self(true)))
     }
 }
 
 impl<'a> ParseSusp<'a> for RockRidge {
-    const SIGNATURE: Option<&'static [u8; 2]> = Some(&[b'R', b'R']);
-
-    fn parse_data(input: &'a [u8], _sig: &'a [u8; 2], version: u8) -> NomRes<&'a [u8], Self> {
-        #[cfg(feature = "assertions")]
-        {
-            assert_eq!(Self::SIGNATURE.unwrap(), _sig);
-            assert_eq!(input.len(), 1);
-            assert_eq!(version, 1);
-        }
+    const SIGNATURE: Option<&'static [u8; 2]> = Some(b"RR");
+    const DATA_LENGTHS: &'static [usize] = &[1];
 
+    fn parse_data(input: &'a [u8], _sig: &'a [u8; 2], _version: u8) -> NomRes<&'a [u8], Self> {
         let (input, flags) = map(le_u8, RockRidgeFlags::from_bits_truncate)(input)?;
 
         Ok((input, Self { flags }))
@@ -719,15 +1146,9 @@ impl<'a> ParseSusp<'a> for RockRidge {
 }
 
 impl<'a> ParseSusp<'a> for SuspIndicator {
-    const SIGNATURE: Option<&'static [u8; 2]> = Some(&[b'S', b'P']);
-
-    fn parse_data(input: &'a [u8], _sig: &'a [u8; 2], version: u8) -> NomRes<&'a [u8], Self> {
-        #[cfg(feature = "assertions")]
-        {
-            assert_eq!(Self::SIGNATURE.unwrap(), _sig);
-            assert_eq!(version, 1);
-        }
+    const SIGNATURE: Option<&'static [u8; 2]> = Some(b"SP");
 
+    fn parse_data(input: &'a [u8], _sig: &'a [u8; 2], _version: u8) -> NomRes<&'a [u8], Self> {
         let (input, _cow) = tag(&[0xBE, 0xEF])(input)?;
         let (input, skip) = le_u8(input)?;
         let ret = Self { skip };
@@ -736,26 +1157,168 @@ impl<'a> ParseSusp<'a> for SuspIndicator {
     }
 }
 
+impl<'a> ParseSusp<'a> for SparseFile {
+    const SIGNATURE: Option<&'static [u8; 2]> = Some(b"SF");
+
+    // v1.12 added the table depth.
+    const DATA_LENGTHS: &'static [usize] = &[16, 17];
+
+    fn parse_data(input: &'a [u8], _sig: &'a [u8; 2], _version: u8) -> NomRes<&'a [u8], Self> {
+        let (input, high) = both_endian32(input)?;
+        let (input, low) = both_endian32(input)?;
+        let (input, table_depth) = opt(le_u8)(input)?;
+
+        Ok((
+            input,
+            Self {
+                virtual_size: u64::from(high) << 32 | u64::from(low),
+                table_depth: table_depth.unwrap_or(0),
+            },
+        ))
+    }
+}
+
+impl<'a> ParseSusp<'a> for ZisofsFile {
+    const SIGNATURE: Option<&'static [u8; 2]> = Some(b"ZF");
+    const DATA_LENGTHS: &'static [usize] = &[12];
+
+    fn parse_data(input: &'a [u8], _sig: &'a [u8; 2], _version: u8) -> NomRes<&'a [u8], Self> {
+        let (input, algorithm) = take(2_usize)(input)?;
+        let (input, header_size) = le_u8(input)?;
+        let (input, block_size_log2) = le_u8(input)?;
+        let (input, uncompressed_size) = both_endian32(input)?;
+
+        Ok((
+            input,
+            Self {
+                algorithm: algorithm.try_into().unwrap(),
+                // Recorded in units of four bytes.
+                header_size: u16::from(header_size) * 4,
+                block_size_log2,
+                uncompressed_size,
+            },
+        ))
+    }
+}
+
+impl<'a> ParseSusp<'a> for AppleInfo {
+    /// Either `AA` or, on older discs, `BA`.
+    const SIGNATURE: Option<&'static [u8; 2]> = None;
+
+    const VERSION: Option<u8> = None;
+
+    /// The byte in the version position identifies the kind of file instead.
+    fn parse_data(input: &'a [u8], sig: &'a [u8; 2], version: u8) -> NomRes<&'a [u8], Self> {
+        const PRODOS: u8 = 1;
+        const HFS: u8 = 2;
+
+        if sig != b"AA" && sig != b"BA" {
+            return Err(nom::Err::Error(crate::error::OurNomError::from_error_kind(
+                input,
+                nom::error::ErrorKind::Tag,
+            )));
+        }
+
+        match version {
+            PRODOS => {
+                let (input, file_type) = le_u8(input)?;
+                let (input, aux_type) = be_u16(input)?;
+                Ok((
+                    input,
+                    Self::ProDos {
+                        file_type,
+                        aux_type,
+                    },
+                ))
+            }
+            HFS => {
+                let (input, file_type) = take(4_usize)(input)?;
+                let (input, creator) = take(4_usize)(input)?;
+                let (input, finder_flags) = be_u16(input)?;
+                Ok((
+                    input,
+                    Self::Hfs {
+                        file_type: file_type.try_into().unwrap(),
+                        creator: creator.try_into().unwrap(),
+                        finder_flags: FinderFlags::from_bits_retain(finder_flags),
+                    },
+                ))
+            }
+            _ => Err(nom::Err::Error(crate::error::OurNomError::from_error_kind(
+                input,
+                nom::error::ErrorKind::Switch,
+            ))),
+        }
+    }
+}
+
+impl<'a> ParseSusp<'a> for AmigaEntry {
+    const SIGNATURE: Option<&'static [u8; 2]> = Some(b"AS");
+
+    /// The flags are followed by four bytes of protection bits, if present, then by the comment,
+    /// if present, with a leading length byte that counts itself.
+    fn parse_data(input: &'a [u8], _sig: &'a [u8; 2], _version: u8) -> NomRes<&'a [u8], Self> {
+        let (input, flags) = map(le_u8, AmigaEntryFlags::from_bits_truncate)(input)?;
+
+        let (input, protection) = match flags.contains(AmigaEntryFlags::PROTECTION) {
+            true => map(be_u32, |bits| Some(AmigaProtection::from_bits_retain(bits)))(input)?,
+            false => (input, None),
+        };
+
+        let (input, comment) = match flags.contains(AmigaEntryFlags::COMMENT) {
+            true => {
+                let (input, length) = le_u8(input)?;
+                let (input, comment) = take(length.saturating_sub(1))(input)?;
+                // Amiga text is ISO 8859-1.
+                (
+                    input,
+                    Some(comment.iter().copied().map(char::from).collect()),
+                )
+            }
+            false => (input, None),
+        };
+
+        Ok((
+            input,
+            Self {
+                protection,
+                comment,
+                should_continue: flags.contains(AmigaEntryFlags::COMMENT_CONTINUE),
+            },
+        ))
+    }
+}
+
+impl<'a> ParseSusp<'a> for SuspTerminator {
+    const SIGNATURE: Option<&'static [u8; 2]> = Some(b"ST");
+    const DATA_LENGTHS: &'static [usize] = &[0];
+
+    fn parse_data(input: &'a [u8], _sig: &'a [u8; 2], _version: u8) -> NomRes<&'a [u8], Self> {
+        Ok((input, Self))
+    }
+}
+
+impl<'a> ParseSusp<'a> for Padding {
+    const SIGNATURE: Option<&'static [u8; 2]> = Some(b"PD");
+
+    fn parse_data(input: &'a [u8], _sig: &'a [u8; 2], _version: u8) -> NomRes<&'a [u8], Self> {
+        Ok((input, Self))
+    }
+}
+
 impl SymbolicLinkRecord {
     fn parse(input: &[u8]) -> NomRes<&[u8], Self> {
         let (input, flags) = map(le_u8, SymbolicLinkRecordFlags::from_bits_truncate)(input)?;
-        let (input, component) =
-            map(map_res(length_data(le_u8), str::from_utf8), String::from)(input)?;
+        let (input, component) = map(length_data(le_u8), <[u8]>::to_vec)(input)?;
 
         Ok((input, Self { flags, component }))
     }
 }
 
 impl<'a> ParseSusp<'a> for SymbolicLink {
-    const SIGNATURE: Option<&'static [u8; 2]> = Some(&[b'S', b'L']);
-
-    fn parse_data(input: &'a [u8], _sig: &'a [u8; 2], version: u8) -> NomRes<&'a [u8], Self> {
-        #[cfg(feature = "assertions")]
-        {
-            assert_eq!(Self::SIGNATURE.unwrap(), _sig);
-            assert_eq!(version, 1);
-        }
+    const SIGNATURE: Option<&'static [u8; 2]> = Some(b"SL");
 
+    fn parse_data(input: &'a [u8], _sig: &'a [u8; 2], _version: u8) -> NomRes<&'a [u8], Self> {
         let (input, should_continue) =
             alt((value(true, tag(b"\x01")), value(false, tag(b"\x00"))))(input)?;
 
@@ -794,6 +1357,8 @@ impl std::fmt::Debug for Unknown {
 impl<'a> ParseSusp<'a> for Unknown {
     const SIGNATURE: Option<&'static [u8; 2]> = None;
 
+    const VERSION: Option<u8> = None;
+
     fn parse_data(input: &'a [u8], sig: &'a [u8; 2], version: u8) -> NomRes<&'a [u8], Self> {
         let ret = Self {
             sig: *sig,
@@ -805,27 +1370,193 @@ impl<'a> ParseSusp<'a> for Unknown {
     }
 }
 
-pub(crate) fn system_use_entries(input: &[u8]) -> NomRes<&[u8], Vec<SystemUseEntry>> {
-    let (input, entries) = many1(alt((
-        map(SuspIndicator::parse, SystemUseEntry::SuspIndicator),
-        map(ContinuationArea::parse, SystemUseEntry::ContinuationArea),
+/// Works out which extension each entry belongs to.  An `ES` entry selects an extension, by its
+/// position among `extensions`, for the entries that follow it.  Before any `ES` entry, an entry
+/// belongs to the first extension that defines its signature.
+///
+/// # See Also
+///
+/// System Use Sharing Protocol § 5.6
+pub(crate) fn attribute(
+    entries: &[SystemUseEntry],
+    extensions: &[SuspExtension],
+) -> Vec<SystemUseOrigin> {
+    let mut selected: Option<Option<&SuspExtension>> = None;
+
+    entries
+        .iter()
+        .map(|entry| {
+            if let SystemUseEntry::ExtensionSelector(selector) = entry {
+                selected = Some(extensions.get(usize::from(selector.sequence)));
+            }
+
+            let signature = entry.signature();
+            let extension = if entry.is_susp() {
+                None
+            } else if let Some(extension) = selected {
+                extension.cloned()
+            } else {
+                extensions
+                    .iter()
+                    .find(|extension| extension.signatures().contains(&signature))
+                    .cloned()
+            };
+
+            SystemUseOrigin {
+                signature,
+                extension,
+            }
+        })
+        .collect()
+}
+
+/// Parses an Apple `AA` or `BA` entry.
+fn apple_entry(input: &[u8]) -> NomRes<&[u8], SystemUseEntry> {
+    let (input, (sig, _length, version, data)) = AppleInfo::parse_sig(input)?;
+    let (_, info) = AppleInfo::parse_data(data, sig.try_into().unwrap(), version)?;
+
+    Ok((
+        input,
+        match sig {
+            b"BA" => SystemUseEntry::AppleLegacy(info),
+            _ => SystemUseEntry::Apple(info),
+        },
+    ))
+}
+
+fn system_use_entry<'a>(input: &'a [u8], mode: Tolerance) -> NomRes<&'a [u8], SystemUseEntry> {
+    alt((
+        map(
+            |i| SuspIndicator::parse(i, mode),
+            SystemUseEntry::SuspIndicator,
+        ),
         map(
-            ExtensionsReference::parse,
+            |i| SuspTerminator::parse(i, mode),
+            SystemUseEntry::SuspTerminator,
+        ),
+        map(|i| Padding::parse(i, mode), SystemUseEntry::Padding),
+        map(
+            |i| ContinuationArea::parse(i, mode),
+            SystemUseEntry::ContinuationArea,
+        ),
+        map(
+            |i| ExtensionsReference::parse(i, mode),
             SystemUseEntry::ExtensionsReference,
         ),
-        map(PosixTimestamp::parse, SystemUseEntry::PosixTimestamp),
-        map(AlternateName::parse, SystemUseEntry::AlternateName),
-        map(PosixAttributes::parse, SystemUseEntry::PosixAttributes),
-        map(RockRidge::parse, SystemUseEntry::RockRidge),
-        map(ChildLink::parse, SystemUseEntry::ChildLink),
         map(
-            RelocatedDirectory::parse,
+            |i| ExtensionSelector::parse(i, mode),
+            SystemUseEntry::ExtensionSelector,
+        ),
+        map(
+            |i| PosixTimestamp::parse(i, mode),
+            SystemUseEntry::PosixTimestamp,
+        ),
+        map(
+            |i| AlternateName::parse(i, mode),
+            SystemUseEntry::AlternateName,
+        ),
+        map(
+            |i| PosixAttributes::parse(i, mode),
+            SystemUseEntry::PosixAttributes,
+        ),
+        map(
+            |i| DeviceNumber::parse(i, mode),
+            SystemUseEntry::DeviceNumber,
+        ),
+        map(|i| RockRidge::parse(i, mode), SystemUseEntry::RockRidge),
+        map(|i| ChildLink::parse(i, mode), SystemUseEntry::ChildLink),
+        map(
+            |i| RelocatedDirectory::parse(i, mode),
             SystemUseEntry::RelocatedDirectory,
         ),
-        map(SymbolicLink::parse, SystemUseEntry::SymbolicLink),
-        map(Unknown::parse, SystemUseEntry::Unknown),
-    )))(input)?;
+        map(
+            |i| SymbolicLink::parse(i, mode),
+            SystemUseEntry::SymbolicLink,
+        ),
+        map(|i| SparseFile::parse(i, mode), SystemUseEntry::SparseFile),
+        map(
+            |i| AttributeList::parse(i, mode),
+            SystemUseEntry::AttributeList,
+        ),
+        map(|i| ZisofsFile::parse(i, mode), SystemUseEntry::Zisofs),
+        apple_entry,
+        map(|i| AmigaEntry::parse(i, mode), SystemUseEntry::Amiga),
+        map(|i| Unknown::parse(i, mode), SystemUseEntry::Unknown),
+    ))(input)
+}
+
+/// Parses the entries of a system use field or continuation area, up to an `ST` entry or
+/// whatever can't be parsed.  `PD` entries are skipped.  An anomaly rejected by `mode` is an error.
+pub(crate) fn system_use_entries<'a>(
+    input: &'a [u8],
+    mode: Tolerance,
+) -> NomRes<&'a [u8], Vec<SystemUseEntry>> {
+    let (mut input, first) = system_use_entry(input, mode)?;
+
+    let mut entries = Vec::new();
+    let mut next = Some(first);
+    while let Some(entry) = next {
+        match entry {
+            SystemUseEntry::SuspTerminator(_) => break,
+            SystemUseEntry::Padding(_) => {}
+            entry => entries.push(entry),
+        }
+
+        next = match system_use_entry(input, mode) {
+            Ok((rest, entry)) => {
+                input = rest;
+                Some(entry)
+            }
+            Err(nom::Err::Failure(err)) => return Err(nom::Err::Failure(err)),
+            Err(_) => None,
+        };
+    }
 
-    // eprintln!("suspremainder={}", input.len());
     Ok((input, entries))
 }
+
+/// Parses a directory record's system use field according to `layout`.  The root's `.` entry,
+/// which holds the `SP` entry itself, isn't skipped.  Without SUSP, only the Apple entries a
+/// field may start with are parsed.
+///
+/// A CD-ROM XA record at the start of the field comes first.  Discs with both are expected to
+/// skip it with `SP`, but the SUSP entries are looked for after it regardless.
+pub(crate) fn system_use_field<'w>(
+    layout: SystemUseLayout,
+    mode: Tolerance<'w>,
+) -> impl Fn(&[u8]) -> NomRes<&[u8], Option<Vec<SystemUseEntry>>> + 'w {
+    move |input| {
+        let (field, xa) = match xa_record(input) {
+            Ok((field, xa)) => (field, Some(SystemUseEntry::Xa(xa))),
+            Err(_) => (input, None),
+        };
+        let with_xa = |entries: Option<Vec<SystemUseEntry>>| {
+            let entries: Vec<SystemUseEntry> = xa
+                .iter()
+                .cloned()
+                .chain(entries.into_iter().flatten())
+                .collect();
+            Some(entries).filter(|entries| !entries.is_empty())
+        };
+
+        let skip = match layout {
+            SystemUseLayout::Absent => {
+                let (field, entries) = many0(apple_entry)(field)?;
+                return Ok((field, with_xa(Some(entries))));
+            }
+            SystemUseLayout::Unknown => 0,
+            SystemUseLayout::Present { .. } if SuspIndicator::parse(field, mode).is_ok() => 0,
+            SystemUseLayout::Present { skip } => {
+                usize::from(skip).saturating_sub(input.len() - field.len())
+            }
+        };
+
+        match field.get(skip..) {
+            Some(field) => {
+                let (field, entries) = opt(|field| system_use_entries(field, mode))(field)?;
+                Ok((field, with_xa(entries)))
+            }
+            None => Ok((&field[field.len()..], with_xa(None))),
+        }
+    }
+}
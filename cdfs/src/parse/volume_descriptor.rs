@@ -1,7 +1,7 @@
 // SPDX-License-Identifier: (MIT OR Apache-2.0)
 
 use nom::bytes::complete::{tag, take};
-use nom::combinator::{map, map_parser};
+use nom::combinator::{map, map_parser, verify};
 use nom::number::complete::*;
 use nom::sequence::tuple;
 use time::OffsetDateTime;
@@ -9,66 +9,186 @@ use time::OffsetDateTime;
 use super::both_endian::{both_endian16, both_endian32};
 use super::date_time::date_time_ascii;
 use super::directory_entry::{directory_entry, DirectoryEntryHeader};
+use super::susp::SystemUseLayout;
 use super::{character_encoding, decode_string, CharacterEncoding};
 use crate::error::NomRes;
-use crate::Result;
+use crate::{Decoding, Result, Tolerance};
 
+/// The contents of a primary or supplementary volume descriptor, which describe a volume and the
+/// directory hierarchy recorded on it.
+///
+/// ## See Also
+///
+/// ISO-9660 / ECMA-119 §§ 8.4, 8.5
 #[derive(Clone, Debug)]
 pub struct VolumeDescriptorTable {
+    /// The version of the descriptor: 2 for an ISO 9660:1999 enhanced volume descriptor, and 1
+    /// otherwise.
+    pub version: u8,
+
+    /// Bit 0 is set if the escape sequences include any that aren't registered according to
+    /// ISO 2375.  Always zero for a primary volume descriptor.
+    pub volume_flags: u8,
+
+    /// Identifies the system that can act upon the system area of the volume.
     pub system_identifier: String,
+
+    /// Identifies the volume.
     pub volume_identifier: String,
-    pub character_encoding: CharacterEncoding,
+
+    /// The number of logical blocks in the volume.
     pub volume_space_size: u32,
+
+    /// The escape sequences designating the character sets used by the descriptor and its
+    /// directory hierarchy, e.g. `%/E` for Joliet.  Always zero for a primary volume descriptor.
+    pub escape_sequences: [u8; 32],
+
+    pub(crate) character_encoding: CharacterEncoding,
+
+    /// The number of volumes in the volume set.
     pub volume_set_size: u16,
+
+    /// The position of this volume within the volume set.
     pub volume_sequence_number: u16,
+
+    /// The size in bytes of a logical block.
     pub logical_block_size: u16,
 
+    /// The size in bytes of the path table.
     pub path_table_size: u32,
+
+    /// The logical block number of the Type L path table.
     pub path_table_loc: u32,
+
+    /// The logical block number of the optional copy of the Type L path table, or zero.
     pub optional_path_table_loc: u32,
 
-    pub root_directory_entry: DirectoryEntryHeader,
-    pub root_directory_entry_identifier: String,
+    /// The logical block number of the Type M path table.
+    pub path_table_loc_be: u32,
+
+    /// The logical block number of the optional copy of the Type M path table, or zero.
+    pub optional_path_table_loc_be: u32,
 
+    pub(crate) root_directory_entry: DirectoryEntryHeader,
+    pub(crate) root_directory_entry_identifier: String,
+
+    /// Identifies the volume set the volume is a member of.
     pub volume_set_identifier: String,
+
+    /// Identifies who specified what's recorded on the volume set.
     pub publisher_identifier: String,
+
+    /// Identifies who prepared the data recorded on the volume.
     pub data_preparer_identifier: String,
+
+    /// Identifies how the data was recorded on the volume.
     pub application_identifier: String,
+
+    /// The name of a file in the root directory holding the copyright statement.
     pub copyright_file_identifier: String,
+
+    /// The name of a file in the root directory holding an abstract of the volume set.
     pub abstract_file_identifier: String,
+
+    /// The name of a file in the root directory holding bibliographic records.
     pub bibliographic_file_identifier: String,
 
+    /// When the volume was created.
     pub creation_time: OffsetDateTime,
+
+    /// When the volume was last modified.
     pub modification_time: OffsetDateTime,
+
+    /// When the volume is to be considered obsolete.
     pub expiration_time: OffsetDateTime,
+
+    /// When the volume may start being used.
     pub effective_time: OffsetDateTime,
 
+    /// The version of the directory records and path table.  1 for ISO 9660 / ECMA-119.
     pub file_structure_version: u8,
+
+    /// 512 bytes reserved for the application, which are not specified by the standard.
+    pub application_use: Vec<u8>,
+}
+
+/// A volume partition descriptor, which describes a region of the volume set aside for a
+/// particular system.
+///
+/// ## See Also
+///
+/// ISO-9660 / ECMA-119 § 8.6
+#[derive(Clone, Debug)]
+pub struct PartitionDescriptor {
+    /// Identifies the system that can act upon the partition.
+    pub system_identifier: String,
+
+    /// Identifies the partition.
+    pub partition_identifier: String,
+
+    /// The logical block number of the first block of the partition.
+    pub partition_location: u32,
+
+    /// The number of logical blocks in the partition.
+    pub partition_size: u32,
+
+    /// 1960 bytes reserved for the system, which are not specified by the standard.
+    pub system_use: Vec<u8>,
 }
 
-#[allow(dead_code)]
+/// A volume descriptor, as recorded in the volume descriptor set starting at logical sector 16.
+///
+/// ## See Also
+///
+/// ISO-9660 / ECMA-119 § 8
+#[allow(clippy::enum_variant_names)]
 #[derive(Clone, Debug)]
-pub(crate) enum VolumeDescriptor {
+pub enum VolumeDescriptor {
+    /// The primary volume descriptor, describing the ISO 9660 directory hierarchy.
     Primary(VolumeDescriptorTable),
+
+    /// A supplementary volume descriptor, describing another directory hierarchy, e.g. Joliet.
     Supplementary(VolumeDescriptorTable),
+
+    /// A boot record, such as El Torito's.
     BootRecord {
+        /// Identifies the system that can recognize and act upon the boot record.
         boot_system_identifier: String,
+
+        /// Identifies the boot system.
         boot_identifier: String,
+
+        /// 1977 bytes reserved for the boot system.
         data: Vec<u8>,
     },
+
+    /// A volume partition descriptor.
+    Partition(PartitionDescriptor),
+
+    /// The descriptor ending the set.
     VolumeDescriptorSetTerminator,
 }
 
 impl VolumeDescriptor {
-    pub fn parse(bytes: &[u8]) -> Result<Option<VolumeDescriptor>> {
-        Ok(volume_descriptor(bytes)?.1)
+    pub(crate) fn parse(
+        bytes: &[u8],
+        decoding: Decoding,
+        mode: Tolerance,
+    ) -> Result<Option<VolumeDescriptor>> {
+        Ok(volume_descriptor(bytes, decoding, mode)?.1)
     }
 }
 
-fn boot_record(i: &[u8]) -> NomRes<&[u8], VolumeDescriptor> {
+fn boot_record<'a>(i: &'a [u8], mode: Tolerance) -> NomRes<&'a [u8], VolumeDescriptor> {
     let (i, (boot_system_identifier, boot_identifier, data)): (&[u8], (String, _, _)) = tuple((
-        map_parser(take(32usize), decode_string(CharacterEncoding::Iso9660)),
-        map_parser(take(32usize), decode_string(CharacterEncoding::Iso9660)),
+        map_parser(
+            take(32usize),
+            decode_string(CharacterEncoding::Iso9660, Decoding::default(), mode),
+        ),
+        map_parser(
+            take(32usize),
+            decode_string(CharacterEncoding::Iso9660, Decoding::default(), mode),
+        ),
         take(1977usize),
     ))(i)?;
 
@@ -82,26 +202,75 @@ fn boot_record(i: &[u8]) -> NomRes<&[u8], VolumeDescriptor> {
     ))
 }
 
-fn volume_descriptor(i: &[u8]) -> NomRes<&[u8], Option<VolumeDescriptor>> {
+fn volume_descriptor<'a>(
+    i: &'a [u8],
+    decoding: Decoding,
+    mode: Tolerance,
+) -> NomRes<&'a [u8], Option<VolumeDescriptor>> {
     let (i, type_code) = le_u8(i)?;
-    let (i, _) = tag("CD001\u{1}")(i)?;
+    let (i, _) = tag("CD001")(i)?;
+    // Only a supplementary volume descriptor may be enhanced.
+    let (i, version) = verify(le_u8, |version| {
+        *version == 1 || (type_code == 2 && *version == 2)
+    })(i)?;
     match type_code {
-        0 => map(boot_record, Some)(i),
-        1 => map(primary_descriptor, Some)(i),
-        2 => map(supplementary_descriptor, Some)(i),
-        //3 => map!(volume_partition_descriptor, Some)(i),
+        0 => map(|i| boot_record(i, mode), Some)(i),
+        1 => map(|i| primary_descriptor(i, decoding, mode), Some)(i),
+        2 => map(
+            |i| supplementary_descriptor(i, version, decoding, mode),
+            Some,
+        )(i),
+        3 => map(|i| partition_descriptor(i, mode), Some)(i),
         255 => Ok((i, Some(VolumeDescriptor::VolumeDescriptorSetTerminator))),
         _ => Ok((i, None)),
     }
 }
 
-fn descriptor_table(i: &[u8]) -> NomRes<&[u8], VolumeDescriptorTable> {
+fn partition_descriptor<'a>(i: &'a [u8], mode: Tolerance) -> NomRes<&'a [u8], VolumeDescriptor> {
     let (i, _) = take(1usize)(i)?; // padding
+    let (i, system_identifier) = map_parser(
+        take(32usize),
+        decode_string(CharacterEncoding::Iso9660, Decoding::default(), mode),
+    )(i)?;
+    let (i, partition_identifier) = map_parser(
+        take(32usize),
+        decode_string(CharacterEncoding::Iso9660, Decoding::default(), mode),
+    )(i)?;
+    let (i, partition_location) = both_endian32(i)?;
+    let (i, partition_size) = both_endian32(i)?;
+    let (i, system_use) = take(1960usize)(i)?;
+
+    Ok((
+        i,
+        VolumeDescriptor::Partition(PartitionDescriptor {
+            system_identifier,
+            partition_identifier,
+            partition_location,
+            partition_size,
+            system_use: system_use.to_vec(),
+        }),
+    ))
+}
+
+fn descriptor_table<'a>(
+    i: &'a [u8],
+    primary: bool,
+    version: u8,
+    decoding: Decoding,
+    mode: Tolerance,
+) -> NomRes<&'a [u8], VolumeDescriptorTable> {
+    let (i, volume_flags) = le_u8(i)?;
     let (i, system_identifier) = take(32usize)(i)?;
     let (i, volume_identifier) = take(32usize)(i)?;
     let (i, _) = take(8usize)(i)?; // padding
     let (i, volume_space_size) = both_endian32(i)?;
-    let (i, character_encoding) = character_encoding(i)?;
+    let (i, escape_sequences) = take(32usize)(i)?;
+    // Escape sequences are unused in the primary descriptor, so only a supplementary one can
+    // select UCS-2.
+    let character_encoding = match primary {
+        true => CharacterEncoding::Iso9660,
+        false => character_encoding(escape_sequences)?.1,
+    };
     let (i, volume_set_size) = both_endian16(i)?;
     let (i, volume_sequence_number) = both_endian16(i)?;
     let (i, logical_block_size) = both_endian16(i)?;
@@ -109,10 +278,16 @@ fn descriptor_table(i: &[u8]) -> NomRes<&[u8], VolumeDescriptorTable> {
     let (i, path_table_size) = both_endian32(i)?;
     let (i, path_table_loc) = le_u32(i)?;
     let (i, optional_path_table_loc) = le_u32(i)?;
-    let (i, _) = take(4usize)(i)?; // path_table_loc_be
-    let (i, _) = take(4usize)(i)?; // optional_path_table_loc_be
+    let (i, path_table_loc_be) = be_u32(i)?;
+    let (i, optional_path_table_loc_be) = be_u32(i)?;
 
-    let (i, root_directory_entry) = directory_entry(i, character_encoding)?;
+    let (i, root_directory_entry) = directory_entry(
+        i,
+        character_encoding,
+        SystemUseLayout::Absent,
+        decoding,
+        mode,
+    )?;
 
     let (i, volume_set_identifier) = take(128usize)(i)?;
     let (i, publisher_identifier) = take(128usize)(i)?;
@@ -128,28 +303,38 @@ fn descriptor_table(i: &[u8]) -> NomRes<&[u8], VolumeDescriptorTable> {
     let (i, effective_time) = date_time_ascii(i)?;
 
     let (i, file_structure_version) = le_u8(i)?;
+    let (i, _) = take(1usize)(i)?; // padding
+    let (i, application_use) = take(512usize)(i)?;
 
-    let (_, system_identifier) = decode_string(character_encoding)(system_identifier)?;
-    let (_, volume_identifier) = decode_string(character_encoding)(volume_identifier)?;
-    let (_, volume_set_identifier) = decode_string(character_encoding)(volume_set_identifier)?;
-    let (_, publisher_identifier) = decode_string(character_encoding)(publisher_identifier)?;
+    let (_, system_identifier) =
+        decode_string(character_encoding, decoding, mode)(system_identifier)?;
+    let (_, volume_identifier) =
+        decode_string(character_encoding, decoding, mode)(volume_identifier)?;
+    let (_, volume_set_identifier) =
+        decode_string(character_encoding, decoding, mode)(volume_set_identifier)?;
+    let (_, publisher_identifier) =
+        decode_string(character_encoding, decoding, mode)(publisher_identifier)?;
     let (_, data_preparer_identifier) =
-        decode_string(character_encoding)(data_preparer_identifier)?;
-    let (_, application_identifier) = decode_string(character_encoding)(application_identifier)?;
+        decode_string(character_encoding, decoding, mode)(data_preparer_identifier)?;
+    let (_, application_identifier) =
+        decode_string(character_encoding, decoding, mode)(application_identifier)?;
     let (_, copyright_file_identifier) =
-        decode_string(character_encoding)(copyright_file_identifier)?;
+        decode_string(character_encoding, decoding, mode)(copyright_file_identifier)?;
     let (_, abstract_file_identifier) =
-        decode_string(character_encoding)(abstract_file_identifier)?;
+        decode_string(character_encoding, decoding, mode)(abstract_file_identifier)?;
     let (_, bibliographic_file_identifier) =
-        decode_string(CharacterEncoding::Iso9660)(bibliographic_file_identifier)?;
+        decode_string(CharacterEncoding::Iso9660, decoding, mode)(bibliographic_file_identifier)?;
 
     Ok((
         i,
         VolumeDescriptorTable {
+            version,
+            volume_flags,
             system_identifier,
             volume_identifier,
-            character_encoding,
             volume_space_size,
+            escape_sequences: escape_sequences.try_into().unwrap(),
+            character_encoding,
             volume_set_size,
             volume_sequence_number,
             logical_block_size,
@@ -157,6 +342,8 @@ fn descriptor_table(i: &[u8]) -> NomRes<&[u8], VolumeDescriptorTable> {
             path_table_size,
             path_table_loc,
             optional_path_table_loc,
+            path_table_loc_be,
+            optional_path_table_loc_be,
 
             root_directory_entry: root_directory_entry.0,
             root_directory_entry_identifier: root_directory_entry.1,
@@ -175,14 +362,30 @@ fn descriptor_table(i: &[u8]) -> NomRes<&[u8], VolumeDescriptorTable> {
             effective_time,
 
             file_structure_version,
+            application_use: application_use.to_vec(),
         },
     ))
 }
 
-fn supplementary_descriptor(i: &[u8]) -> NomRes<&[u8], VolumeDescriptor> {
-    map(descriptor_table, VolumeDescriptor::Supplementary)(i)
+fn supplementary_descriptor<'a>(
+    i: &'a [u8],
+    version: u8,
+    decoding: Decoding,
+    mode: Tolerance,
+) -> NomRes<&'a [u8], VolumeDescriptor> {
+    map(
+        |i| descriptor_table(i, false, version, decoding, mode),
+        VolumeDescriptor::Supplementary,
+    )(i)
 }
 
-fn primary_descriptor(i: &[u8]) -> NomRes<&[u8], VolumeDescriptor> {
-    map(descriptor_table, VolumeDescriptor::Primary)(i)
+fn primary_descriptor<'a>(
+    i: &'a [u8],
+    decoding: Decoding,
+    mode: Tolerance,
+) -> NomRes<&'a [u8], VolumeDescriptor> {
+    map(
+        |i| descriptor_table(i, true, 1, decoding, mode),
+        VolumeDescriptor::Primary,
+    )(i)
 }
@@ -0,0 +1,92 @@
+// SPDX-License-Identifier: (MIT OR Apache-2.0)
+
+//! The CD-ROM XA system use record, which precedes any other system use entries in a directory
+//! record on Video CD, PlayStation, and CD-i discs.
+
+use bitflags::bitflags;
+use nom::{
+    bytes::complete::{tag, take},
+    number::complete::{be_u16, le_u8},
+};
+
+use crate::error::NomRes;
+
+/// The CD-ROM XA record of a directory record.
+///
+/// # See Also
+///
+/// System Description CD-ROM XA (the "White Book") § 4.3
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct XaRecord {
+    /// The owner's group ID.
+    pub group_id: u16,
+
+    /// The owner's user ID.
+    pub user_id: u16,
+
+    /// Permissions and how the file's sectors are recorded.
+    pub attributes: XaAttributes,
+
+    /// The file number, which interleaved sectors are tagged with in their subheaders.
+    pub file_number: u8,
+}
+
+bitflags! {
+    /// The attributes from a CD-ROM XA record.
+    #[derive(Clone, Copy, Debug, PartialEq)]
+    pub struct XaAttributes: u16 {
+        /// The owner may read the file.
+        const OWNER_READ = 1 << 0;
+
+        /// The owner may execute the file.
+        const OWNER_EXECUTE = 1 << 2;
+
+        /// The group may read the file.
+        const GROUP_READ = 1 << 4;
+
+        /// The group may execute the file.
+        const GROUP_EXECUTE = 1 << 6;
+
+        /// Anyone may read the file.
+        const WORLD_READ = 1 << 8;
+
+        /// Anyone may execute the file.
+        const WORLD_EXECUTE = 1 << 10;
+
+        /// The file is recorded in Mode 2 Form 1 sectors.
+        const MODE2_FORM1 = 1 << 11;
+
+        /// The file is recorded in Mode 2 Form 2 sectors, e.g. video and audio streams.
+        const MODE2_FORM2 = 1 << 12;
+
+        /// The file's sectors are interleaved with those of other files.
+        const INTERLEAVED = 1 << 13;
+
+        /// The file is a CD-DA audio track.
+        const CDDA = 1 << 14;
+
+        /// The entry is a directory.
+        const DIRECTORY = 1 << 15;
+    }
+}
+
+/// Parses the XA record at the start of a system use field.  The fields are big-endian, and the
+/// record is identified by the `XA` signature in the middle of it rather than at its start.
+pub(crate) fn xa_record(input: &[u8]) -> NomRes<&[u8], XaRecord> {
+    let (input, group_id) = be_u16(input)?;
+    let (input, user_id) = be_u16(input)?;
+    let (input, attributes) = be_u16(input)?;
+    let (input, _) = tag(b"XA")(input)?;
+    let (input, file_number) = le_u8(input)?;
+    let (input, _reserved) = take(5_usize)(input)?;
+
+    Ok((
+        input,
+        XaRecord {
+            group_id,
+            user_id,
+            attributes: XaAttributes::from_bits_retain(attributes),
+            file_number,
+        },
+    ))
+}
@@ -0,0 +1,305 @@
+// SPDX-License-Identifier: (MIT OR Apache-2.0)
+
+#[allow(unused)]
+use log::{debug, error, info, trace, warn};
+
+use std::{
+    cmp::min,
+    io::{self, Read, Seek, SeekFrom},
+};
+
+use crate::{fileref::ISO9660Reader, BLOCK_SIZE};
+
+/// The twelve byte synchronization pattern at the start of every raw data sector.
+const SYNC: [u8; 12] = [
+    0x00, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x00,
+];
+
+/// The size of a raw sector including sync, header, and error correction.
+pub(crate) const RAW_SECTOR_SIZE: usize = 2352;
+
+/// The size of a Mode 2 sector without the sync and header.
+pub(crate) const MODE2_SECTOR_SIZE: usize = 2336;
+
+/// The size of the user data in a Mode 2 Form 2 sector.
+pub(crate) const FORM2_DATA_SIZE: usize = 2324;
+
+/// Submode bit in the CD-ROM XA subheader that marks a Form 2 sector.
+const SUBMODE_FORM2: u8 = 1 << 5;
+
+/// How sectors are laid out in the underlying image.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum SectorFormat {
+    /// 2048 bytes of user data per sector, e.g. a typical `.iso` file.
+    Cooked,
+
+    /// 2352 byte raw sectors with sync, header, and (for Mode 1 / Mode 2 Form 1) EDC/ECC, e.g. a
+    /// `.bin` dump.  The mode of each sector is read from its header.
+    Raw,
+
+    /// 2336 byte Mode 2 sectors, i.e. raw sectors with the sync and header stripped.
+    Mode2,
+}
+
+impl SectorFormat {
+    /// Returns the number of bytes each sector occupies in the image.
+    pub fn sector_size(&self) -> usize {
+        match self {
+            Self::Cooked => usize::from(BLOCK_SIZE),
+            Self::Raw => RAW_SECTOR_SIZE,
+            Self::Mode2 => MODE2_SECTOR_SIZE,
+        }
+    }
+}
+
+/// The mode of an individual sector, which determines where its user data lives.
+///
+/// # See Also
+///
+/// * ECMA-130 § 14
+/// * System Description CD-ROM XA (the "White Book")
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum SectorMode {
+    /// Mode 0; the user data is all zeros.
+    Mode0,
+
+    /// Mode 1; 2048 bytes of user data.
+    Mode1,
+
+    /// Mode 2 without an XA subheader; 2336 bytes of user data.
+    Mode2Formless,
+
+    /// CD-ROM XA Mode 2 Form 1; 2048 bytes of user data.
+    Mode2Form1,
+
+    /// CD-ROM XA Mode 2 Form 2; 2324 bytes of user data.
+    Mode2Form2,
+
+    /// The image is cooked, so there is no mode information.
+    Cooked,
+}
+
+impl SectorMode {
+    /// Returns the offset of the user data within a sector of a given format, and its length.
+    fn data_range(&self, format: SectorFormat) -> (usize, usize) {
+        let header = match format {
+            SectorFormat::Raw => 16,
+            SectorFormat::Mode2 | SectorFormat::Cooked => 0,
+        };
+
+        match self {
+            Self::Cooked => (0, usize::from(BLOCK_SIZE)),
+            Self::Mode0 | Self::Mode1 => (header, usize::from(BLOCK_SIZE)),
+            Self::Mode2Formless => (header, MODE2_SECTOR_SIZE),
+            Self::Mode2Form1 => (header + 8, usize::from(BLOCK_SIZE)),
+            Self::Mode2Form2 => (header + 8, FORM2_DATA_SIZE),
+        }
+    }
+}
+
+/// An [`ISO9660Reader`] for images made of raw (2352 byte) or Mode 2 (2336 byte) sectors.
+///
+/// Sectors are addressed by their logical block address as usual, and the 2048 bytes of user
+/// data are extracted from each one.  Error detection and correction codes are not verified.
+///
+/// # Example
+///
+/// ```rust
+/// # std::env::set_current_dir(concat!(env!("CARGO_MANIFEST_DIR"), "/.."));
+/// # use std::fs::File;
+/// use cdfs::{RawSectorReader, ISO9660};
+///
+/// let file = File::open("images/test.iso")?;
+/// let iso = ISO9660::new(RawSectorReader::new(file)?)?;
+/// # Ok::<(), cdfs::ISOError>(())
+/// ```
+pub struct RawSectorReader<R: Read + Seek> {
+    inner: R,
+    format: SectorFormat,
+    offset: u64,
+    sector: Vec<u8>,
+}
+
+impl<R: Read + Seek> RawSectorReader<R> {
+    /// Returns a new reader, detecting the sector format by looking for the volume descriptor
+    /// set at sector 16.
+    ///
+    /// # Errors
+    ///
+    /// Returns an I/O error if the image cannot be read or no volume descriptor can be found.
+    pub fn new(mut inner: R) -> io::Result<Self> {
+        let format = detect_format(&mut inner)?;
+        debug!("Detected sector format {format:?}");
+        Ok(Self::with_format(inner, format))
+    }
+
+    /// Returns a new reader for an image with a known sector format.
+    pub fn with_format(inner: R, format: SectorFormat) -> Self {
+        Self::with_offset(inner, format, 0)
+    }
+
+    /// Returns a new reader for an image with a known sector format, where logical block 0
+    /// begins `offset` bytes into the image.  Useful for e.g. a data track that follows audio
+    /// tracks in the same file.
+    pub fn with_offset(inner: R, format: SectorFormat, offset: u64) -> Self {
+        Self {
+            inner,
+            format,
+            offset,
+            sector: vec![0; format.sector_size()],
+        }
+    }
+
+    /// Returns the sector format of the image.
+    pub fn format(&self) -> SectorFormat {
+        self.format
+    }
+
+    /// Returns the underlying reader.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+
+    /// Reads a single sector and returns its mode and user data.  The user data is 2048 bytes
+    /// for Mode 1 / Form 1 sectors, 2324 bytes for Form 2 sectors, and 2336 bytes for formless
+    /// Mode 2 sectors.
+    ///
+    /// # Errors
+    ///
+    /// Returns an I/O error if the sector cannot be read in its entirety.
+    pub fn read_sector(&mut self, lba: u64) -> io::Result<(SectorMode, &[u8])> {
+        let sector_size = self.format.sector_size();
+        self.inner
+            .seek(SeekFrom::Start(self.offset + lba * sector_size as u64))?;
+        self.inner.read_exact(&mut self.sector)?;
+
+        let mode = self.sector_mode()?;
+        let (start, len) = mode.data_range(self.format);
+        Ok((mode, &self.sector[start..start + len]))
+    }
+
+    fn sector_mode(&self) -> io::Result<SectorMode> {
+        let submode = |subheader: &[u8]| {
+            if subheader[2] & SUBMODE_FORM2 != 0 {
+                SectorMode::Mode2Form2
+            } else {
+                SectorMode::Mode2Form1
+            }
+        };
+
+        match self.format {
+            SectorFormat::Cooked => Ok(SectorMode::Cooked),
+            SectorFormat::Mode2 => Ok(submode(&self.sector[0..8])),
+            SectorFormat::Raw => {
+                if self.sector[0..12] != SYNC {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        "Missing sector sync pattern",
+                    ));
+                }
+
+                match self.sector[15] {
+                    0 => Ok(SectorMode::Mode0),
+                    1 => Ok(SectorMode::Mode1),
+                    2 => {
+                        // The subheader is recorded twice; a formless sector won't repeat itself.
+                        let subheader = &self.sector[16..24];
+                        if subheader[0..4] == subheader[4..8] {
+                            Ok(submode(subheader))
+                        } else {
+                            Ok(SectorMode::Mode2Formless)
+                        }
+                    }
+                    mode => {
+                        warn!("Unknown sector mode {mode}");
+                        Err(io::Error::new(
+                            io::ErrorKind::InvalidData,
+                            "Unknown sector mode",
+                        ))
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl<R: Read + Seek> ISO9660Reader for RawSectorReader<R> {
+    fn read_at(&mut self, buf: &mut [u8], lba: u64) -> io::Result<usize> {
+        let blksize = usize::from(BLOCK_SIZE);
+        let mut count = 0;
+
+        for (lba, chunk) in (lba..).zip(buf.chunks_mut(blksize)) {
+            let (mode, data) = match self.read_sector(lba) {
+                Ok(sector) => sector,
+                Err(err) if err.kind() == io::ErrorKind::UnexpectedEof => break,
+                Err(err) => return Err(err),
+            };
+
+            let len = min(chunk.len(), data.len());
+            if mode == SectorMode::Mode0 {
+                chunk[..len].fill(0);
+            } else {
+                chunk[..len].copy_from_slice(&data[..len]);
+            }
+            count += len;
+        }
+
+        Ok(count)
+    }
+
+    fn read_sector_at(&mut self, buf: &mut [u8], lba: u64) -> io::Result<Option<usize>> {
+        if self.format == SectorFormat::Cooked {
+            return Ok(None);
+        }
+
+        let (mode, data) = self.read_sector(lba)?;
+        let len = min(buf.len(), data.len());
+        if mode == SectorMode::Mode0 {
+            buf[..len].fill(0);
+        } else {
+            buf[..len].copy_from_slice(&data[..len]);
+        }
+
+        Ok(Some(len))
+    }
+}
+
+/// Looks for the standard identifier of the first volume descriptor at sector 16 under each
+/// sector format.
+fn detect_format<R: Read + Seek>(inner: &mut R) -> io::Result<SectorFormat> {
+    let mut buf = [0; 32];
+
+    let mut probe = |offset: u64| -> io::Result<Option<[u8; 32]>> {
+        inner.seek(SeekFrom::Start(offset))?;
+        match inner.read_exact(&mut buf) {
+            Ok(()) => Ok(Some(buf)),
+            Err(err) if err.kind() == io::ErrorKind::UnexpectedEof => Ok(None),
+            Err(err) => Err(err),
+        }
+    };
+
+    let is_descriptor = |data: &[u8]| &data[1..6] == b"CD001";
+
+    if let Some(sector) = probe(16 * RAW_SECTOR_SIZE as u64)? {
+        if sector[0..12] == SYNC && (is_descriptor(&sector[16..]) || is_descriptor(&sector[24..])) {
+            return Ok(SectorFormat::Raw);
+        }
+    }
+
+    if let Some(sector) = probe(16 * MODE2_SECTOR_SIZE as u64)? {
+        if is_descriptor(&sector[8..]) {
+            return Ok(SectorFormat::Mode2);
+        }
+    }
+
+    if let Some(sector) = probe(16 * u64::from(BLOCK_SIZE))? {
+        if is_descriptor(&sector) {
+            return Ok(SectorFormat::Cooked);
+        }
+    }
+
+    Err(io::Error::new(
+        io::ErrorKind::InvalidData,
+        "No volume descriptor found",
+    ))
+}
@@ -0,0 +1,118 @@
+// SPDX-License-Identifier: (MIT OR Apache-2.0)
+
+#[allow(unused)]
+use log::{debug, error, info, trace, warn};
+
+use std::cmp::max;
+
+use crate::{
+    parse::volume_descriptor::VolumeDescriptor, BlockBuffer, BlockBufferCtor, Decoding,
+    ISO9660Reader, ISOError, ParseMode, Result, Tolerance, BLOCK_SIZE,
+};
+
+/// A session on a multisession disc (or an image appended to with e.g. `growisofs -M`).
+///
+/// Each session records a complete volume descriptor set 16 sectors past its start.  The
+/// addresses inside a session are absolute, so later sessions can refer back to files recorded
+/// by earlier ones, and the last session describes the whole disc.
+///
+/// # See Also
+/// ISO-9660 / ECMA-119 § 6.2.1
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Session {
+    /// The logical sector at which the session begins.
+    pub start: u32,
+
+    /// The volume space size recorded in the session's primary volume descriptor, in logical
+    /// blocks.  This is measured from the start of the disc rather than the start of the session.
+    pub volume_space_size: u32,
+
+    /// The logical block size recorded in the session's primary volume descriptor.
+    pub block_size: u16,
+}
+
+impl Session {
+    /// Returns the first logical sector past the end of the volume described by this session.
+    pub fn end(&self) -> u32 {
+        let bytes = u64::from(self.volume_space_size) * u64::from(self.block_size);
+        u32::try_from(bytes.div_ceil(u64::from(BLOCK_SIZE))).unwrap_or(u32::MAX)
+    }
+}
+
+/// Scans an image for sessions.  The first session always starts at sector 0, and each
+/// subsequent session is looked for after the end of the volume described by the previous one.
+pub(crate) fn find_sessions<T: ISO9660Reader>(reader: &mut T) -> Result<Vec<Session>> {
+    let mut buf = BlockBuffer::new();
+
+    let first = match probe(reader, &mut buf, 0)? {
+        Probe::Session(session) => session,
+        Probe::Nothing | Probe::End => {
+            return Err(ISOError::InvalidFs("No primary volume descriptor"));
+        }
+    };
+
+    let mut start = max(first.end(), 1);
+    let mut sessions = vec![first];
+
+    loop {
+        match probe(reader, &mut buf, start)? {
+            Probe::Session(session) => {
+                debug!("Found session at sector {start}");
+                start = max(session.end(), start + 1);
+                sessions.push(session);
+            }
+            Probe::Nothing => start += 1,
+            Probe::End => break,
+        }
+    }
+
+    Ok(sessions)
+}
+
+enum Probe {
+    /// A volume descriptor set with a primary descriptor.
+    Session(Session),
+    Nothing,
+    End,
+}
+
+/// Looks for a volume descriptor set in a session starting at `start`.
+fn probe<T: ISO9660Reader>(reader: &mut T, buf: &mut BlockBuffer, start: u32) -> Result<Probe> {
+    let mut lba = u64::from(start) + 16;
+
+    loop {
+        let count = reader.read_at(buf, lba)?;
+        if count != usize::from(BLOCK_SIZE) {
+            return Ok(Probe::End);
+        }
+
+        // Cheaply skip over sectors that can't be volume descriptors before parsing.
+        if &buf[1..6] != b"CD001" {
+            return Ok(Probe::Nothing);
+        }
+
+        // Anomalies are left for whoever opens the session to judge.
+        match VolumeDescriptor::parse(
+            buf.as_slice(),
+            Decoding::default(),
+            Tolerance::new(ParseMode::Lenient, None),
+        ) {
+            Ok(Some(VolumeDescriptor::Primary(table))) => {
+                return Ok(Probe::Session(Session {
+                    start,
+                    volume_space_size: table.volume_space_size,
+                    block_size: table.logical_block_size,
+                }));
+            }
+            Ok(Some(VolumeDescriptor::VolumeDescriptorSetTerminator)) => return Ok(Probe::Nothing),
+            Ok(_) => {}
+            Err(err) if start == 0 => return Err(err),
+            Err(err) => {
+                debug!("Ignoring unparseable descriptor at sector {lba}: {err}");
+                return Ok(Probe::Nothing);
+            }
+        }
+
+        lba += 1;
+    }
+}
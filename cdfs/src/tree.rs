@@ -0,0 +1,83 @@
+// SPDX-License-Identifier: (MIT OR Apache-2.0)
+
+use crate::{
+    parse::{volume_descriptor::VolumeDescriptorTable, CharacterEncoding},
+    ISO9660Reader, ISODirectory,
+};
+
+/// One of the directory hierarchies that may be recorded on a volume.  Every hierarchy describes
+/// the same files, but with different names and metadata.
+///
+/// # See Also
+/// ISO-9660 / ECMA-119 §§ 8.4, 8.5
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Tree {
+    /// The primary volume descriptor's hierarchy, with ISO 9660 names.  If the hierarchy has Rock
+    /// Ridge entries, its system use fields are ignored.
+    Primary,
+
+    /// The primary volume descriptor's hierarchy, with Rock Ridge names and POSIX metadata.
+    RockRidge,
+
+    /// A Joliet supplementary volume descriptor's hierarchy, with UCS-2 names.  `level` is the
+    /// UCS-2 implementation level from 1 to 3.
+    Joliet {
+        /// The UCS-2 implementation level.
+        level: u8,
+    },
+
+    /// An ISO 9660:1999 enhanced volume descriptor's hierarchy.
+    Enhanced,
+
+    /// Any other supplementary volume descriptor's hierarchy.  `index` is the position of the
+    /// descriptor among the supplementary volume descriptors, counting from 1, as with
+    /// [`ISO9660::root_at()`](crate::ISO9660::root_at).
+    Supplementary {
+        /// The position of the descriptor.
+        index: usize,
+    },
+}
+
+impl Tree {
+    /// Works out which kind of hierarchy the `index`th supplementary volume descriptor describes.
+    pub(crate) fn supplementary(index: usize, table: &VolumeDescriptorTable) -> Self {
+        match table.character_encoding {
+            _ if table.version == 2 => Self::Enhanced,
+            CharacterEncoding::Ucs2Level1 => Self::Joliet { level: 1 },
+            CharacterEncoding::Ucs2Level2 => Self::Joliet { level: 2 },
+            CharacterEncoding::Ucs2Level3 => Self::Joliet { level: 3 },
+            CharacterEncoding::Iso9660 => Self::Supplementary { index },
+        }
+    }
+
+    /// Orders the trees by how much they have to offer when none has been selected: Rock Ridge,
+    /// then the highest level of Joliet, then an enhanced hierarchy.
+    fn preference(&self) -> u8 {
+        match self {
+            Self::RockRidge => 5,
+            Self::Joliet { level } => 1 + level,
+            Self::Enhanced => 1,
+            Self::Primary | Self::Supplementary { .. } => 0,
+        }
+    }
+}
+
+/// A directory hierarchy, with the volume descriptor it's found from.
+pub(crate) struct Hierarchy<T: ISO9660Reader> {
+    pub tree: Tree,
+    pub root: ISODirectory<T>,
+
+    /// 0 for the primary volume descriptor, or the position among the supplementary volume
+    /// descriptors.
+    pub index: usize,
+}
+
+/// Returns the most featureful tree of `hierarchies`.  The first is used if there's a tie.
+pub(crate) fn preferred<T: ISO9660Reader>(hierarchies: &[Hierarchy<T>]) -> Tree {
+    hierarchies
+        .iter()
+        .map(|hierarchy| hierarchy.tree)
+        .rev()
+        .max_by_key(Tree::preference)
+        .unwrap_or(Tree::Primary)
+}
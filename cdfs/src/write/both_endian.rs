@@ -0,0 +1,13 @@
+// SPDX-License-Identifier: (MIT OR Apache-2.0)
+
+// The inverse of `parse::both_endian`: both byte orders are always recorded, little endian first.
+
+pub(crate) fn both_endian16(n: u16) -> [u8; 4] {
+    let (le, be) = (n.to_le_bytes(), n.to_be_bytes());
+    [le[0], le[1], be[0], be[1]]
+}
+
+pub(crate) fn both_endian32(n: u32) -> [u8; 8] {
+    let (le, be) = (n.to_le_bytes(), n.to_be_bytes());
+    [le[0], le[1], le[2], le[3], be[0], be[1], be[2], be[3]]
+}
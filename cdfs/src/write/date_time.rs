@@ -0,0 +1,50 @@
+// SPDX-License-Identifier: (MIT OR Apache-2.0)
+
+use time::{OffsetDateTime, UtcOffset};
+
+/// Encodes the seven byte date and time used in directory records.  Times are recorded in UTC,
+/// and years outside of 1900 - 2155 are clamped.
+///
+/// # See Also
+/// ISO-9660 / ECMA-119 § 9.1.5
+pub(crate) fn date_time(time: OffsetDateTime) -> [u8; 7] {
+    let time = time.to_offset(UtcOffset::UTC);
+    let year = (time.year() - 1900).clamp(0, 255) as u8;
+
+    [
+        year,
+        time.month() as u8,
+        time.day(),
+        time.hour(),
+        time.minute(),
+        time.second(),
+        0,
+    ]
+}
+
+/// Encodes the seventeen byte date and time used in volume descriptors.  `None` is recorded as
+/// "not specified".
+///
+/// # See Also
+/// ISO-9660 / ECMA-119 § 8.4.26.1
+pub(crate) fn date_time_ascii(time: Option<OffsetDateTime>) -> [u8; 17] {
+    let mut bytes = [b'0'; 17];
+    bytes[16] = 0;
+
+    if let Some(time) = time {
+        let time = time.to_offset(UtcOffset::UTC);
+        let digits = format!(
+            "{:04}{:02}{:02}{:02}{:02}{:02}{:02}",
+            time.year().clamp(1, 9999),
+            time.month() as u8,
+            time.day(),
+            time.hour(),
+            time.minute(),
+            time.second(),
+            time.millisecond() / 10,
+        );
+        bytes[..16].copy_from_slice(digits.as_bytes());
+    }
+
+    bytes
+}
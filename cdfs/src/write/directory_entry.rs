@@ -0,0 +1,52 @@
+// SPDX-License-Identifier: (MIT OR Apache-2.0)
+
+use time::OffsetDateTime;
+
+use super::{
+    both_endian::{both_endian16, both_endian32},
+    date_time::date_time,
+};
+use crate::parse::directory_entry::FileFlags;
+
+/// A directory record to be written; the inverse of `parse::directory_entry`.
+///
+/// # See Also
+/// ISO-9660 / ECMA-119 § 9.1
+pub(crate) struct DirectoryRecord<'a> {
+    pub extent_loc: u32,
+    pub extent_length: u32,
+    pub time: OffsetDateTime,
+    pub file_flags: FileFlags,
+    pub identifier: &'a [u8],
+    pub system_use: &'a [u8],
+}
+
+impl DirectoryRecord<'_> {
+    /// The length of the record, including the padding byte following an even length identifier
+    /// and any system use entries.
+    pub fn len(&self) -> usize {
+        let identifier = 33 + self.identifier.len();
+        identifier + (identifier % 2) + self.system_use.len()
+    }
+
+    pub fn write(&self, out: &mut Vec<u8>) {
+        let len = self.len();
+        debug_assert!(len <= usize::from(u8::MAX));
+
+        out.push(len as u8);
+        out.push(0); // extended attribute record length
+        out.extend(both_endian32(self.extent_loc));
+        out.extend(both_endian32(self.extent_length));
+        out.extend(date_time(self.time));
+        out.push(self.file_flags.bits());
+        out.push(0); // file unit size
+        out.push(0); // interleave gap size
+        out.extend(both_endian16(1)); // volume sequence number
+        out.push(self.identifier.len() as u8);
+        out.extend(self.identifier);
+        if self.identifier.len().is_multiple_of(2) {
+            out.push(0);
+        }
+        out.extend(self.system_use);
+    }
+}
@@ -0,0 +1,1261 @@
+// SPDX-License-Identifier: (MIT OR Apache-2.0)
+
+#[allow(unused)]
+use log::{debug, error, info, trace, warn};
+
+use std::{
+    cmp::min,
+    collections::HashSet,
+    fs::{self, File},
+    io::{self, Read, Seek, Write},
+    path::Path,
+};
+
+use time::OffsetDateTime;
+
+mod both_endian;
+mod date_time;
+mod directory_entry;
+mod path_table;
+mod susp;
+mod tree;
+mod volume_descriptor;
+
+use directory_entry::DirectoryRecord;
+use path_table::path_table_record;
+use susp::Continuations;
+use tree::{build_error, Node, NodeKind, Source, Tree};
+use volume_descriptor::{terminator, VolumeDescriptorFields};
+
+use crate::{
+    parse::directory_entry::FileFlags, PathTableType, PosixAttributes, PosixFileMode,
+    PosixTimestamp, Result, BLOCK_SIZE,
+};
+
+const SECTOR_SIZE: usize = BLOCK_SIZE as usize;
+
+/// The largest extent that can be recorded in a single directory record while ending on a block
+/// boundary.  Larger files are recorded in multiple extents.
+const MAX_EXTENT_LENGTH: u64 = u32::MAX as u64 & !(SECTOR_SIZE as u64 - 1);
+
+/// The deepest directory hierarchy permitted by ISO 9660 / ECMA-119.
+const MAX_DEPTH: usize = 8;
+
+/// The longest Joliet identifier, in UCS-2 code units, not counting the version number.
+const MAX_JOLIET_LEN: usize = 64;
+
+/// The file type bits of a mode.
+const FILE_TYPE: u32 = 0o170000;
+
+macro_rules! identifier_setter {
+    ($(#[$attr:meta])* $name:ident) => {
+        $(#[$attr])*
+        pub fn $name(&mut self, identifier: &str) -> &mut Self {
+            self.$name = identifier.to_string();
+            self
+        }
+    };
+}
+
+/// Builds an ISO 9660 / ECMA-119 image from files on disk and in-memory buffers.
+///
+/// Names are mapped onto the restricted ISO 9660 character set: letters are upper cased, other
+/// characters are replaced with `_`, names are truncated to 31 characters, and a number is
+/// appended to any name that would otherwise collide with a sibling.  Files larger than 4 GiB are
+/// recorded in multiple extents.  Paths use `/` as a separator, and missing parent directories
+/// are created as needed.
+///
+/// The original names and POSIX metadata can be preserved by enabling
+/// [Rock Ridge](IsoBuilder::rock_ridge), and long Unicode names for other systems by adding a
+/// [Joliet](IsoBuilder::joliet) tree.
+///
+/// # Example
+///
+/// ```rust
+/// # use std::io::Cursor;
+/// use cdfs::{IsoBuilder, ISO9660};
+///
+/// let mut builder = IsoBuilder::new();
+/// builder.volume_identifier("EXAMPLE");
+/// builder.add_buffer("docs/readme.txt", "Hello, world!")?;
+///
+/// let mut image = Cursor::new(Vec::new());
+/// builder.write(&mut image)?;
+///
+/// let iso = ISO9660::new(image)?;
+/// assert!(iso.open("docs/readme.txt")?.is_some());
+/// # Ok::<(), cdfs::ISOError>(())
+/// ```
+///
+/// # See Also
+/// ISO-9660 / ECMA-119 §§ 6, 8, 9
+pub struct IsoBuilder {
+    tree: Tree,
+    timestamp: OffsetDateTime,
+    rock_ridge: bool,
+    joliet: bool,
+
+    system_identifier: String,
+    volume_identifier: String,
+    volume_set_identifier: String,
+    publisher_identifier: String,
+    data_preparer_identifier: String,
+    application_identifier: String,
+}
+
+impl Default for IsoBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl IsoBuilder {
+    /// Returns a new builder for an empty image.
+    pub fn new() -> Self {
+        IsoBuilder {
+            tree: Tree::new(),
+            timestamp: OffsetDateTime::now_utc(),
+            rock_ridge: false,
+            joliet: false,
+            system_identifier: String::new(),
+            volume_identifier: String::from("CDROM"),
+            volume_set_identifier: String::new(),
+            publisher_identifier: String::new(),
+            data_preparer_identifier: String::new(),
+            application_identifier: String::new(),
+        }
+    }
+
+    identifier_setter! {
+        /// # See Also
+        /// ISO-9660 / ECMA-119 § 8.4.5
+        system_identifier
+    }
+
+    identifier_setter! {
+        /// # See Also
+        /// ISO-9660 / ECMA-119 § 8.4.6
+        volume_identifier
+    }
+
+    identifier_setter! {
+        /// # See Also
+        /// ISO-9660 / ECMA-119 § 8.4.19
+        volume_set_identifier
+    }
+
+    identifier_setter! {
+        /// # See Also
+        /// ISO-9660 / ECMA-119 § 8.4.20
+        publisher_identifier
+    }
+
+    identifier_setter! {
+        /// # See Also
+        /// ISO-9660 / ECMA-119 § 8.4.21
+        data_preparer_identifier
+    }
+
+    identifier_setter! {
+        /// # See Also
+        /// ISO-9660 / ECMA-119 § 8.4.22
+        application_identifier
+    }
+
+    /// Sets the time recorded for the volume, and for entries without a time of their own.
+    /// Defaults to the time the builder was created; set it for reproducible images.
+    pub fn timestamp(&mut self, timestamp: OffsetDateTime) -> &mut Self {
+        self.timestamp = timestamp;
+        self
+    }
+
+    /// Enables or disables recording Rock Ridge entries in the primary tree.  These carry the
+    /// original names, POSIX attributes and timestamps, symbolic links, and device numbers, and
+    /// allow directories to be nested deeper than eight levels.  Disabled by default.
+    ///
+    /// # See Also
+    /// * Rock Ridge Interchange Protocol v1.12
+    /// * System Use Sharing Protocol v1.12
+    pub fn rock_ridge(&mut self, enabled: bool) -> &mut Self {
+        self.rock_ridge = enabled;
+        self
+    }
+
+    /// Enables or disables recording a Joliet tree alongside the primary tree.  Joliet names are
+    /// recorded in UCS-2, preserving case, and may be up to 64 characters long.  Disabled by
+    /// default.
+    ///
+    /// # See Also
+    /// Joliet Specification
+    pub fn joliet(&mut self, enabled: bool) -> &mut Self {
+        self.joliet = enabled;
+        self
+    }
+
+    /// Adds a file with the contents of an in-memory buffer.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ISOError::Build`](crate::ISOError::Build) if something already exists at `path`,
+    /// or one of its parents is a file.
+    pub fn add_buffer(&mut self, path: &str, contents: impl Into<Vec<u8>>) -> Result<()> {
+        let contents = contents.into();
+        let size = contents.len() as u64;
+        self.tree.insert(
+            path,
+            NodeKind::File {
+                source: Source::Buffer(contents),
+                size,
+            },
+        )?;
+        Ok(())
+    }
+
+    /// Adds a file whose contents are read from `source` when the image is written.  Its
+    /// timestamps, and on Unix its mode and ownership, are taken from `source`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an I/O error if `source` can't be inspected, or
+    /// [`ISOError::Build`](crate::ISOError::Build) if something already exists at `path`, or one
+    /// of its parents is a file.
+    pub fn add_file(&mut self, path: &str, source: impl AsRef<Path>) -> Result<()> {
+        let source = source.as_ref();
+        let metadata = fs::metadata(source)?;
+        if !metadata.is_file() {
+            return Err(build_error(path, "Not a regular file"));
+        }
+
+        let node = self.tree.insert(
+            path,
+            NodeKind::File {
+                source: Source::Path(source.to_path_buf()),
+                size: metadata.len(),
+            },
+        )?;
+        self.set_host_metadata(node, &metadata);
+        Ok(())
+    }
+
+    /// Adds an empty directory, along with any missing parents.  Adding a directory that already
+    /// exists is not an error.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ISOError::Build`](crate::ISOError::Build) if `path` or one of its parents is a
+    /// file.
+    pub fn add_directory(&mut self, path: &str) -> Result<()> {
+        self.tree.mkdir_p(path)?;
+        Ok(())
+    }
+
+    /// Adds a symbolic link pointing at `target`.  Without Rock Ridge it's recorded as an empty
+    /// file.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ISOError::Build`](crate::ISOError::Build) if something already exists at `path`,
+    /// or one of its parents is a file.
+    ///
+    /// # See Also
+    /// Rock Ridge Interchange Protocol § 4.1.3
+    pub fn add_symlink(&mut self, path: &str, target: &str) -> Result<()> {
+        self.tree
+            .insert(path, NodeKind::Symlink(target.to_string()))?;
+        Ok(())
+    }
+
+    /// Adds a device, named pipe, or socket.  Only the type bits of `file_type` are used, and
+    /// `device` is only recorded for block and character devices.  Without Rock Ridge it's
+    /// recorded as an empty file.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ISOError::Build`](crate::ISOError::Build) if `file_type` isn't one of the types
+    /// above, something already exists at `path`, or one of its parents is a file.
+    ///
+    /// # See Also
+    /// Rock Ridge Interchange Protocol §§ 4.1.1, 4.1.2
+    pub fn add_special(&mut self, path: &str, file_type: PosixFileMode, device: u64) -> Result<()> {
+        let file_type = self::file_type(file_type);
+        if !is_special(file_type) {
+            return Err(build_error(path, "Not a device, named pipe, or socket"));
+        }
+
+        self.tree
+            .insert(path, NodeKind::Special { file_type, device })?;
+        Ok(())
+    }
+
+    /// Sets the POSIX attributes recorded with Rock Ridge for the entry at `path`.  The file type
+    /// bits of the mode always match the kind of entry, and an inode number is assigned if none
+    /// is given.  Entries default to being owned by root, and readable and searchable by all.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ISOError::Build`](crate::ISOError::Build) if nothing exists at `path`.
+    ///
+    /// # See Also
+    /// Rock Ridge Interchange Protocol § 4.1.1
+    pub fn set_attributes(&mut self, path: &str, attributes: PosixAttributes) -> Result<()> {
+        let node = self.find(path)?;
+        self.tree.nodes[node].attributes = Some(attributes);
+        Ok(())
+    }
+
+    /// Sets the timestamps recorded with Rock Ridge for the entry at `path`.  The modification
+    /// time is also recorded in its directory record; if it's unset, the
+    /// [image's timestamp](IsoBuilder::timestamp) is used.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ISOError::Build`](crate::ISOError::Build) if nothing exists at `path`.
+    ///
+    /// # See Also
+    /// Rock Ridge Interchange Protocol § 4.1.6
+    pub fn set_timestamps(&mut self, path: &str, timestamps: PosixTimestamp) -> Result<()> {
+        let node = self.find(path)?;
+        self.tree.nodes[node].timestamps = timestamps;
+        Ok(())
+    }
+
+    /// Recursively adds the contents of the directory `source` at `path`, along with their
+    /// timestamps, and on Unix their modes, ownership, and device numbers.  Symbolic links are
+    /// added as links rather than followed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an I/O error if `source` can't be read, or
+    /// [`ISOError::Build`](crate::ISOError::Build) if an entry conflicts with one already added.
+    pub fn add_tree(&mut self, path: &str, source: impl AsRef<Path>) -> Result<()> {
+        let source = source.as_ref();
+        let metadata = fs::metadata(source)?;
+        let dir = self.tree.mkdir_p(path)?;
+        self.set_host_metadata(dir, &metadata);
+
+        for entry in fs::read_dir(source)? {
+            let entry = entry?;
+            let name = entry.file_name();
+            let name = match name.to_str() {
+                Some(name) => name,
+                None => {
+                    warn!("Skipping {:?}, its name isn't valid UTF-8", entry.path());
+                    continue;
+                }
+            };
+
+            let child = format!("{}/{name}", path.trim_end_matches('/'));
+            let file_type = entry.file_type()?;
+            if file_type.is_dir() {
+                self.add_tree(&child, entry.path())?;
+            } else if file_type.is_file() {
+                self.add_file(&child, entry.path())?;
+            } else if file_type.is_symlink() {
+                let target = fs::read_link(entry.path())?;
+                let target = match target.to_str() {
+                    Some(target) => target.to_string(),
+                    None => {
+                        warn!("Skipping {:?}, its target isn't valid UTF-8", entry.path());
+                        continue;
+                    }
+                };
+
+                let node = self.tree.insert(&child, NodeKind::Symlink(target))?;
+                self.set_host_metadata(node, &entry.metadata()?);
+            } else {
+                let metadata = entry.metadata()?;
+                match host_special(&metadata) {
+                    Some((file_type, device)) => {
+                        let node = self
+                            .tree
+                            .insert(&child, NodeKind::Special { file_type, device })?;
+                        self.set_host_metadata(node, &metadata);
+                    }
+                    None => warn!("Skipping {:?}, its type isn't supported", entry.path()),
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Lays out the image and writes it to `out`, starting at its current position.
+    ///
+    /// # Errors
+    ///
+    /// Returns an I/O error if writing fails or a source file can't be read, or
+    /// [`ISOError::Build`](crate::ISOError::Build) if a source file changed size after it was
+    /// added or the image is too large to describe.
+    pub fn write<W: Write + Seek>(&self, out: &mut W) -> Result<()> {
+        let mut layouts = vec![Layout::new(&self.tree, Naming::Primary, self.rock_ridge)?];
+        if self.joliet {
+            layouts.push(Layout::new(&self.tree, Naming::Joliet, false)?);
+        }
+
+        let nodes = &self.tree.nodes;
+        let mut extents = layouts
+            .iter()
+            .map(|layout| vec![Allocation::default(); layout.len()])
+            .collect::<Vec<_>>();
+
+        // The system area and volume descriptor set come first.
+        let mut lba = Allocator(16 + layouts.len() as u32 + 1);
+
+        let mut path_tables = Vec::new();
+        for (layout, extents) in layouts.iter().zip(&extents) {
+            let size = layout.path_table(PathTableType::L, extents).len();
+            path_tables.push(PathTables {
+                size: u32::try_from(size)?,
+                loc: lba.take(size as u64)?,
+                loc_be: lba.take(size as u64)?,
+            });
+        }
+
+        // Each directory is followed by the continuation areas of its records, which are sized
+        // while measuring it and filled in while writing it.
+        for (layout, extents) in layouts.iter().zip(&mut extents) {
+            for &dir in &layout.directories {
+                let mut continuations = Continuations::new(0);
+                let length = self
+                    .directory(layout, dir, extents, &mut continuations)
+                    .len() as u64;
+                extents[dir] = Allocation {
+                    lba: lba.take(length)?,
+                    length,
+                };
+                lba.take(continuations.into_sectors().len() as u64)?;
+            }
+        }
+
+        let files = layouts[0].files().collect::<Vec<_>>();
+        for &file in &files {
+            if let NodeKind::File { size, .. } = nodes[file].kind {
+                let allocation = Allocation {
+                    lba: lba.take(size)?,
+                    length: size,
+                };
+                for extents in &mut extents {
+                    extents[file] = allocation;
+                }
+            }
+        }
+
+        let volume_space_size = lba.0;
+        debug!("Image is {volume_space_size} blocks");
+
+        let base = out.stream_position()?;
+        let mut buf = vec![0; 16 * SECTOR_SIZE];
+
+        for ((layout, extents), path_tables) in layouts.iter().zip(&extents).zip(&path_tables) {
+            let mut root = Vec::new();
+            self.record(layout, 0, &[0], extents).write(&mut root);
+            self.volume_descriptor(
+                &mut buf,
+                layout.naming,
+                &root,
+                volume_space_size,
+                path_tables,
+            );
+        }
+        terminator(&mut buf);
+        out.write_all(&buf)?;
+
+        for ((layout, extents), path_tables) in layouts.iter().zip(&extents).zip(&path_tables) {
+            for (lba, table_type) in [
+                (path_tables.loc, PathTableType::L),
+                (path_tables.loc_be, PathTableType::M),
+            ] {
+                pad_to(out, base, lba)?;
+                out.write_all(&layout.path_table(table_type, extents))?;
+            }
+        }
+
+        for (layout, extents) in layouts.iter().zip(&extents) {
+            for &dir in &layout.directories {
+                let Allocation { lba, length } = extents[dir];
+                let mut continuations =
+                    Continuations::new(lba + (length / SECTOR_SIZE as u64) as u32);
+
+                pad_to(out, base, lba)?;
+                out.write_all(&self.directory(layout, dir, extents, &mut continuations))?;
+                out.write_all(&continuations.into_sectors())?;
+            }
+        }
+
+        for &file in &files {
+            if let NodeKind::File { source, size } = &nodes[file].kind {
+                pad_to(out, base, extents[0][file].lba)?;
+                let copied = match source {
+                    Source::Buffer(contents) => {
+                        out.write_all(contents)?;
+                        contents.len() as u64
+                    }
+                    Source::Path(path) => io::copy(&mut File::open(path)?.take(*size), out)?,
+                };
+
+                if copied != *size {
+                    return Err(build_error(&nodes[file].name, "File changed size"));
+                }
+            }
+        }
+
+        pad_to(out, base, volume_space_size)?;
+        out.flush()?;
+        Ok(())
+    }
+
+    fn find(&self, path: &str) -> Result<usize> {
+        self.tree
+            .find(path)?
+            .ok_or_else(|| build_error(path, "No such file or directory"))
+    }
+
+    fn set_host_metadata(&mut self, node: usize, metadata: &fs::Metadata) {
+        let node = &mut self.tree.nodes[node];
+        node.timestamps = PosixTimestamp {
+            modify: metadata.modified().ok().map(OffsetDateTime::from),
+            access: metadata.accessed().ok().map(OffsetDateTime::from),
+            attributes: host_ctime(metadata),
+            ..PosixTimestamp::default()
+        };
+        node.attributes = host_attributes(metadata);
+    }
+
+    /// Appends a primary or Joliet supplementary volume descriptor.
+    fn volume_descriptor(
+        &self,
+        out: &mut Vec<u8>,
+        naming: Naming,
+        root: &[u8],
+        volume_space_size: u32,
+        path_tables: &PathTables,
+    ) {
+        let encode = |s: &str, d: bool, len: usize| match naming {
+            Naming::Primary if d => d_characters(s).into_bytes(),
+            Naming::Primary => a_characters(s).into_bytes(),
+            Naming::Joliet => ucs2(&joliet_characters(s), len / 2),
+        };
+
+        let (type_code, escape_sequences, padding): (u8, &[u8], &[u8]) = match naming {
+            Naming::Primary => (1, &[], b" "),
+            Naming::Joliet => (2, b"%/E", b"\0 "),
+        };
+
+        VolumeDescriptorFields {
+            type_code,
+            escape_sequences,
+            padding,
+            system_identifier: &encode(&self.system_identifier, false, 32),
+            volume_identifier: &encode(&self.volume_identifier, true, 32),
+            volume_space_size,
+            path_table_size: path_tables.size,
+            path_table_loc: path_tables.loc,
+            path_table_loc_be: path_tables.loc_be,
+            root_directory_entry: root,
+            volume_set_identifier: &encode(&self.volume_set_identifier, true, 128),
+            publisher_identifier: &encode(&self.publisher_identifier, false, 128),
+            data_preparer_identifier: &encode(&self.data_preparer_identifier, false, 128),
+            application_identifier: &encode(&self.application_identifier, false, 128),
+            creation_time: self.timestamp,
+        }
+        .write(out);
+    }
+
+    /// Returns the timestamps recorded for `node`, with the modification time filled in.
+    fn timestamps(&self, layout: &Layout<'_>, node: usize) -> PosixTimestamp {
+        let mut timestamps = layout
+            .node(node)
+            .map(|node| node.timestamps.clone())
+            .unwrap_or_default();
+        timestamps.modify.get_or_insert(self.timestamp);
+        timestamps
+    }
+
+    /// Returns the attributes recorded for `node`, with the file type and inode filled in.
+    fn attributes(&self, layout: &Layout<'_>, node: usize) -> PosixAttributes {
+        let (file_type, permissions) = match layout.node(node).map(|node| &node.kind) {
+            Some(NodeKind::File { .. }) => (PosixFileMode::TYPE_FILE, 0o444),
+            Some(NodeKind::Symlink(_)) => (PosixFileMode::TYPE_SYMLINK, 0o777),
+            Some(NodeKind::Special { file_type, .. }) => (*file_type, 0o444),
+            Some(NodeKind::Directory(_)) | None => (PosixFileMode::TYPE_DIRECTORY, 0o555),
+        };
+
+        let mut attributes = layout
+            .node(node)
+            .and_then(|node| node.attributes.clone())
+            .unwrap_or_else(|| PosixAttributes {
+                mode: PosixFileMode::from_bits_truncate(permissions),
+                links: match layout.is_dir(node) {
+                    true => 2 + layout.subdirectories(node) as u32,
+                    false => 1,
+                },
+                uid: 0,
+                gid: 0,
+                inode: None,
+            });
+
+        attributes.mode =
+            PosixFileMode::from_bits_truncate(attributes.mode.bits() & !FILE_TYPE) | file_type;
+        attributes.inode.get_or_insert(node as u32 + 1);
+        attributes
+    }
+
+    /// Returns the Rock Ridge entries of a directory record describing `node`.
+    ///
+    /// # See Also
+    /// Rock Ridge Interchange Protocol § 4
+    fn rock_ridge_entries(
+        &self,
+        layout: &Layout<'_>,
+        node: usize,
+        record: RecordKind,
+        extents: &[Allocation],
+    ) -> Vec<Vec<u8>> {
+        let mut entries = Vec::new();
+        if !layout.rock_ridge {
+            return entries;
+        }
+
+        if let RecordKind::Current = record {
+            if node == 0 {
+                entries.push(susp::sp());
+            }
+        }
+
+        entries.push(susp::px(&self.attributes(layout, node)));
+        entries.push(susp::tf(&self.timestamps(layout, node)));
+
+        match record {
+            RecordKind::Current if node == 0 => entries.push(susp::er()),
+            RecordKind::Current => {}
+            RecordKind::Parent { of } => {
+                if layout.is_relocated(of) {
+                    entries.push(susp::pl(extents[self.tree.nodes[of].parent].lba));
+                }
+            }
+            RecordKind::Child { placeholder } => {
+                entries.extend(susp::nm(layout.name(node)));
+                match layout.node(node).map(|node| &node.kind) {
+                    Some(NodeKind::Symlink(target)) => entries.extend(susp::sl(target)),
+                    Some(NodeKind::Special { file_type, device })
+                        if [PosixFileMode::TYPE_BLOCK_DEV, PosixFileMode::TYPE_CHAR_DEV]
+                            .contains(file_type) =>
+                    {
+                        entries.push(susp::pn(*device));
+                    }
+                    _ => {}
+                }
+
+                if placeholder {
+                    entries.push(susp::cl(extents[node].lba));
+                } else if layout.is_relocated(node) {
+                    entries.push(susp::re());
+                }
+            }
+        }
+
+        entries
+    }
+
+    /// Returns the directory records of `dir`, padded to a whole number of sectors.  Records never
+    /// straddle a sector boundary.
+    fn directory(
+        &self,
+        layout: &Layout<'_>,
+        dir: usize,
+        extents: &[Allocation],
+        continuations: &mut Continuations,
+    ) -> Vec<u8> {
+        let mut out = Vec::new();
+        let mut push = |record: DirectoryRecord, entries: Vec<Vec<u8>>| {
+            let system_use = continuations.system_use(entries, usize::from(u8::MAX) - record.len());
+            let record = DirectoryRecord {
+                system_use: &system_use,
+                ..record
+            };
+
+            let remaining = SECTOR_SIZE - out.len() % SECTOR_SIZE;
+            if record.len() > remaining {
+                out.resize(out.len() + remaining, 0);
+            }
+            record.write(&mut out);
+        };
+
+        let parent = layout.parents[dir];
+        push(
+            self.record(layout, dir, &[0], extents),
+            self.rock_ridge_entries(layout, dir, RecordKind::Current, extents),
+        );
+        push(
+            self.record(layout, parent, &[1], extents),
+            self.rock_ridge_entries(layout, parent, RecordKind::Parent { of: dir }, extents),
+        );
+
+        for Entry { node, identifier } in &layout.children[dir] {
+            let node = *node;
+            let placeholder = layout.parents[node] != dir;
+            let entries =
+                self.rock_ridge_entries(layout, node, RecordKind::Child { placeholder }, extents);
+
+            if placeholder {
+                // A relocated directory is represented by an empty file pointing at it.
+                push(
+                    DirectoryRecord {
+                        extent_length: 0,
+                        file_flags: FileFlags::empty(),
+                        ..self.record(layout, node, identifier, extents)
+                    },
+                    entries,
+                );
+                continue;
+            }
+
+            if layout.is_dir(node) {
+                push(self.record(layout, node, identifier, extents), entries);
+                continue;
+            }
+
+            // Every record but the last of a multi-extent file has the multi-extent flag set.
+            let Allocation { lba, length } = extents[node];
+            let count = length.div_ceil(MAX_EXTENT_LENGTH).max(1);
+            for index in 0..count {
+                let offset = index * MAX_EXTENT_LENGTH;
+                let mut file_flags = FileFlags::empty();
+                if index + 1 < count {
+                    file_flags |= FileFlags::MULTIEXTENT;
+                }
+
+                push(
+                    DirectoryRecord {
+                        extent_loc: lba + (offset / SECTOR_SIZE as u64) as u32,
+                        extent_length: min(length - offset, MAX_EXTENT_LENGTH) as u32,
+                        file_flags,
+                        ..self.record(layout, node, identifier, extents)
+                    },
+                    entries.clone(),
+                );
+            }
+        }
+
+        out.resize(out.len().next_multiple_of(SECTOR_SIZE), 0);
+        out
+    }
+
+    /// Returns the directory record of `node`, without any system use entries.
+    fn record<'a>(
+        &self,
+        layout: &Layout<'_>,
+        node: usize,
+        identifier: &'a [u8],
+        extents: &[Allocation],
+    ) -> DirectoryRecord<'a> {
+        DirectoryRecord {
+            extent_loc: extents[node].lba,
+            extent_length: extents[node].length as u32,
+            time: self.timestamps(layout, node).modify.unwrap(),
+            file_flags: if layout.is_dir(node) {
+                FileFlags::DIRECTORY
+            } else {
+                FileFlags::empty()
+            },
+            identifier,
+            system_use: &[],
+        }
+    }
+}
+
+/// Which directory record of a node is being written.
+#[derive(Clone, Copy)]
+enum RecordKind {
+    /// The `.` record of a directory.
+    Current,
+
+    /// The `..` record of the directory `of`.
+    Parent { of: usize },
+
+    /// A record in the node's parent.  A placeholder stands in for a relocated directory.
+    Child { placeholder: bool },
+}
+
+/// Where a tree's path tables are recorded.
+struct PathTables {
+    size: u32,
+    loc: u32,
+    loc_be: u32,
+}
+
+/// Where a node's data is recorded.
+#[derive(Clone, Copy, Default)]
+struct Allocation {
+    lba: u32,
+    length: u64,
+}
+
+/// Hands out consecutive runs of logical blocks.
+struct Allocator(u32);
+
+impl Allocator {
+    fn take(&mut self, length: u64) -> Result<u32> {
+        let lba = self.0;
+        let blocks = u32::try_from(length.div_ceil(SECTOR_SIZE as u64))
+            .ok()
+            .and_then(|blocks| lba.checked_add(blocks));
+        self.0 = blocks.ok_or_else(|| build_error("/", "Image too large"))?;
+        Ok(lba)
+    }
+}
+
+/// The character set a tree's names are recorded in.
+#[derive(Clone, Copy, PartialEq)]
+enum Naming {
+    Primary,
+    Joliet,
+}
+
+impl Naming {
+    fn identifier(self, name: &str, is_dir: bool, attempt: usize) -> Vec<u8> {
+        match self {
+            Naming::Primary => primary_identifier(name, is_dir, attempt),
+            Naming::Joliet => joliet_identifier(name, is_dir, attempt),
+        }
+    }
+
+    /// The key directory records are sorted by: the name, then the extension, ignoring the
+    /// version.  Joliet names are compared whole.
+    ///
+    /// # See Also
+    /// ISO-9660 / ECMA-119 § 9.3
+    fn sort_key(self, identifier: &[u8]) -> (Vec<u8>, Vec<u8>) {
+        match self {
+            Naming::Primary => {
+                let (stem, extension) = sort_key(identifier);
+                (stem.to_vec(), extension.to_vec())
+            }
+            Naming::Joliet => {
+                let end = identifier
+                    .chunks(2)
+                    .position(|unit| unit == [0, b';'])
+                    .map_or(identifier.len(), |end| end * 2);
+                (identifier[..end].to_vec(), Vec::new())
+            }
+        }
+    }
+}
+
+/// A directory record other than `.` and `..`.
+struct Entry {
+    node: usize,
+    identifier: Vec<u8>,
+}
+
+/// How a tree is recorded: the name of each node, the order of each directory's records, and
+/// the order of the directories themselves.
+///
+/// With Rock Ridge, directories nested deeper than ISO 9660 allows are moved into `RR_MOVED`, a
+/// directory that isn't part of the tree.  It's numbered after every node of the tree, and the
+/// relocated directory's original parent records an empty file in its place.
+struct Layout<'a> {
+    tree: &'a Tree,
+    naming: Naming,
+    rock_ridge: bool,
+
+    /// The recorded identifier of each directory.
+    identifiers: Vec<Vec<u8>>,
+
+    /// The directory each node is recorded in.
+    parents: Vec<usize>,
+
+    /// The records of each directory, in the order they appear.
+    children: Vec<Vec<Entry>>,
+
+    /// Every directory, in path table order.
+    directories: Vec<usize>,
+}
+
+impl<'a> Layout<'a> {
+    fn new(tree: &'a Tree, naming: Naming, rock_ridge: bool) -> Result<Self> {
+        let mut parents = tree
+            .nodes
+            .iter()
+            .map(|node| node.parent)
+            .collect::<Vec<_>>();
+        let mut relocated = Vec::new();
+
+        // Directories are visited breadth first so that the shallowest are relocated first.
+        let mut depths = vec![1; tree.nodes.len()];
+        let mut queue = vec![0];
+        let mut index = 0;
+        while let Some(&dir) = queue.get(index) {
+            index += 1;
+            for &child in tree.children(dir) {
+                if !tree.is_dir(child) {
+                    continue;
+                }
+
+                depths[child] = depths[parents[child]] + 1;
+                if depths[child] > MAX_DEPTH {
+                    if rock_ridge {
+                        relocated.push(child);
+                        depths[child] = 3;
+                    } else if naming == Naming::Primary {
+                        warn!(
+                            "{:?} is nested deeper than 8 levels",
+                            tree.nodes[child].name
+                        );
+                    }
+                }
+                queue.push(child);
+            }
+        }
+
+        let mut members = (0..tree.nodes.len())
+            .map(|dir| tree.children(dir).to_vec())
+            .collect::<Vec<_>>();
+        if !relocated.is_empty() {
+            let moved = parents.len();
+            parents.push(0);
+            for &dir in &relocated {
+                parents[dir] = moved;
+            }
+            members[0].push(moved);
+            members.push(relocated);
+        }
+
+        let mut layout = Layout {
+            tree,
+            naming,
+            rock_ridge,
+            identifiers: vec![Vec::new(); parents.len()],
+            parents,
+            children: Vec::new(),
+            directories: vec![0],
+        };
+        layout.identifiers[0] = vec![0];
+
+        for (dir, mut members) in members.into_iter().enumerate() {
+            // Resolve collisions in a stable order.
+            members.sort_by(|&a, &b| layout.name(a).cmp(layout.name(b)));
+
+            let mut used = HashSet::new();
+            let mut entries = Vec::new();
+            for node in members {
+                let is_dir = layout.is_dir(node);
+                let identifier = (0..)
+                    .map(|attempt| naming.identifier(layout.name(node), is_dir, attempt))
+                    .find(|identifier| used.insert(naming.sort_key(identifier)))
+                    .unwrap();
+
+                if layout.parents[node] == dir {
+                    layout.identifiers[node] = identifier.clone();
+                }
+                entries.push(Entry { node, identifier });
+            }
+
+            entries.sort_by_cached_key(|entry| naming.sort_key(&entry.identifier));
+            layout.children.push(entries);
+        }
+
+        // Directories are numbered breadth first, which with each directory's children sorted
+        // yields the order required of the path table: by level, then parent, then identifier.
+        let mut index = 0;
+        while let Some(&dir) = layout.directories.get(index) {
+            index += 1;
+            for entry in &layout.children[dir] {
+                if layout.is_dir(entry.node) && layout.parents[entry.node] == dir {
+                    layout.directories.push(entry.node);
+                }
+            }
+        }
+
+        if layout.directories.len() > usize::from(u16::MAX) {
+            return Err(build_error("/", "Too many directories"));
+        }
+
+        Ok(layout)
+    }
+
+    /// The number of nodes, including `RR_MOVED`.
+    fn len(&self) -> usize {
+        self.parents.len()
+    }
+
+    /// Returns the node of the tree, or `None` for `RR_MOVED`.
+    fn node(&self, node: usize) -> Option<&'a Node> {
+        self.tree.nodes.get(node)
+    }
+
+    fn name(&self, node: usize) -> &'a str {
+        self.node(node).map_or("rr_moved", |node| &node.name)
+    }
+
+    fn is_dir(&self, node: usize) -> bool {
+        self.node(node).is_none() || self.tree.is_dir(node)
+    }
+
+    /// Returns true if `node` is a directory moved into `RR_MOVED`.
+    fn is_relocated(&self, node: usize) -> bool {
+        self.node(node)
+            .is_some_and(|tree_node| tree_node.parent != self.parents[node])
+    }
+
+    /// Returns the number of directories recorded in `dir`.
+    fn subdirectories(&self, dir: usize) -> usize {
+        self.children[dir]
+            .iter()
+            .filter(|entry| self.is_dir(entry.node) && self.parents[entry.node] == dir)
+            .count()
+    }
+
+    /// Returns every file, in the order their data is recorded.
+    fn files(&self) -> impl Iterator<Item = usize> + '_ {
+        self.directories.iter().flat_map(move |&dir| {
+            self.children[dir]
+                .iter()
+                .map(|entry| entry.node)
+                .filter(move |&node| {
+                    matches!(
+                        self.node(node),
+                        Some(Node {
+                            kind: NodeKind::File { .. },
+                            ..
+                        })
+                    )
+                })
+        })
+    }
+
+    fn path_table(&self, table_type: PathTableType, extents: &[Allocation]) -> Vec<u8> {
+        let mut numbers = vec![0; self.len()];
+        let mut out = Vec::new();
+
+        for (number, &dir) in (1..).zip(&self.directories) {
+            numbers[dir] = number;
+            path_table_record(
+                &mut out,
+                table_type,
+                &self.identifiers[dir],
+                extents[dir].lba,
+                numbers[self.parents[dir]],
+            );
+        }
+
+        out
+    }
+}
+
+/// Maps a name onto the ISO 9660 character set.  `attempt` is appended to names that would
+/// otherwise collide.
+///
+/// # See Also
+/// ISO-9660 / ECMA-119 §§ 7.5, 7.6
+fn primary_identifier(name: &str, is_dir: bool, attempt: usize) -> Vec<u8> {
+    let suffix = match attempt {
+        0 => String::new(),
+        attempt => attempt.to_string(),
+    };
+
+    let (stem, extension) = match name.rsplit_once('.') {
+        Some((stem, extension)) if !is_dir && !stem.is_empty() => {
+            (d_characters(stem), Some(d_characters(extension)))
+        }
+        _ => (d_characters(name), None),
+    };
+
+    let mut extension = extension.unwrap_or_default();
+    let limit = if is_dir {
+        31
+    } else {
+        if stem.len() + extension.len() > 30 {
+            extension.truncate(8);
+        }
+        30 - extension.len()
+    };
+
+    let mut stem = stem;
+    stem.truncate(limit.saturating_sub(suffix.len()));
+    stem += &suffix;
+    if stem.is_empty() && extension.is_empty() {
+        stem.push('_');
+    }
+
+    if is_dir {
+        stem.into_bytes()
+    } else {
+        format!("{stem}.{extension};1").into_bytes()
+    }
+}
+
+/// Maps a name onto a Joliet identifier of at most 64 characters, keeping the extension of files
+/// where possible.  `attempt` is appended to names that would otherwise collide.
+///
+/// # See Also
+/// Joliet Specification § 3.1
+fn joliet_identifier(name: &str, is_dir: bool, attempt: usize) -> Vec<u8> {
+    let name = joliet_characters(name);
+    let suffix = match attempt {
+        0 => String::new(),
+        attempt => attempt.to_string(),
+    };
+
+    let (stem, extension) = match name.rsplit_once('.') {
+        Some((stem, extension))
+            if !is_dir
+                && !stem.is_empty()
+                && extension.encode_utf16().count() + suffix.len() < MAX_JOLIET_LEN / 2 =>
+        {
+            (stem, format!(".{extension}"))
+        }
+        _ => (name.as_str(), String::new()),
+    };
+
+    let limit = MAX_JOLIET_LEN - suffix.len() - extension.encode_utf16().count();
+    let mut identifier = ucs2(stem, limit);
+    identifier.extend(ucs2(&suffix, suffix.len()));
+    identifier.extend(ucs2(&extension, MAX_JOLIET_LEN));
+    if !is_dir {
+        identifier.extend(ucs2(";1", 2));
+    }
+
+    identifier
+}
+
+/// Replaces the characters Joliet doesn't permit with `_`.
+///
+/// # See Also
+/// Joliet Specification § 3.1
+fn joliet_characters(s: &str) -> String {
+    s.chars()
+        .map(|c| match c {
+            '\0'..='\u{1F}' | '*' | '/' | ':' | ';' | '?' | '\\' => '_',
+            c => c,
+        })
+        .collect()
+}
+
+/// Encodes the first `max` UTF-16 code units of a string as big endian, without splitting a
+/// surrogate pair.
+fn ucs2(s: &str, max: usize) -> Vec<u8> {
+    let mut units = Vec::new();
+    for c in s.chars() {
+        let mut buf = [0; 2];
+        let encoded = c.encode_utf16(&mut buf);
+        if units.len() + encoded.len() > max {
+            break;
+        }
+        units.extend_from_slice(encoded);
+    }
+
+    units.into_iter().flat_map(u16::to_be_bytes).collect()
+}
+
+/// The key directory records are sorted by: the name, then the extension, ignoring the version.
+///
+/// # See Also
+/// ISO-9660 / ECMA-119 § 9.3
+fn sort_key(identifier: &[u8]) -> (&[u8], &[u8]) {
+    let identifier = match identifier.iter().position(|&c| c == b';') {
+        Some(end) => &identifier[..end],
+        None => identifier,
+    };
+
+    match identifier.iter().position(|&c| c == b'.') {
+        Some(dot) => (&identifier[..dot], &identifier[dot + 1..]),
+        None => (identifier, &[]),
+    }
+}
+
+/// Upper cases a string and replaces anything other than a d-character with `_`.
+///
+/// # See Also
+/// ISO-9660 / ECMA-119 § 7.4.1
+fn d_characters(s: &str) -> String {
+    s.chars()
+        .map(|c| match c.to_ascii_uppercase() {
+            c @ ('A'..='Z' | '0'..='9' | '_') => c,
+            _ => '_',
+        })
+        .collect()
+}
+
+/// Upper cases a string and replaces anything other than an a-character with `_`.
+///
+/// # See Also
+/// ISO-9660 / ECMA-119 § 7.4.1
+fn a_characters(s: &str) -> String {
+    s.chars()
+        .map(|c| match c.to_ascii_uppercase() {
+            c @ ('A'..='Z' | '0'..='9' | '_' | ' ' | '!' | '"' | '%'..='/' | ':'..='?') => c,
+            _ => '_',
+        })
+        .collect()
+}
+
+/// Returns just the file type bits of a mode.
+fn file_type(mode: PosixFileMode) -> PosixFileMode {
+    PosixFileMode::from_bits_truncate(mode.bits() & FILE_TYPE)
+}
+
+/// Returns true for the file types of devices, named pipes, and sockets.
+fn is_special(file_type: PosixFileMode) -> bool {
+    [
+        PosixFileMode::TYPE_BLOCK_DEV,
+        PosixFileMode::TYPE_CHAR_DEV,
+        PosixFileMode::TYPE_PIPE,
+        PosixFileMode::TYPE_SOCKET,
+    ]
+    .contains(&file_type)
+}
+
+#[cfg(unix)]
+fn host_attributes(metadata: &fs::Metadata) -> Option<PosixAttributes> {
+    use std::os::unix::fs::MetadataExt;
+
+    Some(PosixAttributes {
+        mode: PosixFileMode::from_bits_truncate(metadata.mode()),
+        links: u32::try_from(metadata.nlink()).unwrap_or(u32::MAX),
+        uid: metadata.uid(),
+        gid: metadata.gid(),
+        inode: None,
+    })
+}
+
+#[cfg(not(unix))]
+fn host_attributes(_metadata: &fs::Metadata) -> Option<PosixAttributes> {
+    None
+}
+
+#[cfg(unix)]
+fn host_ctime(metadata: &fs::Metadata) -> Option<OffsetDateTime> {
+    use std::os::unix::fs::MetadataExt;
+
+    OffsetDateTime::from_unix_timestamp(metadata.ctime()).ok()
+}
+
+#[cfg(not(unix))]
+fn host_ctime(_metadata: &fs::Metadata) -> Option<OffsetDateTime> {
+    None
+}
+
+/// Returns the file type and device number of a device, named pipe, or socket.
+#[cfg(unix)]
+fn host_special(metadata: &fs::Metadata) -> Option<(PosixFileMode, u64)> {
+    use std::os::unix::fs::MetadataExt;
+
+    let file_type = file_type(PosixFileMode::from_bits_truncate(metadata.mode()));
+    is_special(file_type).then_some((file_type, metadata.rdev()))
+}
+
+#[cfg(not(unix))]
+fn host_special(_metadata: &fs::Metadata) -> Option<(PosixFileMode, u64)> {
+    None
+}
+
+/// Writes zeros until `out` reaches logical block `lba` of the image starting at `base`.
+fn pad_to<W: Write + Seek>(out: &mut W, base: u64, lba: u32) -> Result<()> {
+    let target = base + u64::from(lba) * SECTOR_SIZE as u64;
+    let pos = out.stream_position()?;
+    debug_assert!(pos <= target, "Overran the allocation for block {lba}");
+
+    io::copy(&mut io::repeat(0).take(target.saturating_sub(pos)), out)?;
+    Ok(())
+}
@@ -0,0 +1,32 @@
+// SPDX-License-Identifier: (MIT OR Apache-2.0)
+
+use crate::PathTableType;
+
+/// Appends a path table record; the inverse of `parse::path_table`.
+///
+/// # See Also
+/// ISO-9660 / ECMA-119 § 9.4
+pub(crate) fn path_table_record(
+    out: &mut Vec<u8>,
+    table_type: PathTableType,
+    identifier: &[u8],
+    extent_loc: u32,
+    parent: u16,
+) {
+    out.push(identifier.len() as u8);
+    out.push(0); // extended attribute record length
+    match table_type {
+        PathTableType::L => {
+            out.extend(extent_loc.to_le_bytes());
+            out.extend(parent.to_le_bytes());
+        }
+        PathTableType::M => {
+            out.extend(extent_loc.to_be_bytes());
+            out.extend(parent.to_be_bytes());
+        }
+    }
+    out.extend(identifier);
+    if identifier.len() % 2 == 1 {
+        out.push(0);
+    }
+}
@@ -0,0 +1,314 @@
+// SPDX-License-Identifier: (MIT OR Apache-2.0)
+
+use super::{both_endian::both_endian32, date_time::date_time};
+use crate::{PosixAttributes, PosixTimestamp, BLOCK_SIZE};
+
+// The inverse of `parse::susp`: each function returns a complete system use entry.
+
+const SECTOR_SIZE: usize = BLOCK_SIZE as usize;
+
+/// The length of a `CE` entry.
+const CE_LEN: usize = 28;
+
+/// The most data a single entry can carry after its signature, length, version, and flags.
+const MAX_PAYLOAD: usize = u8::MAX as usize - 5;
+
+fn entry(signature: &[u8; 2], data: &[u8]) -> Vec<u8> {
+    let len = 4 + data.len();
+    debug_assert!(len <= usize::from(u8::MAX));
+
+    let mut out = Vec::with_capacity(len);
+    out.extend(signature);
+    out.push(len as u8);
+    out.push(1); // version
+    out.extend(data);
+    out
+}
+
+/// # See Also
+/// System Use Sharing Protocol § 5.3
+pub(crate) fn sp() -> Vec<u8> {
+    entry(b"SP", &[0xBE, 0xEF, 0])
+}
+
+/// Registers IEEE P1282 (Rock Ridge v1.12).
+///
+/// # See Also
+/// * System Use Sharing Protocol § 5.5
+/// * Rock Ridge Interchange Protocol § 5
+pub(crate) fn er() -> Vec<u8> {
+    const ID: &[u8] = b"IEEE_P1282";
+    const DESCRIPTION: &[u8] =
+        b"THE IEEE P1282 PROTOCOL PROVIDES SUPPORT FOR POSIX FILE SYSTEM SEMANTICS.";
+    const SOURCE: &[u8] = b"PLEASE CONTACT THE IEEE STANDARDS DEPARTMENT, PISCATAWAY, NJ, USA FOR THE P1282 SPECIFICATION.";
+
+    let mut data = vec![
+        ID.len() as u8,
+        DESCRIPTION.len() as u8,
+        SOURCE.len() as u8,
+        1,
+    ];
+    data.extend(ID);
+    data.extend(DESCRIPTION);
+    data.extend(SOURCE);
+    entry(b"ER", &data)
+}
+
+/// # See Also
+/// System Use Sharing Protocol § 5.1
+fn ce(block_location: u32, offset: u32, length: u32) -> Vec<u8> {
+    let mut data = Vec::with_capacity(CE_LEN - 4);
+    data.extend(both_endian32(block_location));
+    data.extend(both_endian32(offset));
+    data.extend(both_endian32(length));
+    entry(b"CE", &data)
+}
+
+/// # See Also
+/// Rock Ridge Interchange Protocol § 4.1.1
+pub(crate) fn px(attributes: &PosixAttributes) -> Vec<u8> {
+    let mut data = Vec::with_capacity(40);
+    data.extend(both_endian32(attributes.mode.bits()));
+    data.extend(both_endian32(attributes.links));
+    data.extend(both_endian32(attributes.uid));
+    data.extend(both_endian32(attributes.gid));
+    data.extend(both_endian32(attributes.inode.unwrap_or_default()));
+    entry(b"PX", &data)
+}
+
+/// # See Also
+/// Rock Ridge Interchange Protocol § 4.1.2
+pub(crate) fn pn(device: u64) -> Vec<u8> {
+    let mut data = Vec::with_capacity(16);
+    data.extend(both_endian32((device >> 32) as u32));
+    data.extend(both_endian32(device as u32));
+    entry(b"PN", &data)
+}
+
+/// Records the timestamps that are set, using the seven byte form.
+///
+/// # See Also
+/// Rock Ridge Interchange Protocol § 4.1.6
+pub(crate) fn tf(timestamps: &PosixTimestamp) -> Vec<u8> {
+    let mut flags = 0;
+    let mut data = vec![0];
+
+    for (bit, timestamp) in [
+        timestamps.creation,
+        timestamps.modify,
+        timestamps.access,
+        timestamps.attributes,
+        timestamps.backup,
+        timestamps.expiration,
+        timestamps.effective,
+    ]
+    .into_iter()
+    .enumerate()
+    {
+        if let Some(timestamp) = timestamp {
+            flags |= 1 << bit;
+            data.extend(date_time(timestamp));
+        }
+    }
+
+    data[0] = flags;
+    entry(b"TF", &data)
+}
+
+/// Splits a name across as many `NM` entries as it takes.
+///
+/// # See Also
+/// Rock Ridge Interchange Protocol § 4.1.4
+pub(crate) fn nm(name: &str) -> Vec<Vec<u8>> {
+    let chunks = chunks(name, MAX_PAYLOAD);
+    let count = chunks.len();
+
+    (1..)
+        .zip(chunks)
+        .map(|(index, chunk)| {
+            let mut data = vec![u8::from(index < count)]; // CONTINUE
+            data.extend(chunk.as_bytes());
+            entry(b"NM", &data)
+        })
+        .collect()
+}
+
+/// Splits a symbolic link's target into component records, and those across as many `SL` entries
+/// as it takes.
+///
+/// # See Also
+/// Rock Ridge Interchange Protocol § 4.1.3
+pub(crate) fn sl(target: &str) -> Vec<Vec<u8>> {
+    const CONTINUE: u8 = 1 << 0;
+    const CURRENT: u8 = 1 << 1;
+    const PARENT: u8 = 1 << 2;
+    const ROOT: u8 = 1 << 3;
+
+    let mut records = Vec::new();
+    if target.starts_with('/') {
+        records.push(vec![ROOT, 0]);
+    }
+
+    for component in target.split('/').filter(|component| !component.is_empty()) {
+        match component {
+            "." => records.push(vec![CURRENT, 0]),
+            ".." => records.push(vec![PARENT, 0]),
+            _ => {
+                // Component records are limited to a single entry, so long components are
+                // continued in the next record.
+                let chunks = chunks(component, MAX_PAYLOAD - 2);
+                let count = chunks.len();
+                for (index, chunk) in (1..).zip(chunks) {
+                    let mut record = vec![if index < count { CONTINUE } else { 0 }];
+                    record.push(chunk.len() as u8);
+                    record.extend(chunk.as_bytes());
+                    records.push(record);
+                }
+            }
+        }
+    }
+
+    let mut entries: Vec<Vec<u8>> = vec![vec![0]];
+    for record in records {
+        let current = entries.last_mut().unwrap();
+        if current.len() + record.len() > MAX_PAYLOAD + 1 {
+            current[0] = CONTINUE;
+            entries.push(vec![0]);
+        }
+        entries.last_mut().unwrap().extend(record);
+    }
+
+    entries.iter().map(|data| entry(b"SL", data)).collect()
+}
+
+/// # See Also
+/// Rock Ridge Interchange Protocol § 4.1.5.1
+pub(crate) fn cl(lba: u32) -> Vec<u8> {
+    entry(b"CL", &both_endian32(lba))
+}
+
+/// # See Also
+/// Rock Ridge Interchange Protocol § 4.1.5.2
+pub(crate) fn pl(lba: u32) -> Vec<u8> {
+    entry(b"PL", &both_endian32(lba))
+}
+
+/// # See Also
+/// Rock Ridge Interchange Protocol § 4.1.5.3
+pub(crate) fn re() -> Vec<u8> {
+    entry(b"RE", &[])
+}
+
+/// Splits a string into pieces of at most `max` bytes without splitting a character.
+fn chunks(s: &str, max: usize) -> Vec<&str> {
+    let mut chunks = Vec::new();
+    let mut rest = s;
+
+    while rest.len() > max {
+        let end = (0..=max)
+            .rev()
+            .find(|&end| rest.is_char_boundary(end))
+            .unwrap();
+        let (chunk, tail) = rest.split_at(end);
+        chunks.push(chunk);
+        rest = tail;
+    }
+    chunks.push(rest);
+
+    chunks
+}
+
+/// Continuation areas for system use entries that don't fit in their directory records.  Areas
+/// are packed into consecutive sectors starting at `lba`, and never straddle a sector boundary.
+///
+/// # See Also
+/// System Use Sharing Protocol § 5.1
+pub(crate) struct Continuations {
+    lba: u32,
+    data: Vec<u8>,
+}
+
+impl Continuations {
+    pub fn new(lba: u32) -> Self {
+        Continuations {
+            lba,
+            data: Vec::new(),
+        }
+    }
+
+    /// The continuation areas, padded to a whole number of sectors.
+    pub fn into_sectors(mut self) -> Vec<u8> {
+        self.data
+            .resize(self.data.len().next_multiple_of(SECTOR_SIZE), 0);
+        self.data
+    }
+
+    /// Returns the block and offset of a new area of `length` bytes.
+    fn reserve(&mut self, length: usize) -> (u32, usize) {
+        debug_assert!(length <= SECTOR_SIZE);
+        if self.data.len() % SECTOR_SIZE + length > SECTOR_SIZE {
+            self.data
+                .resize(self.data.len().next_multiple_of(SECTOR_SIZE), 0);
+        }
+
+        let start = self.data.len();
+        self.data.resize(start + length, 0);
+        (self.lba + (start / SECTOR_SIZE) as u32, start)
+    }
+
+    /// Returns the system use area of a directory record with room for `available` bytes.
+    /// Entries that don't fit are moved to continuation areas, chained together with `CE`
+    /// entries.
+    pub fn system_use(&mut self, entries: Vec<Vec<u8>>, available: usize) -> Vec<u8> {
+        // Each group but the last ends with a `CE` entry pointing at the next.
+        let mut groups = vec![Vec::new()];
+        let mut capacity = available;
+        let mut remaining = entries.iter().map(Vec::len).sum::<usize>();
+        let mut used = 0;
+
+        for entry in entries {
+            if remaining > capacity - used && used + entry.len() > capacity - CE_LEN {
+                groups.push(Vec::new());
+                capacity = SECTOR_SIZE;
+                used = 0;
+            }
+
+            used += entry.len();
+            remaining -= entry.len();
+            groups.last_mut().unwrap().push(entry);
+        }
+
+        let lengths = groups
+            .iter()
+            .enumerate()
+            .map(|(index, group)| {
+                let ce = if index + 1 < groups.len() { CE_LEN } else { 0 };
+                group.iter().map(Vec::len).sum::<usize>() + ce
+            })
+            .collect::<Vec<_>>();
+
+        let areas = lengths[1..]
+            .iter()
+            .map(|&length| self.reserve(length))
+            .collect::<Vec<_>>();
+
+        let mut record = Vec::new();
+        for (index, group) in groups.into_iter().enumerate() {
+            let mut out = group.concat();
+            if let Some(&(lba, start)) = areas.get(index) {
+                let offset = (start % SECTOR_SIZE) as u32;
+                out.extend(ce(lba, offset, lengths[index + 1] as u32));
+            }
+
+            match index {
+                0 => record = out,
+                _ => {
+                    let start = areas[index - 1].1;
+                    self.data[start..start + out.len()].copy_from_slice(&out);
+                }
+            }
+        }
+
+        record
+    }
+}
@@ -0,0 +1,165 @@
+// SPDX-License-Identifier: (MIT OR Apache-2.0)
+
+use std::path::PathBuf;
+
+use crate::{ISOError, PosixAttributes, PosixFileMode, PosixTimestamp, Result};
+
+/// Where the contents of a file come from.
+pub(crate) enum Source {
+    Buffer(Vec<u8>),
+    Path(PathBuf),
+}
+
+pub(crate) enum NodeKind {
+    Directory(Vec<usize>),
+    File {
+        source: Source,
+        size: u64,
+    },
+    Symlink(String),
+    /// A device, named pipe, or socket; `file_type` holds the type bits of its mode.
+    Special {
+        file_type: PosixFileMode,
+        device: u64,
+    },
+}
+
+/// A file or directory to be recorded.  Nodes refer to each other by their index in
+/// [`Tree::nodes`]; the root directory is node 0 and is its own parent.
+pub(crate) struct Node {
+    /// The name as given, before it's mapped onto any particular character set.
+    pub name: String,
+    pub parent: usize,
+    /// Unset times are recorded as the time the image is written.
+    pub timestamps: PosixTimestamp,
+    /// `None` means the defaults are recorded.  The type bits of the mode are ignored.
+    pub attributes: Option<PosixAttributes>,
+    pub kind: NodeKind,
+}
+
+pub(crate) struct Tree {
+    pub nodes: Vec<Node>,
+}
+
+impl Tree {
+    pub fn new() -> Self {
+        Tree {
+            nodes: vec![Node {
+                name: String::new(),
+                parent: 0,
+                timestamps: PosixTimestamp::default(),
+                attributes: None,
+                kind: NodeKind::Directory(Vec::new()),
+            }],
+        }
+    }
+
+    pub fn children(&self, node: usize) -> &[usize] {
+        match &self.nodes[node].kind {
+            NodeKind::Directory(children) => children,
+            _ => &[],
+        }
+    }
+
+    pub fn is_dir(&self, node: usize) -> bool {
+        matches!(self.nodes[node].kind, NodeKind::Directory(_))
+    }
+
+    fn child(&self, dir: usize, name: &str) -> Option<usize> {
+        self.children(dir)
+            .iter()
+            .copied()
+            .find(|&child| self.nodes[child].name == name)
+    }
+
+    /// Returns the directory at `path`, creating it and any missing parents.
+    pub fn mkdir_p(&mut self, path: &str) -> Result<usize> {
+        let mut dir = 0;
+
+        for segment in segments(path)? {
+            dir = match self.child(dir, segment) {
+                Some(child) if self.is_dir(child) => child,
+                Some(_) => return Err(build_error(path, "Not a directory")),
+                None => self.push(
+                    dir,
+                    Node {
+                        name: segment.to_string(),
+                        parent: dir,
+                        timestamps: PosixTimestamp::default(),
+                        attributes: None,
+                        kind: NodeKind::Directory(Vec::new()),
+                    },
+                ),
+            };
+        }
+
+        Ok(dir)
+    }
+
+    /// Adds anything but a directory at `path`, creating any missing parent directories.
+    pub fn insert(&mut self, path: &str, kind: NodeKind) -> Result<usize> {
+        let (parent, name) = match path.trim_end_matches('/').rsplit_once('/') {
+            Some((parent, name)) => (parent, name),
+            None => ("", path),
+        };
+
+        if segments(name)?.count() != 1 {
+            return Err(build_error(path, "Invalid file name"));
+        }
+
+        let dir = self.mkdir_p(parent)?;
+        if self.child(dir, name).is_some() {
+            return Err(build_error(path, "Already exists"));
+        }
+
+        Ok(self.push(
+            dir,
+            Node {
+                name: name.to_string(),
+                parent: dir,
+                timestamps: PosixTimestamp::default(),
+                attributes: None,
+                kind,
+            },
+        ))
+    }
+
+    /// Returns the node at `path`, if there is one.
+    pub fn find(&self, path: &str) -> Result<Option<usize>> {
+        let mut node = 0;
+        for segment in segments(path)? {
+            node = match self.child(node, segment) {
+                Some(child) => child,
+                None => return Ok(None),
+            };
+        }
+
+        Ok(Some(node))
+    }
+
+    fn push(&mut self, parent: usize, node: Node) -> usize {
+        let index = self.nodes.len();
+        self.nodes.push(node);
+        if let NodeKind::Directory(children) = &mut self.nodes[parent].kind {
+            children.push(index);
+        }
+        index
+    }
+}
+
+/// Splits a path into its segments, rejecting `.` and `..`.
+fn segments(path: &str) -> Result<impl Iterator<Item = &str>> {
+    let mut segments = path.split('/').filter(|segment| !segment.is_empty());
+    if segments.any(|segment| segment == "." || segment == "..") {
+        return Err(build_error(path, "Relative path segments aren't allowed"));
+    }
+
+    Ok(path.split('/').filter(|segment| !segment.is_empty()))
+}
+
+pub(crate) fn build_error(path: &str, reason: &'static str) -> ISOError {
+    ISOError::Build {
+        path: path.to_string(),
+        reason,
+    }
+}
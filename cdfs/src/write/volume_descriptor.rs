@@ -0,0 +1,92 @@
+// SPDX-License-Identifier: (MIT OR Apache-2.0)
+
+use time::OffsetDateTime;
+
+use super::{
+    both_endian::{both_endian16, both_endian32},
+    date_time::date_time_ascii,
+};
+use crate::BLOCK_SIZE;
+
+/// The fields of a primary or supplementary volume descriptor to be written; the inverse of
+/// `parse::volume_descriptor`.  Identifiers are already encoded, and are padded with `padding`.
+///
+/// # See Also
+/// ISO-9660 / ECMA-119 §§ 8.4, 8.5
+pub(crate) struct VolumeDescriptorFields<'a> {
+    pub type_code: u8,
+    pub escape_sequences: &'a [u8],
+    pub padding: &'a [u8],
+
+    pub system_identifier: &'a [u8],
+    pub volume_identifier: &'a [u8],
+    pub volume_space_size: u32,
+
+    pub path_table_size: u32,
+    pub path_table_loc: u32,
+    pub path_table_loc_be: u32,
+
+    pub root_directory_entry: &'a [u8],
+
+    pub volume_set_identifier: &'a [u8],
+    pub publisher_identifier: &'a [u8],
+    pub data_preparer_identifier: &'a [u8],
+    pub application_identifier: &'a [u8],
+
+    pub creation_time: OffsetDateTime,
+}
+
+impl VolumeDescriptorFields<'_> {
+    pub fn write(&self, out: &mut Vec<u8>) {
+        let start = out.len();
+        let field = |out: &mut Vec<u8>, value: &[u8], len: usize| {
+            let value = &value[..value.len().min(len)];
+            out.extend(value);
+            out.extend(self.padding.iter().cycle().take(len - value.len()));
+        };
+
+        out.push(self.type_code);
+        out.extend(b"CD001\x01");
+        out.push(0); // volume flags
+        field(out, self.system_identifier, 32);
+        field(out, self.volume_identifier, 32);
+        out.extend([0; 8]);
+        out.extend(both_endian32(self.volume_space_size));
+        out.extend(self.escape_sequences);
+        out.extend(std::iter::repeat_n(0, 32 - self.escape_sequences.len()));
+        out.extend(both_endian16(1)); // volume set size
+        out.extend(both_endian16(1)); // volume sequence number
+        out.extend(both_endian16(BLOCK_SIZE));
+        out.extend(both_endian32(self.path_table_size));
+        out.extend(self.path_table_loc.to_le_bytes());
+        out.extend(0u32.to_le_bytes()); // optional type L path table
+        out.extend(self.path_table_loc_be.to_be_bytes());
+        out.extend(0u32.to_be_bytes()); // optional type M path table
+        out.extend(self.root_directory_entry);
+        field(out, self.volume_set_identifier, 128);
+        field(out, self.publisher_identifier, 128);
+        field(out, self.data_preparer_identifier, 128);
+        field(out, self.application_identifier, 128);
+        field(out, b"", 37); // copyright file identifier
+        field(out, b"", 37); // abstract file identifier
+        field(out, b"", 37); // bibliographic file identifier
+        out.extend(date_time_ascii(Some(self.creation_time)));
+        out.extend(date_time_ascii(Some(self.creation_time)));
+        out.extend(date_time_ascii(None)); // expiration
+        out.extend(date_time_ascii(None)); // effective
+        out.push(1); // file structure version
+
+        out.resize(start + usize::from(BLOCK_SIZE), 0);
+    }
+}
+
+/// Appends a volume descriptor set terminator.
+///
+/// # See Also
+/// ISO-9660 / ECMA-119 § 8.3
+pub(crate) fn terminator(out: &mut Vec<u8>) {
+    let start = out.len();
+    out.push(255);
+    out.extend(b"CD001\x01");
+    out.resize(start + usize::from(BLOCK_SIZE), 0);
+}
@@ -0,0 +1,131 @@
+// SPDX-License-Identifier: (MIT OR Apache-2.0)
+
+use std::io::Cursor;
+
+use cdfs::{Acl, AclEntry, AclPermissions, AclTag, ExtraAttributes, SuspExtension, ISO9660};
+
+mod common;
+use common::{ce, entry, er, file_record, open_file, Image, SP};
+
+fn al(should_continue: bool, components: &[u8]) -> Vec<u8> {
+    entry(b"AL", &[&[u8::from(should_continue)], components].concat())
+}
+
+/// A name or value as a single component.
+fn field(data: &[u8]) -> Vec<u8> {
+    component(false, data)
+}
+
+fn component(should_continue: bool, data: &[u8]) -> Vec<u8> {
+    [&[u8::from(should_continue), data.len() as u8], data].concat()
+}
+
+fn long_value() -> Vec<u8> {
+    (0..300).map(|n| b'a' + (n % 26) as u8).collect()
+}
+
+fn acl_value() -> Vec<u8> {
+    [
+        &[1 << 4 | 6][..],                      // user::rw-
+        &[10 << 4 | 8 | 5, 2, 0x03, 0xE8],      // user:1000:r-x
+        &[2 << 4 | 8 | 4, 3, b'b', b'o', b'b'], // user:bob:r--
+        &[3 << 4 | 4],                          // group::r--
+        &[5 << 4 | 7],                          // mask::rwx
+        &[6 << 4 | 4],                          // other::r--
+        &[8 << 4],                              // default:
+        &[1 << 4 | 7],                          // user::rwx
+        &[12 << 4 | 8 | 5, 1, 100],             // group:100:r-x
+        &[6 << 4],                              // other::---
+    ]
+    .concat()
+}
+
+fn build_image() -> Vec<u8> {
+    let dot_system_use = [SP.to_vec(), er(b"AAIP_0200"), er(b"SOMETHING_ELSE")].concat();
+
+    // The attributes don't fit in the directory record, and the long value is continued across
+    // components in separate `AL` entries.
+    let long = long_value();
+    let attributes = [
+        al(
+            true,
+            &[field(b"\x03comment"), field(b"hello"), field(b"\x03long")].concat(),
+        ),
+        al(true, &component(true, &long[..200])),
+        al(
+            false,
+            &[
+                component(false, &long[200..]),
+                field(b""),
+                field(&acl_value()),
+            ]
+            .concat(),
+        ),
+    ]
+    .concat();
+
+    Image::new()
+        .dot_system_use(&dot_system_use)
+        .record(file_record(b"FILE.TXT;1", &ce(21, attributes.len() as u32)))
+        .sector(b"hello") // 20
+        .sector(&attributes) // 21
+        .build()
+}
+
+#[test]
+fn aaip_extensions() {
+    let fs = ISO9660::new(Cursor::new(build_image())).unwrap();
+
+    let dot = fs.root().contents().next().unwrap().unwrap();
+    let extensions = &dot.ext().extensions;
+    assert!(extensions.contains(&SuspExtension::Aaip));
+    assert!(extensions.contains(&SuspExtension::Other {
+        identifier: "SOMETHING_ELSE".to_string(),
+        version: 1,
+    }));
+}
+
+#[test]
+fn aaip_attributes() {
+    let fs = ISO9660::new(Cursor::new(build_image())).unwrap();
+
+    let file = open_file(&fs, "file.txt");
+
+    let xattrs = file.xattrs();
+    assert_eq!(
+        xattrs.keys().collect::<Vec<_>>(),
+        ["user.comment", "user.long"]
+    );
+    assert_eq!(xattrs["user.comment"], b"hello");
+    assert_eq!(xattrs["user.long"], long_value());
+}
+
+#[test]
+fn aaip_acl() {
+    let fs = ISO9660::new(Cursor::new(build_image())).unwrap();
+
+    let file = fs.open("file.txt").unwrap().unwrap();
+
+    let entry = |tag, permissions| AclEntry { tag, permissions };
+    let rwx = AclPermissions::all();
+    let r = AclPermissions::READ;
+    let rx = AclPermissions::READ | AclPermissions::EXECUTE;
+    assert_eq!(
+        file.acl(),
+        Some(&Acl {
+            access: vec![
+                entry(AclTag::UserObj, r | AclPermissions::WRITE),
+                entry(AclTag::User(1000), rx),
+                entry(AclTag::UserName("bob".to_string()), r),
+                entry(AclTag::GroupObj, r),
+                entry(AclTag::Mask, rwx),
+                entry(AclTag::Other, r),
+            ],
+            default: vec![
+                entry(AclTag::UserObj, rwx),
+                entry(AclTag::Group(100), rx),
+                entry(AclTag::Other, AclPermissions::empty()),
+            ],
+        })
+    );
+}
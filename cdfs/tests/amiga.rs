@@ -0,0 +1,86 @@
+// SPDX-License-Identifier: (MIT OR Apache-2.0)
+
+use std::io::Cursor;
+
+use cdfs::{AmigaInfo, AmigaProtection, ExtraAttributes, ISO9660};
+
+mod common;
+use common::{entry, file_record, Image, SP};
+
+const PROTECTION: u8 = 1 << 0;
+const COMMENT: u8 = 1 << 1;
+const COMMENT_CONTINUE: u8 = 1 << 2;
+
+fn as_entry(data: &[u8]) -> Vec<u8> {
+    entry(b"AS", data)
+}
+
+fn comment(flags: u8, text: &[u8]) -> Vec<u8> {
+    as_entry(&[&[flags, 1 + text.len() as u8], text].concat())
+}
+
+fn build_image() -> Vec<u8> {
+    let commented = [
+        as_entry(&[
+            PROTECTION | COMMENT | COMMENT_CONTINUE,
+            0,
+            0,
+            0b1010_0000,
+            0b0100_0101,
+            7,
+            b'G',
+            b'r',
+            0xFC, // ü
+            0xDF, // ß
+            b'e',
+            b',',
+        ]),
+        comment(COMMENT, b" Welt"),
+    ]
+    .concat();
+
+    Image::new()
+        .dot_system_use(&SP)
+        .record(file_record(b"COMMENT.;1", &commented))
+        .record(file_record(
+            b"PLAIN.;1",
+            &as_entry(&[PROTECTION, 0, 0, 0, 0b0001_0000]),
+        ))
+        .sector(b"hello") // 20
+        .build()
+}
+
+#[test]
+fn amiga_comment() {
+    let fs = ISO9660::new(Cursor::new(build_image())).unwrap();
+
+    let file = fs.open("comment").unwrap().unwrap();
+    assert_eq!(
+        file.amiga_info(),
+        Some(&AmigaInfo {
+            protection: Some(
+                AmigaProtection::DELETE
+                    | AmigaProtection::WRITE
+                    | AmigaProtection::SCRIPT
+                    | AmigaProtection::OTHER_EXECUTE
+                    | AmigaProtection::OTHER_READ
+            ),
+            comment: Some("Grüße, Welt".to_string()),
+        })
+    );
+}
+
+#[test]
+fn amiga_protection() {
+    let fs = ISO9660::new(Cursor::new(build_image())).unwrap();
+
+    let file = fs.open("plain").unwrap().unwrap();
+    assert_eq!(
+        file.amiga_info(),
+        Some(&AmigaInfo {
+            protection: Some(AmigaProtection::ARCHIVE),
+            comment: None,
+        })
+    );
+    assert!(fs.root().amiga_info().is_none());
+}
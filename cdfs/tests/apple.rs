@@ -0,0 +1,147 @@
+// SPDX-License-Identifier: (MIT OR Apache-2.0)
+
+use std::io::{Cursor, Read};
+
+use cdfs::{Anomaly, AppleInfo, ExtraAttributes, FinderFlags, OpenOptions, ParseMode, ISO9660};
+
+mod common;
+use common::{
+    collect_filenames, directory_record_with_system_use, file_record, open, open_file, Image,
+};
+
+const ASSOCIATED_FILE: u8 = 1 << 2;
+
+fn hfs(signature: &[u8; 2], file_type: &[u8; 4], creator: &[u8; 4], flags: u16) -> Vec<u8> {
+    [
+        &signature[..],
+        &[14, 2],
+        file_type,
+        creator,
+        &flags.to_be_bytes(),
+    ]
+    .concat()
+}
+
+fn prodos(file_type: u8, aux_type: u16) -> Vec<u8> {
+    [&b"AA"[..], &[7, 1, file_type], &aux_type.to_be_bytes()].concat()
+}
+
+/// A plain ISO 9660 volume, without SUSP, as written for Macs.  `README` has a resource fork,
+/// while `ORPHAN` is an associated file with nothing to be associated with.
+fn build_image() -> Vec<u8> {
+    let finder_info = hfs(b"AA", b"TEXT", b"ttxt", 0x2100);
+    Image::new()
+        .record(directory_record_with_system_use(
+            22,
+            6,
+            ASSOCIATED_FILE,
+            b"ORPHAN.;1",
+            &[],
+        ))
+        .record(directory_record_with_system_use(
+            21,
+            4,
+            ASSOCIATED_FILE,
+            b"README.;1",
+            &finder_info,
+        ))
+        .record(file_record(b"README.;1", &finder_info))
+        .record(file_record(b"OLD.;1", &hfs(b"BA", b"APPL", b"MACS", 0)))
+        .record(file_record(b"PRODOS.;1", &prodos(0x06, 0x2000)))
+        .sector(b"hello") // 20
+        .sector(b"RSRC") // 21
+        .sector(b"orphan") // 22
+        .build()
+}
+
+#[test]
+fn apple_finder_info() {
+    let fs = ISO9660::new(Cursor::new(build_image())).unwrap();
+
+    assert_eq!(
+        open_file(&fs, "readme").apple_info(),
+        Some(&AppleInfo::Hfs {
+            file_type: *b"TEXT",
+            creator: *b"ttxt",
+            finder_flags: FinderFlags::HAS_BUNDLE | FinderFlags::HAS_BEEN_INITED,
+        })
+    );
+    assert_eq!(
+        open_file(&fs, "old").apple_info(),
+        Some(&AppleInfo::Hfs {
+            file_type: *b"APPL",
+            creator: *b"MACS",
+            finder_flags: FinderFlags::empty(),
+        })
+    );
+    assert_eq!(
+        open_file(&fs, "prodos").apple_info(),
+        Some(&AppleInfo::ProDos {
+            file_type: 0x06,
+            aux_type: 0x2000,
+        })
+    );
+}
+
+#[test]
+fn apple_resource_fork() {
+    let fs = ISO9660::new(Cursor::new(build_image())).unwrap();
+
+    let readme = open_file(&fs, "readme");
+    let mut data = String::new();
+    readme.read().read_to_string(&mut data).unwrap();
+    assert_eq!(data, "hello");
+
+    let mut fork = String::new();
+    readme
+        .resource_fork()
+        .unwrap()
+        .read_to_string(&mut fork)
+        .unwrap();
+    assert_eq!(fork, "RSRC");
+
+    assert!(open_file(&fs, "old").resource_fork().is_none());
+}
+
+#[test]
+fn apple_associated_files_listed_once() {
+    let fs = ISO9660::new(Cursor::new(build_image())).unwrap();
+
+    assert_eq!(
+        collect_filenames(fs.root()),
+        [".", "..", "ORPHAN", "README", "OLD", "PRODOS"]
+    );
+    assert!(fs.open("orphan").unwrap().is_none());
+}
+
+#[test]
+fn apple_associated_file_before_corrupt_record() {
+    // The record following the associated file has a version number that isn't one.
+    let image = Image::new()
+        .record(directory_record_with_system_use(
+            21,
+            4,
+            ASSOCIATED_FILE,
+            b"BROKEN.;1",
+            &[],
+        ))
+        .record(file_record(b"BROKEN.;X", &[]))
+        .sector(b"hello") // 20
+        .sector(b"RSRC") // 21
+        .build();
+
+    let fs = open(
+        image.clone(),
+        OpenOptions::new().parse_mode(ParseMode::Strict),
+    );
+    let mut contents = fs.root().contents().skip(2);
+    assert!(contents.next().unwrap().is_err());
+    assert!(contents.next().is_none());
+
+    let fs = open(image, &OpenOptions::new());
+    let mut contents = fs.root().contents().skip(2);
+    assert_eq!(contents.next().unwrap().unwrap().identifier(), "BROKEN");
+    assert_eq!(fs.warnings(), [Anomaly::UnpairedAssociatedFile]);
+    assert!(contents.next().unwrap().is_err());
+    assert!(contents.next().is_none());
+}
@@ -0,0 +1,95 @@
+// SPDX-License-Identifier: (MIT OR Apache-2.0)
+
+use std::io::{Cursor, Read};
+
+use cdfs::{ISOError, ISO9660};
+
+mod common;
+use common::{directory_record, open_file, primary_descriptor, terminator, SECTOR_SIZE};
+
+/// Builds an image with a single root directory containing `HELLO.TXT`.
+fn build_image(block_size: u16, contents: &[u8]) -> Vec<u8> {
+    let blksize = usize::from(block_size);
+    let blocks_per_sector = (SECTOR_SIZE / blksize) as u32;
+
+    // System area + primary descriptor + terminator, then the root directory and the file.
+    let root_lba = 18 * blocks_per_sector;
+    let file_lba = root_lba + 1;
+    let file_blocks = contents.len().div_ceil(blksize) as u32;
+
+    let root = directory_record(root_lba, u32::from(block_size), 2, &[0]);
+
+    let mut image = vec![0; 16 * SECTOR_SIZE];
+    image.extend(primary_descriptor(
+        block_size,
+        file_lba + file_blocks,
+        &root,
+    ));
+
+    image.extend(terminator());
+
+    let mut directory = root.clone();
+    directory.extend(directory_record(root_lba, u32::from(block_size), 2, &[1]));
+    directory.extend(directory_record(
+        file_lba,
+        contents.len() as u32,
+        0,
+        b"HELLO.TXT;1",
+    ));
+    directory.resize(blksize, 0);
+    image.extend(directory);
+
+    image.extend(contents);
+    image.resize(image.len().next_multiple_of(SECTOR_SIZE), 0);
+    image
+}
+
+fn check_block_size(block_size: u16) {
+    let contents = (0..3000).map(|i| (i % 251) as u8).collect::<Vec<_>>();
+    let fs = ISO9660::new(Cursor::new(build_image(block_size, &contents))).unwrap();
+
+    assert_eq!(fs.block_size(), block_size);
+    assert_eq!(fs.root().block_size(), block_size);
+    assert_eq!(fs.root().block_count(), 1);
+
+    let names = fs
+        .root()
+        .contents()
+        .map(|entry| entry.unwrap().identifier().to_string())
+        .collect::<Vec<_>>();
+    assert_eq!(names, &[".", "..", "HELLO.TXT"]);
+
+    let file = open_file(&fs, "hello.txt");
+
+    let mut read = Vec::new();
+    file.read().read_to_end(&mut read).unwrap();
+    assert_eq!(read, contents);
+}
+
+#[test]
+fn block_size_512() {
+    check_block_size(512);
+}
+
+#[test]
+fn block_size_1024() {
+    check_block_size(1024);
+}
+
+#[test]
+fn block_size_2048() {
+    check_block_size(2048);
+}
+
+#[test]
+fn block_size_invalid() {
+    let mut image = build_image(2048, b"hello");
+    // Patch the logical block size in the primary volume descriptor to 1000.
+    let offset = 16 * SECTOR_SIZE + 128;
+    image[offset..offset + 4].copy_from_slice(&[0xE8, 0x03, 0x03, 0xE8]);
+
+    assert!(matches!(
+        ISO9660::new(Cursor::new(image)),
+        Err(ISOError::InvalidFs(_))
+    ));
+}
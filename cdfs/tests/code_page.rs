@@ -0,0 +1,87 @@
+// SPDX-License-Identifier: (MIT OR Apache-2.0)
+
+use cdfs::{DirectoryEntry, OpenOptions, ParseMode};
+
+mod common;
+use common::{directory_record_with_system_use, entries, entry, nm, open, px, Image, SP};
+
+/// A root directory holding a single empty file, `identifier`, with the given system use field.
+fn build_image(identifier: &[u8], system_use: &[u8]) -> Vec<u8> {
+    Image::new()
+        .dot_system_use(&SP)
+        .record(directory_record_with_system_use(
+            19, 0, 0, identifier, system_use,
+        ))
+        .build()
+}
+
+#[test]
+fn code_page_primary() {
+    // "CAFÉ.TXT" in Latin-1.
+    let image = build_image(b"CAF\xC9.TXT;1", &[]);
+
+    let fs = open(image.clone(), &OpenOptions::new());
+    let entries_utf8 = entries(&fs).unwrap();
+    assert_eq!(entries_utf8[0].identifier(), "CAF\u{FFFD}.TXT");
+    assert_eq!(entries_utf8[0].raw_identifier(), b"CAF\xC9.TXT;1");
+
+    let fs = open(
+        image.clone(),
+        OpenOptions::new().parse_mode(ParseMode::Strict),
+    );
+    assert!(entries(&fs).is_err());
+
+    let fs = open(
+        image,
+        OpenOptions::new()
+            .parse_mode(ParseMode::Strict)
+            .code_page(cdfs::encoding_rs::WINDOWS_1252),
+    );
+    let entries = entries(&fs).unwrap();
+    assert_eq!(entries[0].identifier(), "CAFÉ.TXT");
+    assert_eq!(entries[0].raw_identifier(), b"CAF\xC9.TXT;1");
+}
+
+#[test]
+fn code_page_rock_ridge() {
+    // "日本.txt" in Shift-JIS, with the first character split across two `NM` entries.
+    let name = b"\x93\xfa\x96\x7b.txt";
+    let system_use = [entry(b"NM", &[&[1], &name[..1]].concat()), nm(&name[1..])].concat();
+    let image = build_image(b"NIHON.TXT;1", &system_use);
+
+    let fs = open(
+        image.clone(),
+        OpenOptions::new().code_page(cdfs::encoding_rs::SHIFT_JIS),
+    );
+    let entries_sjis = entries(&fs).unwrap();
+    assert_eq!(entries_sjis[0].identifier(), "日本.txt");
+    assert_eq!(entries_sjis[0].raw_identifier(), name);
+
+    let fs = open(image, OpenOptions::new().parse_mode(ParseMode::Strict));
+    assert!(entries(&fs).is_err());
+}
+
+#[test]
+fn code_page_symlink() {
+    // "../日本" in Shift-JIS, with the second character split across two components.
+    let system_use = [
+        px(0o120777),
+        entry(b"SL", b"\x01\x04\x00\x01\x03\x93\xfa\x96"),
+        entry(b"SL", b"\x00\x00\x01\x7b"),
+    ]
+    .concat();
+    let image = build_image(b"LINK;1", &system_use);
+
+    let fs = open(
+        image.clone(),
+        OpenOptions::new().code_page(cdfs::encoding_rs::SHIFT_JIS),
+    );
+    let entries_sjis = entries(&fs).unwrap();
+    let DirectoryEntry::Symlink(link) = &entries_sjis[0] else {
+        panic!("expected a symbolic link");
+    };
+    assert_eq!(link.target().map(String::as_str), Some("../日本"));
+
+    let fs = open(image, OpenOptions::new().parse_mode(ParseMode::Strict));
+    assert!(entries(&fs).is_err());
+}
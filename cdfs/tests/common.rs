@@ -1,13 +1,303 @@
 // SPDX-License-Identifier: (MIT OR Apache-2.0)
 
-use cdfs::{ISO9660Reader, ISODirectory};
+// Not every test uses every helper.
+#![allow(dead_code)]
+
+use std::io::Cursor;
+
+use cdfs::{DirectoryEntry, ISO9660Reader, ISODirectory, ISOFile, OpenOptions, Result, ISO9660};
 
 pub fn collect_filenames<T: ISO9660Reader>(directory: &ISODirectory<T>) -> Vec<String> {
     directory
         .contents()
-        .collect::<Result<Vec<_>, _>>()
+        .collect::<Result<Vec<_>>>()
         .unwrap()
         .into_iter()
         .map(|item| item.identifier().to_string())
         .collect::<Vec<_>>()
 }
+
+pub const SECTOR_SIZE: usize = 2048;
+
+pub fn both_endian16(n: u16) -> Vec<u8> {
+    [n.to_le_bytes(), n.to_be_bytes()].concat()
+}
+
+pub fn both_endian32(n: u32) -> Vec<u8> {
+    [n.to_le_bytes(), n.to_be_bytes()].concat()
+}
+
+/// The `SP` entry that must lead the system use field of the root's `.` entry for any system use
+/// entries to be read.
+pub const SP: [u8; 7] = [b'S', b'P', 7, 1, 0xBE, 0xEF, 0];
+
+pub fn directory_record(extent: u32, size: u32, flags: u8, identifier: &[u8]) -> Vec<u8> {
+    directory_record_with_system_use(extent, size, flags, identifier, &[])
+}
+
+pub fn directory_record_with_system_use(
+    extent: u32,
+    size: u32,
+    flags: u8,
+    identifier: &[u8],
+    system_use: &[u8],
+) -> Vec<u8> {
+    let mut record = vec![0, 0];
+    record.extend(both_endian32(extent));
+    record.extend(both_endian32(size));
+    record.extend([0; 7]);
+    record.extend([flags, 0, 0]);
+    record.extend(both_endian16(1));
+    record.push(identifier.len() as u8);
+    record.extend(identifier);
+    if record.len() % 2 == 1 {
+        record.push(0);
+    }
+    record.extend(system_use);
+    record[0] = record.len() as u8;
+    record
+}
+
+pub fn primary_descriptor(block_size: u16, volume_blocks: u32, root: &[u8]) -> Vec<u8> {
+    let mut pvd = vec![1];
+    pvd.extend(b"CD001\x01\x00");
+    pvd.extend([b' '; 32]); // system identifier
+    pvd.extend(format!("{:32}", "BLOCKS").as_bytes()); // volume identifier
+    pvd.extend([0; 8]);
+    pvd.extend(both_endian32(volume_blocks));
+    pvd.extend([0; 32]); // escape sequences
+    pvd.extend(both_endian16(1));
+    pvd.extend(both_endian16(1));
+    pvd.extend(both_endian16(block_size));
+    pvd.extend(both_endian32(0)); // path table size
+    pvd.extend([0; 16]); // path table locations
+    pvd.extend(root);
+    pvd.extend([b' '; 128 * 4 + 37 * 3]);
+    for _ in 0..4 {
+        pvd.extend(b"0000000000000000\x00");
+    }
+    pvd.push(1);
+    pvd.resize(SECTOR_SIZE, 0);
+    pvd
+}
+
+/// A Joliet supplementary volume descriptor with its root directory at sector 20.  Its UCS-2 level 3
+/// escape sequence is preceded by another.
+pub fn joliet_descriptor() -> Vec<u8> {
+    let mut descriptor =
+        primary_descriptor(2048, 22, &directory_record(20, SECTOR_SIZE as u32, 2, &[0]));
+    descriptor[0] = 2;
+    descriptor[88..95].copy_from_slice(b"\x1B(B\x00%/E");
+    descriptor
+}
+
+pub fn terminator() -> Vec<u8> {
+    let mut terminator = vec![255];
+    terminator.extend(b"CD001\x01");
+    terminator.resize(SECTOR_SIZE, 0);
+    terminator
+}
+
+/// Pads `data` to a whole number of sectors, at least one.
+pub fn sector(data: &[u8]) -> Vec<u8> {
+    let mut sector = data.to_vec();
+    sector.resize(data.len().div_ceil(SECTOR_SIZE).max(1) * SECTOR_SIZE, 0);
+    sector
+}
+
+/// A directory at `extent` holding `.`, `..`, then `records`.
+pub fn directory(extent: u32, records: &[Vec<u8>]) -> Vec<u8> {
+    let mut directory = directory_record(extent, SECTOR_SIZE as u32, 2, &[0]);
+    directory.extend(directory_record(extent, SECTOR_SIZE as u32, 2, &[1]));
+    directory.extend(records.concat());
+    sector(&directory)
+}
+
+/// The record of a five byte file at sector 20, e.g. `hello`.
+pub fn file_record(identifier: &[u8], system_use: &[u8]) -> Vec<u8> {
+    directory_record_with_system_use(20, 5, 0, identifier, system_use)
+}
+
+/// Builds an image whose primary volume descriptor has its root directory at sector 19.  Other
+/// descriptors follow the primary one, then the terminator, leaving sector 18 unused if there's
+/// room.  Sectors added to the image follow the root directory, from sector 20 on.
+#[derive(Default)]
+pub struct Image {
+    root_system_use: Vec<u8>,
+    dot_system_use: Vec<u8>,
+    records: Vec<Vec<u8>>,
+    descriptors: Vec<Vec<u8>>,
+    data: Vec<u8>,
+}
+
+impl Image {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The system use field of every record of the root directory: its record in the
+    /// descriptor, `.`, and `..`.
+    pub fn root_system_use(mut self, system_use: &[u8]) -> Self {
+        self.root_system_use = system_use.to_vec();
+        self
+    }
+
+    /// The system use field of `.`, after that of the root.  Starts with [`SP`] for any system
+    /// use entries to be read.
+    pub fn dot_system_use(mut self, system_use: &[u8]) -> Self {
+        self.dot_system_use = system_use.to_vec();
+        self
+    }
+
+    /// Adds a record to the root directory, after `.`, `..`, and those already added.
+    pub fn record(mut self, record: Vec<u8>) -> Self {
+        self.records.push(record);
+        self
+    }
+
+    pub fn records(mut self, records: impl IntoIterator<Item = Vec<u8>>) -> Self {
+        self.records.extend(records);
+        self
+    }
+
+    /// Adds a volume descriptor after the primary one.
+    pub fn descriptor(mut self, descriptor: Vec<u8>) -> Self {
+        assert!(self.descriptors.is_empty(), "Only sector 18 is free");
+        self.descriptors.push(descriptor);
+        self
+    }
+
+    /// Adds `data` after the sectors already added, padded to a whole number of sectors.
+    pub fn sector(mut self, data: &[u8]) -> Self {
+        self.data.extend(sector(data));
+        self
+    }
+
+    pub fn build(self) -> Vec<u8> {
+        let root_size = SECTOR_SIZE as u32;
+        let volume_blocks = (20 + self.data.len() / SECTOR_SIZE) as u32;
+        let root = directory_record_with_system_use(19, root_size, 2, &[0], &self.root_system_use);
+
+        let mut image = vec![0; 16 * SECTOR_SIZE];
+        image.extend(primary_descriptor(2048, volume_blocks, &root));
+        image.extend(self.descriptors.concat());
+        image.extend(terminator());
+        image.resize(19 * SECTOR_SIZE, 0);
+
+        let dot_system_use = [self.root_system_use.clone(), self.dot_system_use].concat();
+        let mut directory =
+            directory_record_with_system_use(19, root_size, 2, &[0], &dot_system_use);
+        directory.extend(directory_record_with_system_use(
+            19,
+            root_size,
+            2,
+            &[1],
+            &self.root_system_use,
+        ));
+        directory.extend(self.records.concat());
+        image.extend(sector(&directory)); // 19
+
+        image.extend(self.data);
+        image
+    }
+}
+
+/// A version 1 system use entry.
+pub fn entry(signature: &[u8; 2], data: &[u8]) -> Vec<u8> {
+    [&signature[..], &[4 + data.len() as u8, 1], data].concat()
+}
+
+/// An `ER` entry registering the extension `id`, version 1.
+pub fn er(id: &[u8]) -> Vec<u8> {
+    entry(b"ER", &[&[id.len() as u8, 0, 0, 1], id].concat())
+}
+
+/// An `NM` entry holding the whole of `name`.
+pub fn nm(name: &[u8]) -> Vec<u8> {
+    entry(b"NM", &[&[0], name].concat())
+}
+
+/// A `CE` entry pointing at `length` bytes at the start of sector `lba`.
+pub fn ce(lba: u32, length: u32) -> Vec<u8> {
+    let data = [both_endian32(lba), both_endian32(0), both_endian32(length)].concat();
+    entry(b"CE", &data)
+}
+
+/// A `PX` entry with the given mode, one link, and root as the owner.
+pub fn px(mode: u32) -> Vec<u8> {
+    let data = [mode, 1, 0, 0, 0].map(both_endian32).concat();
+    entry(b"PX", &data)
+}
+
+/// A Joliet identifier of the given UTF-16 code units, which needn't be valid.
+pub fn ucs2(units: &[u16]) -> Vec<u8> {
+    units.iter().flat_map(|unit| unit.to_be_bytes()).collect()
+}
+
+pub fn ucs2_str(s: &str) -> Vec<u8> {
+    ucs2(&s.encode_utf16().collect::<Vec<_>>())
+}
+
+pub fn open(image: Vec<u8>, options: &OpenOptions) -> ISO9660<Cursor<Vec<u8>>> {
+    options.open(Cursor::new(image)).unwrap()
+}
+
+/// The contents of the root directory, past `.` and `..`.
+pub fn entries<T: ISO9660Reader>(fs: &ISO9660<T>) -> Result<Vec<DirectoryEntry<T>>> {
+    fs.root().contents().skip(2).collect()
+}
+
+pub fn open_file<T: ISO9660Reader>(fs: &ISO9660<T>, path: &str) -> ISOFile<T> {
+    match fs.open(path).unwrap().unwrap() {
+        DirectoryEntry::File(file) => file,
+        _ => panic!("Not a file"),
+    }
+}
+
+pub const SYNC: [u8; 12] = [
+    0, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0,
+];
+
+#[derive(Clone, Copy)]
+pub enum Layout {
+    Mode1,
+    Mode2Form1,
+    Mode2,
+}
+
+pub fn raw_header(lba: usize, mode: u8) -> Vec<u8> {
+    // Minute / second / frame in BCD, offset by the two second lead-in.
+    let bcd = |n: usize| (((n / 10) << 4) | (n % 10)) as u8;
+    let frame = lba + 150;
+    let mut header = SYNC.to_vec();
+    header.extend([
+        bcd(frame / 75 / 60),
+        bcd(frame / 75 % 60),
+        bcd(frame % 75),
+        mode,
+    ]);
+    header
+}
+
+pub fn rawify(cooked: &[u8], layout: Layout) -> Vec<u8> {
+    let subheader = [0, 0, 0x08, 0, 0, 0, 0x08, 0];
+
+    cooked
+        .chunks(SECTOR_SIZE)
+        .enumerate()
+        .flat_map(|(lba, data)| {
+            let mut sector = match layout {
+                Layout::Mode1 => raw_header(lba, 1),
+                Layout::Mode2Form1 => [raw_header(lba, 2), subheader.to_vec()].concat(),
+                Layout::Mode2 => subheader.to_vec(),
+            };
+            sector.extend(data);
+            // EDC / ECC isn't checked.
+            sector.resize(sector.len() + 280, 0);
+            if let Layout::Mode1 = layout {
+                sector.resize(2352, 0);
+            }
+            sector
+        })
+        .collect()
+}
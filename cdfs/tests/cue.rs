@@ -0,0 +1,169 @@
+// SPDX-License-Identifier: (MIT OR Apache-2.0)
+
+use std::{
+    fs,
+    io::Read,
+    path::{Path, PathBuf},
+};
+
+use cdfs::{CueSheet, DirectoryEntry, ISOError, TrackIndex, TrackMode, ISO9660};
+
+mod common;
+use common::{collect_filenames, rawify, Layout};
+
+const TEST_IMAGE: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../images/test.iso");
+
+fn scratch_dir(name: &str) -> PathBuf {
+    let dir = Path::new(env!("CARGO_TARGET_TMPDIR")).join(name);
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn check_gpl(fs: &ISO9660<impl cdfs::ISO9660Reader>) {
+    assert_eq!(
+        collect_filenames(fs.root()),
+        &[".", "..", "A", "GPL_3_0.TXT"]
+    );
+
+    let file = match fs.open("gpl_3_0.txt").unwrap().unwrap() {
+        DirectoryEntry::File(file) => file,
+        _ => panic!("Not a file"),
+    };
+
+    let mut text = String::new();
+    file.read().read_to_string(&mut text).unwrap();
+    let hash = md5::compute(text);
+    assert_eq!(format!("{:x}", hash), "1ebbd3e34237af26da5dc08a4e440464");
+}
+
+#[test]
+fn cue_parse() {
+    let cue = CueSheet::parse(
+        r#"REM GENRE Game
+CATALOG 0000000000000
+FILE "Some Disc (Track 1).bin" BINARY
+  TRACK 01 MODE2/2352
+    INDEX 01 00:00:00
+FILE "Some Disc (Track 2).bin" BINARY
+  TRACK 02 AUDIO
+    PREGAP 00:02:00
+    INDEX 00 00:00:00
+    INDEX 01 00:01:74
+  TRACK 03 AUDIO
+    INDEX 01 01:00:00
+"#,
+        Path::new("/discs"),
+    )
+    .unwrap();
+
+    let tracks = cue.tracks();
+    assert_eq!(tracks.len(), 3);
+
+    assert_eq!(tracks[0].number, 1);
+    assert_eq!(tracks[0].mode, TrackMode::Mode2_2352);
+    assert_eq!(tracks[0].file, Path::new("/discs/Some Disc (Track 1).bin"));
+    assert_eq!(tracks[0].offset, 0);
+
+    assert_eq!(tracks[1].file, Path::new("/discs/Some Disc (Track 2).bin"));
+    assert_eq!(tracks[1].pregap, 150);
+    assert_eq!(
+        tracks[1].indexes,
+        &[
+            TrackIndex {
+                number: 0,
+                position: 0
+            },
+            TrackIndex {
+                number: 1,
+                position: 149
+            }
+        ]
+    );
+    assert_eq!(tracks[1].offset, 149 * 2352);
+    assert_eq!(tracks[2].offset, 60 * 75 * 2352);
+
+    assert_eq!(cue.data_track().unwrap().number, 1);
+}
+
+#[test]
+fn cue_parse_errors() {
+    assert!(matches!(
+        CueSheet::parse("TRACK 01 AUDIO\n", Path::new("")),
+        Err(ISOError::InvalidCue { line: 1, .. })
+    ));
+
+    assert!(matches!(
+        CueSheet::parse("FILE a.bin BINARY\n  TRACK 01 MODE3/2352\n", Path::new("")),
+        Err(ISOError::InvalidCue { line: 2, .. })
+    ));
+
+    assert!(matches!(
+        CueSheet::parse(
+            "FILE a.bin BINARY\n  TRACK 01 AUDIO\n    INDEX 01 00:60:00\n",
+            Path::new("")
+        ),
+        Err(ISOError::InvalidCue { line: 3, .. })
+    ));
+}
+
+#[test]
+fn cue_data_after_audio() {
+    let dir = scratch_dir("cue_data_after_audio");
+
+    // Ten sectors of audio, two sectors of pregap, then the data track.
+    let mut bin = vec![0; 12 * 2352];
+    bin.extend(rawify(&fs::read(TEST_IMAGE).unwrap(), Layout::Mode1));
+    fs::write(dir.join("disc.bin"), bin).unwrap();
+
+    fs::write(
+        dir.join("disc.cue"),
+        "FILE \"disc.bin\" BINARY\n\
+         \x20 TRACK 01 AUDIO\n\
+         \x20   INDEX 01 00:00:00\n\
+         \x20 TRACK 02 MODE1/2352\n\
+         \x20   INDEX 00 00:00:10\n\
+         \x20   INDEX 01 00:00:12\n",
+    )
+    .unwrap();
+
+    let cue = CueSheet::open(dir.join("disc.cue")).unwrap();
+    assert_eq!(cue.tracks().len(), 2);
+    assert_eq!(cue.data_track().unwrap().number, 2);
+    assert_eq!(cue.data_track().unwrap().offset, 12 * 2352);
+
+    check_gpl(&ISO9660::new(cue.open_data_track().unwrap()).unwrap());
+}
+
+#[test]
+fn cue_multiple_files() {
+    let dir = scratch_dir("cue_multiple_files");
+
+    fs::write(
+        dir.join("data track.bin"),
+        rawify(&fs::read(TEST_IMAGE).unwrap(), Layout::Mode2Form1),
+    )
+    .unwrap();
+    fs::write(dir.join("audio track.bin"), vec![0; 300 * 2352]).unwrap();
+
+    fs::write(
+        dir.join("disc.cue"),
+        "FILE \"data track.bin\" BINARY\n\
+         \x20 TRACK 01 MODE2/2352\n\
+         \x20   INDEX 01 00:00:00\n\
+         FILE \"audio track.bin\" BINARY\n\
+         \x20 TRACK 02 AUDIO\n\
+         \x20   INDEX 00 00:00:00\n\
+         \x20   INDEX 01 00:02:00\n",
+    )
+    .unwrap();
+
+    let cue = CueSheet::open(dir.join("disc.cue")).unwrap();
+    let audio = &cue.tracks()[1];
+    assert!(!audio.mode.is_data());
+    assert_eq!(audio.file, dir.join("audio track.bin"));
+    assert_eq!(audio.offset, 150 * 2352);
+    assert!(cue.open_track(audio).is_err());
+
+    check_gpl(&ISO9660::new(cue.open_data_track().unwrap()).unwrap());
+}
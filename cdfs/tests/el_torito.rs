@@ -0,0 +1,130 @@
+// SPDX-License-Identifier: (MIT OR Apache-2.0)
+
+use std::{
+    fs::File,
+    io::{Cursor, Read, Seek, SeekFrom},
+};
+
+use cdfs::{BootMediaType, BootPlatform, ISOError, ISO9660};
+
+mod common;
+use common::{Image, SECTOR_SIZE};
+
+const TEST_IMAGE: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../images/test.iso");
+
+const CATALOG_LBA: u32 = 20;
+const BIOS_LBA: u32 = 21;
+const EFI_LBA: u32 = 22;
+
+fn boot_record() -> Vec<u8> {
+    let mut record = vec![0];
+    record.extend(b"CD001\x01");
+    let mut identifier = b"EL TORITO SPECIFICATION".to_vec();
+    identifier.resize(32, 0);
+    record.extend(identifier);
+    record.extend([0; 32]);
+    record.extend(CATALOG_LBA.to_le_bytes());
+    record.resize(SECTOR_SIZE, 0);
+    record
+}
+
+fn boot_entry(sector_count: u16, load_rba: u32) -> Vec<u8> {
+    let mut entry = vec![0x88, 0];
+    entry.extend(0_u16.to_le_bytes());
+    entry.extend([0, 0]);
+    entry.extend(sector_count.to_le_bytes());
+    entry.extend(load_rba.to_le_bytes());
+    entry.resize(32, 0);
+    entry
+}
+
+fn boot_catalog() -> Vec<u8> {
+    let mut validation = vec![0x01, 0x00, 0, 0];
+    let mut identifier = b"CDFS".to_vec();
+    identifier.resize(24, 0);
+    validation.extend(identifier);
+    validation.extend([0, 0, 0x55, 0xAA]);
+    let sum = validation.chunks(2).fold(0_u16, |acc, word| {
+        acc.wrapping_add(u16::from_le_bytes([word[0], word[1]]))
+    });
+    validation[28..30].copy_from_slice(&0_u16.wrapping_sub(sum).to_le_bytes());
+
+    let mut catalog = validation;
+    catalog.extend(boot_entry(4, BIOS_LBA));
+
+    catalog.extend([0x91, 0xEF]);
+    catalog.extend(1_u16.to_le_bytes());
+    let mut identifier = b"UEFI".to_vec();
+    identifier.resize(28, 0);
+    catalog.extend(identifier);
+    catalog.extend(boot_entry(8, EFI_LBA));
+
+    catalog.resize(SECTOR_SIZE, 0);
+    catalog
+}
+
+fn build_image() -> Vec<u8> {
+    Image::new()
+        .descriptor(boot_record())
+        .sector(&boot_catalog()) // 20
+        .sector(&[0xAA; SECTOR_SIZE]) // 21
+        .sector(&[0xEE; 2 * SECTOR_SIZE]) // 22, 23
+        .build()
+}
+
+#[test]
+fn el_torito_catalog() {
+    let fs = ISO9660::new(Cursor::new(build_image())).unwrap();
+    let catalog = fs.boot_catalog().unwrap().expect("missing boot catalog");
+
+    assert_eq!(catalog.validation.platform, BootPlatform::X86);
+    assert_eq!(catalog.validation.identifier, "CDFS");
+
+    assert!(catalog.default_entry.bootable);
+    assert_eq!(catalog.default_entry.media_type, BootMediaType::NoEmulation);
+    assert_eq!(catalog.default_entry.sector_count, 4);
+    assert_eq!(catalog.default_entry.load_rba, BIOS_LBA);
+
+    assert_eq!(catalog.sections.len(), 1);
+    let section = &catalog.sections[0];
+    assert_eq!(section.platform, BootPlatform::Efi);
+    assert_eq!(section.identifier, "UEFI");
+    assert_eq!(section.entries.len(), 1);
+    assert_eq!(section.entries[0].platform, BootPlatform::Efi);
+    assert_eq!(section.entries[0].load_rba, EFI_LBA);
+
+    assert_eq!(catalog.entries().count(), 2);
+}
+
+#[test]
+fn el_torito_boot_images() {
+    let fs = ISO9660::new(Cursor::new(build_image())).unwrap();
+    let catalog = fs.boot_catalog().unwrap().unwrap();
+
+    let mut bios = Vec::new();
+    fs.boot_image(&catalog.default_entry)
+        .read_to_end(&mut bios)
+        .unwrap();
+    assert_eq!(bios, vec![0xAA; SECTOR_SIZE]);
+
+    let mut efi = fs.boot_image(&catalog.sections[0].entries[0]);
+    let mut tail = Vec::new();
+    efi.seek(SeekFrom::End(-16)).unwrap();
+    efi.read_to_end(&mut tail).unwrap();
+    assert_eq!(tail, vec![0xEE; 16]);
+}
+
+#[test]
+fn el_torito_bad_checksum() {
+    let mut image = build_image();
+    image[CATALOG_LBA as usize * SECTOR_SIZE + 28] ^= 0xFF;
+
+    let fs = ISO9660::new(Cursor::new(image)).unwrap();
+    assert!(matches!(fs.boot_catalog(), Err(ISOError::InvalidFs(_))));
+}
+
+#[test]
+fn el_torito_absent() {
+    let fs = ISO9660::new(File::open(TEST_IMAGE).unwrap()).unwrap();
+    assert!(fs.boot_catalog().unwrap().is_none());
+}
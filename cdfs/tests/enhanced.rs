@@ -0,0 +1,121 @@
+// SPDX-License-Identifier: (MIT OR Apache-2.0)
+
+use std::io::{Cursor, Read};
+
+use cdfs::{DirectoryEntry, ISO9660Reader, ISODirectory, Tree, VolumeDescriptor, ISO9660};
+
+mod common;
+use common::{
+    directory, directory_record, directory_record_with_system_use, entry, primary_descriptor, px,
+    Image, SECTOR_SIZE, SP,
+};
+
+const LONG_NAME: &[u8] = b"An identifier longer than 31 characters; version-less";
+
+/// An enhanced volume descriptor, as written by `genisoimage -iso-level 4`.
+fn enhanced_descriptor(root: &[u8]) -> Vec<u8> {
+    let mut descriptor = primary_descriptor(2048, 22, root);
+    descriptor[0] = 2;
+    descriptor[6] = 2;
+    descriptor[881] = 2; // file structure version
+    descriptor
+}
+
+fn file_records(files: &[&[u8]]) -> Vec<Vec<u8>> {
+    files
+        .iter()
+        .map(|file| directory_record(21, 5, 0, file))
+        .collect()
+}
+
+fn build_image() -> Vec<u8> {
+    Image::new()
+        .records(file_records(&[b"FILE.;1", b"LONG_NAM.;1"]))
+        .descriptor(enhanced_descriptor(&directory_record(
+            20,
+            SECTOR_SIZE as u32,
+            2,
+            &[0],
+        )))
+        .sector(&directory(20, &file_records(&[b"file.", LONG_NAME]))) // 20
+        .sector(b"hello") // 21
+        .build()
+}
+
+fn names<T: ISO9660Reader>(directory: &ISODirectory<T>) -> Vec<(String, u16)> {
+    directory
+        .contents()
+        .skip(2)
+        .map(|entry| match entry.unwrap() {
+            DirectoryEntry::File(file) => (file.identifier.clone(), file.version),
+            _ => panic!("Expected a file"),
+        })
+        .collect()
+}
+
+#[test]
+fn enhanced_descriptor_version() {
+    let fs = ISO9660::new(Cursor::new(build_image())).unwrap();
+
+    let VolumeDescriptor::Supplementary(table) = &fs.volume_descriptors()[1] else {
+        panic!("Expected a supplementary volume descriptor");
+    };
+    assert_eq!(table.version, 2);
+    assert_eq!(table.file_structure_version, 2);
+    assert_eq!(fs.primary_descriptor().version, 1);
+}
+
+#[test]
+fn enhanced_tree() {
+    let fs = ISO9660::new(Cursor::new(build_image())).unwrap();
+
+    let long_name = std::str::from_utf8(LONG_NAME).unwrap();
+    assert_eq!(fs.trees(), [Tree::Primary, Tree::Enhanced]);
+    assert_eq!(fs.selected_tree(), Tree::Enhanced);
+
+    assert_eq!(
+        names(fs.tree(Tree::Primary).unwrap()),
+        [("FILE".to_string(), 1), ("LONG_NAM".to_string(), 1)]
+    );
+    assert_eq!(
+        names(fs.root()),
+        [("file.".to_string(), 1), (long_name.to_string(), 1)]
+    );
+
+    let Some(DirectoryEntry::File(file)) = fs.root().find(long_name).unwrap() else {
+        panic!("Expected a file");
+    };
+    let mut contents = String::new();
+    file.read().read_to_string(&mut contents).unwrap();
+    assert_eq!(contents, "hello");
+}
+
+#[test]
+fn enhanced_symlink() {
+    // A Rock Ridge symbolic link, whose identifier is as verbatim as a file's.
+    let link = [px(0o120777), entry(b"SL", b"\x00\x00\x05file.")].concat();
+    let directory = [
+        directory_record_with_system_use(20, SECTOR_SIZE as u32, 2, &[0], &SP),
+        directory_record(20, SECTOR_SIZE as u32, 2, &[1]),
+        directory_record_with_system_use(0, 0, 0, b"link.", &link),
+    ]
+    .concat();
+    let image = Image::new()
+        .descriptor(enhanced_descriptor(&directory_record(
+            20,
+            SECTOR_SIZE as u32,
+            2,
+            &[0],
+        )))
+        .sector(&directory) // 20
+        .sector(&[]) // 21
+        .build();
+    let fs = ISO9660::new(Cursor::new(image)).unwrap();
+
+    let Some(DirectoryEntry::Symlink(link)) = fs.root().contents().nth(2).transpose().unwrap()
+    else {
+        panic!("Expected a symbolic link");
+    };
+    assert_eq!((link.identifier.as_str(), link.version), ("link.", 1));
+    assert_eq!(link.target().map(String::as_str), Some("file."));
+}
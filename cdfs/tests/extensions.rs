@@ -0,0 +1,93 @@
+// SPDX-License-Identifier: (MIT OR Apache-2.0)
+
+use std::io::Cursor;
+
+use cdfs::{ExtraAttributes, SuspExtension, ISO9660};
+
+mod common;
+use common::{entry, er, file_record, nm, open_file, Image, SP};
+
+fn es(sequence: u8) -> Vec<u8> {
+    entry(b"ES", &[sequence])
+}
+
+fn vendor() -> SuspExtension {
+    SuspExtension::Other {
+        identifier: "VENDOR_X".to_string(),
+        version: 1,
+    }
+}
+
+/// The root registers Rock Ridge, a vendor extension, and AAIP, in that order.  `FILE.TXT` has a
+/// vendor entry that reuses the `NM` signature, then a Rock Ridge `NM` entry, then an AAIP entry.
+fn build_image() -> Vec<u8> {
+    let dot_system_use = [
+        SP.to_vec(),
+        er(b"RRIP_1991A"),
+        er(b"VENDOR_X"),
+        er(b"AAIP_0200"),
+    ]
+    .concat();
+    let file_system_use = [
+        es(1),
+        nm(b"something else entirely"),
+        es(0),
+        nm(b"file name"),
+        es(2),
+        entry(b"AL", &[0, 0, 2, 3, b'k', 0, 1, b'v']),
+    ]
+    .concat();
+
+    Image::new()
+        .dot_system_use(&dot_system_use)
+        .record(file_record(b"FILE.TXT;1", &file_system_use))
+        .sector(b"hello") // 20
+        .build()
+}
+
+#[test]
+fn extensions_in_order() {
+    let fs = ISO9660::new(Cursor::new(build_image())).unwrap();
+
+    let expected = [SuspExtension::RockRidge1_09, vendor(), SuspExtension::Aaip];
+    assert_eq!(fs.extensions(), expected);
+
+    let dot = fs.root().contents().next().unwrap().unwrap();
+    assert_eq!(dot.ext().extensions, expected);
+    assert!(dot
+        .ext()
+        .origins
+        .iter()
+        .all(|origin| origin.extension.is_none()));
+}
+
+#[test]
+fn extensions_attribution() {
+    let fs = ISO9660::new(Cursor::new(build_image())).unwrap();
+
+    let file = open_file(&fs, "file name");
+
+    let origins = file
+        .ext()
+        .origins
+        .iter()
+        .map(|origin| (&origin.signature, origin.extension.clone()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        origins,
+        [
+            (b"ES", None),
+            (b"NM", Some(vendor())),
+            (b"ES", None),
+            (b"NM", Some(SuspExtension::RockRidge1_09)),
+            (b"ES", None),
+            (b"AL", Some(SuspExtension::Aaip)),
+        ]
+    );
+
+    // The vendor's `NM` entry isn't mistaken for part of the Rock Ridge name.
+    assert_eq!(file.identifier, "file name");
+    assert_eq!(file.entry_extension(b"NM"), Some(&vendor()));
+    assert_eq!(file.entry_extension(b"AL"), Some(&SuspExtension::Aaip));
+    assert_eq!(file.xattrs()["user.k"], b"v");
+}
@@ -0,0 +1,50 @@
+// SPDX-License-Identifier: (MIT OR Apache-2.0)
+
+use std::io::{Cursor, Read, Seek, SeekFrom};
+
+use cdfs::ISO9660;
+
+mod common;
+use common::{directory_record, open_file, Image, SECTOR_SIZE};
+
+/// `FILE.BIN` is recorded in file units of two blocks, each followed by a one block gap.
+fn build_image() -> Vec<u8> {
+    let mut record = directory_record(20, 5000, 0, b"FILE.BIN;1");
+    record[26] = 2; // file unit size
+    record[27] = 1; // interleave gap size
+
+    Image::new()
+        .record(record)
+        .sector(&[1; SECTOR_SIZE]) // 20
+        .sector(&[2; SECTOR_SIZE]) // 21
+        .sector(&[0xFF; SECTOR_SIZE]) // 22, the gap
+        .sector(&[3; SECTOR_SIZE]) // 23
+        .build()
+}
+
+fn expected() -> Vec<u8> {
+    [vec![1; 2048], vec![2; 2048], vec![3; 904]].concat()
+}
+
+#[test]
+fn interleaved_read() {
+    let fs = ISO9660::new(Cursor::new(build_image())).unwrap();
+    let file = open_file(&fs, "file.bin");
+    assert_eq!(file.size(), 5000);
+
+    let mut contents = Vec::new();
+    file.read().read_to_end(&mut contents).unwrap();
+    assert_eq!(contents, expected());
+}
+
+#[test]
+fn interleaved_seek() {
+    let fs = ISO9660::new(Cursor::new(build_image())).unwrap();
+    let file = open_file(&fs, "file.bin");
+
+    let mut reader = file.read();
+    let mut buf = [0; 8];
+    reader.seek(SeekFrom::Start(4092)).unwrap();
+    reader.read_exact(&mut buf).unwrap();
+    assert_eq!(buf, [2, 2, 2, 2, 3, 3, 3, 3]);
+}
@@ -2,12 +2,15 @@
 
 use std::fs::File;
 
-use cdfs::ISO9660;
+use cdfs::{OpenOptions, ParseMode, Tree, UnpairedSurrogates, ISO9660};
 
 mod common;
-use common::collect_filenames;
+use common::{
+    collect_filenames, directory, directory_record, joliet_descriptor, open, ucs2, ucs2_str, Image,
+    SECTOR_SIZE,
+};
 
-const JOLIET_IMAGE: &'static str = concat!(env!("CARGO_MANIFEST_DIR"), "/../images/joliet.iso");
+const JOLIET_IMAGE: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../images/joliet.iso");
 
 #[test]
 fn joliet_long_filenames_exist() {
@@ -34,3 +37,87 @@ fn joliet_short_filenames_exist() {
 
     assert_eq!(contents, &[".", "..", "README.TXT", "READ_ME_.TXT",]);
 }
+
+/// 103 characters, the most `genisoimage -joliet-long` allows.
+fn long_name() -> String {
+    format!("{}.txt", "long".repeat(25))
+}
+
+/// The primary hierarchy holds `FILE`, while the Joliet one holds `names`.
+fn build_image(names: &[Vec<u8>]) -> Vec<u8> {
+    let records = names
+        .iter()
+        .map(|name| directory_record(21, 0, 0, name))
+        .collect::<Vec<_>>();
+
+    Image::new()
+        .descriptor(joliet_descriptor())
+        .record(directory_record(21, 0, 0, b"FILE.;1"))
+        .sector(&directory(20, &records)) // 20
+        .sector(&[]) // 21
+        .build()
+}
+
+#[test]
+fn joliet_escape_sequences() {
+    let fs = open(build_image(&[]), &OpenOptions::new());
+
+    assert_eq!(fs.trees(), [Tree::Primary, Tree::Joliet { level: 3 }]);
+    assert_eq!(fs.selected_tree(), Tree::Joliet { level: 3 });
+}
+
+#[test]
+fn joliet_escape_sequences_primary() {
+    // The field is unused in the primary descriptor, so its contents are ignored.
+    let mut image = Image::new()
+        .record(directory_record(21, 0, 0, b"FILE.;1"))
+        .build();
+    image[16 * SECTOR_SIZE + 88..16 * SECTOR_SIZE + 91].copy_from_slice(b"%/E");
+    let fs = open(image, &OpenOptions::new());
+
+    assert_eq!(fs.trees(), [Tree::Primary]);
+    assert_eq!(collect_filenames(fs.root()), [".", "..", "FILE"]);
+}
+
+#[test]
+fn joliet_names_verbatim() {
+    let image = build_image(&[
+        ucs2_str(&format!("{};1", long_name())),
+        ucs2_str("trailing spaces  ;1"),
+        ucs2_str("smile \u{1F600}.txt;1"),
+    ]);
+    let fs = open(image, &OpenOptions::new());
+
+    assert_eq!(
+        collect_filenames(fs.root()),
+        [
+            ".",
+            "..",
+            &long_name(),
+            "trailing spaces  ",
+            "smile \u{1F600}.txt"
+        ]
+    );
+}
+
+#[test]
+fn joliet_unpaired_surrogates() {
+    let image = build_image(&[ucs2(&[0x61, 0xD800, 0x62, 0xDC00, 0x3B, 0x31])]);
+
+    let fs = open(image.clone(), &OpenOptions::new());
+    let escaped = collect_filenames(fs.root()).remove(2);
+    assert_eq!(escaped, "a\u{10F800}b\u{10FC00}");
+    assert_eq!(
+        UnpairedSurrogates::unescape(&escaped),
+        [0x61, 0xD800, 0x62, 0xDC00]
+    );
+
+    let fs = open(
+        image.clone(),
+        OpenOptions::new().unpaired_surrogates(UnpairedSurrogates::Replace),
+    );
+    assert_eq!(collect_filenames(fs.root())[2], "a\u{FFFD}b\u{FFFD}");
+
+    let fs = open(image, OpenOptions::new().parse_mode(ParseMode::Strict));
+    assert!(fs.root().contents().nth(2).unwrap().is_err());
+}
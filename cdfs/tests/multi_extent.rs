@@ -0,0 +1,78 @@
+// SPDX-License-Identifier: (MIT OR Apache-2.0)
+
+use std::io::{Cursor, Read, Seek, SeekFrom};
+
+use cdfs::ISO9660;
+
+mod common;
+use common::{collect_filenames, directory_record, open_file, Image, SECTOR_SIZE};
+
+const MULTIEXTENT: u8 = 1 << 7;
+
+/// `BIG.BIN` is recorded as three non-contiguous extents, out of order on disc.
+fn build_image() -> Vec<u8> {
+    Image::new()
+        .record(directory_record(23, 2048, MULTIEXTENT, b"BIG.BIN;1"))
+        .record(directory_record(20, 2048, MULTIEXTENT, b"BIG.BIN;1"))
+        .record(directory_record(22, 1000, 0, b"BIG.BIN;1"))
+        .record(directory_record(24, 5, 0, b"SMALL.TXT;1"))
+        .sector(&[2; SECTOR_SIZE]) // 20
+        .sector(&[0xFF; SECTOR_SIZE]) // 21
+        .sector(&[3; SECTOR_SIZE]) // 22
+        .sector(&[1; SECTOR_SIZE]) // 23
+        .sector(b"small") // 24
+        .build()
+}
+
+fn expected() -> Vec<u8> {
+    [vec![1; 2048], vec![2; 2048], vec![3; 1000]].concat()
+}
+
+#[test]
+fn multi_extent_merged() {
+    let fs = ISO9660::new(Cursor::new(build_image())).unwrap();
+
+    assert_eq!(
+        collect_filenames(fs.root()),
+        &[".", "..", "BIG.BIN", "SMALL.TXT"]
+    );
+
+    let file = open_file(&fs, "big.bin");
+    assert_eq!(file.size(), 5096);
+    assert_eq!(file.extent_count(), 3);
+
+    let mut contents = Vec::new();
+    file.read().read_to_end(&mut contents).unwrap();
+    assert_eq!(contents, expected());
+}
+
+#[test]
+fn multi_extent_seek() {
+    let fs = ISO9660::new(Cursor::new(build_image())).unwrap();
+
+    let file = open_file(&fs, "big.bin");
+
+    let mut reader = file.read();
+    let mut buf = [0; 8];
+
+    reader.seek(SeekFrom::Start(2044)).unwrap();
+    reader.read_exact(&mut buf).unwrap();
+    assert_eq!(buf, [1, 1, 1, 1, 2, 2, 2, 2]);
+
+    reader.seek(SeekFrom::End(-4)).unwrap();
+    let mut tail = Vec::new();
+    reader.read_to_end(&mut tail).unwrap();
+    assert_eq!(tail, [3; 4]);
+}
+
+#[test]
+fn multi_extent_following_entry() {
+    let fs = ISO9660::new(Cursor::new(build_image())).unwrap();
+
+    let file = open_file(&fs, "small.txt");
+    assert_eq!(file.extent_count(), 1);
+
+    let mut contents = String::new();
+    file.read().read_to_string(&mut contents).unwrap();
+    assert_eq!(contents, "small");
+}
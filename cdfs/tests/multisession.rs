@@ -0,0 +1,108 @@
+// SPDX-License-Identifier: (MIT OR Apache-2.0)
+
+use std::io::{Cursor, Read};
+
+use cdfs::{ISOError, Session, ISO9660};
+
+mod common;
+use common::{
+    collect_filenames, directory, directory_record, open_file, primary_descriptor, sector,
+    terminator, SECTOR_SIZE,
+};
+
+/// The first session holds `A.TXT`.  The second session, starting at sector 32, replaces it and
+/// adds `B.TXT`, while still referring to the data recorded by the first.
+fn build_image() -> Vec<u8> {
+    let root1 = directory_record(18, SECTOR_SIZE as u32, 2, &[0]);
+
+    let mut image = vec![0; 16 * SECTOR_SIZE];
+    image.extend(primary_descriptor(2048, 20, &root1));
+    image.extend(terminator());
+
+    image.extend(directory(18, &[directory_record(19, 3, 0, b"A.TXT;1")])); // 18
+    image.extend(sector(b"one")); // 19
+
+    // Padding between sessions, as left by e.g. growisofs.
+    image.resize(48 * SECTOR_SIZE, 0);
+
+    let root2 = directory_record(50, SECTOR_SIZE as u32, 2, &[0]);
+    image.extend(primary_descriptor(2048, 52, &root2)); // 48
+    image.extend(terminator()); // 49
+
+    image.extend(directory(
+        50,
+        &[
+            directory_record(19, 3, 0, b"A.TXT;1"),
+            directory_record(51, 3, 0, b"B.TXT;1"),
+        ],
+    )); // 50
+    image.extend(sector(b"two")); // 51
+
+    image
+}
+
+fn read_file<T: cdfs::ISO9660Reader>(fs: &ISO9660<T>, path: &str) -> String {
+    let mut contents = String::new();
+    open_file(fs, path)
+        .read()
+        .read_to_string(&mut contents)
+        .unwrap();
+    contents
+}
+
+#[test]
+fn multisession_enumerate() {
+    let sessions = ISO9660::sessions(&mut Cursor::new(build_image())).unwrap();
+    assert_eq!(
+        sessions,
+        &[
+            Session {
+                start: 0,
+                volume_space_size: 20,
+                block_size: 2048,
+            },
+            Session {
+                start: 32,
+                volume_space_size: 52,
+                block_size: 2048,
+            }
+        ]
+    );
+    assert_eq!(sessions[1].end(), 52);
+}
+
+#[test]
+fn multisession_first_session() {
+    let fs = ISO9660::new(Cursor::new(build_image())).unwrap();
+    assert_eq!(fs.session_start(), 0);
+    assert_eq!(collect_filenames(fs.root()), &[".", "..", "A.TXT"]);
+    assert_eq!(read_file(&fs, "a.txt"), "one");
+}
+
+#[test]
+fn multisession_last_session() {
+    let fs = ISO9660::new_last_session(Cursor::new(build_image())).unwrap();
+    assert_eq!(fs.session_start(), 32);
+    assert_eq!(collect_filenames(fs.root()), &[".", "..", "A.TXT", "B.TXT"]);
+    assert_eq!(read_file(&fs, "a.txt"), "one");
+    assert_eq!(read_file(&fs, "b.txt"), "two");
+
+    let fs = ISO9660::new_at_session(Cursor::new(build_image()), 32).unwrap();
+    assert_eq!(collect_filenames(fs.root()), &[".", "..", "A.TXT", "B.TXT"]);
+}
+
+#[test]
+fn multisession_single_session() {
+    let image = std::fs::read(concat!(env!("CARGO_MANIFEST_DIR"), "/../images/test.iso")).unwrap();
+    let sessions = ISO9660::sessions(&mut Cursor::new(image)).unwrap();
+    assert_eq!(sessions.len(), 1);
+    assert_eq!(sessions[0].start, 0);
+}
+
+#[test]
+fn multisession_not_an_image() {
+    assert!(matches!(
+        ISO9660::sessions(&mut Cursor::new(vec![0; 64 * 1024])),
+        Err(ISOError::InvalidFs(_))
+    ));
+}
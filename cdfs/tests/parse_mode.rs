@@ -0,0 +1,260 @@
+// SPDX-License-Identifier: (MIT OR Apache-2.0)
+
+use std::io::{Cursor, Read};
+
+use cdfs::{
+    Anomaly, DirectoryEntry, ExtraAttributes, ISOError, OpenOptions, ParseMode, Result, ISO9660,
+};
+
+mod common;
+use common::{
+    both_endian32, ce, directory, directory_record, directory_record_with_system_use, entries,
+    entry, file_record, joliet_descriptor, nm, px, ucs2, Image, SECTOR_SIZE, SP,
+};
+
+/// A root directory holding a single five byte file, `identifier`, with the given system use
+/// field.  The file's record is passed to `patch` before being written.
+fn build_image(identifier: &[u8], system_use: &[u8], patch: impl Fn(&mut Vec<u8>)) -> Vec<u8> {
+    let mut file = file_record(identifier, system_use);
+    patch(&mut file);
+
+    Image::new()
+        .dot_system_use(&SP)
+        .record(file)
+        .sector(b"hello") // 20
+        .build()
+}
+
+/// A `PX` entry with an unexpected version.
+fn px_version_2() -> Vec<u8> {
+    let mut entry = px(0o100644);
+    entry[3] = 2;
+    entry
+}
+
+/// An `SL` entry holding a single component.
+fn sl(flags: u8, component: &[u8]) -> Vec<u8> {
+    entry(
+        b"SL",
+        &[&[0, flags, component.len() as u8], component].concat(),
+    )
+}
+
+fn open(image: Vec<u8>, mode: ParseMode) -> ISO9660<Cursor<Vec<u8>>> {
+    common::open(image, OpenOptions::new().parse_mode(mode))
+}
+
+fn assert_anomaly<T>(result: Result<T>, anomaly: Anomaly) {
+    match result {
+        Err(ISOError::Anomaly(rejected)) => assert_eq!(rejected, anomaly),
+        Err(err) => panic!("Expected {anomaly:?}, got {err}"),
+        Ok(_) => panic!("Expected {anomaly:?}"),
+    }
+}
+
+/// Checks that listing the root directory of `image` rejects `anomaly` in strict mode, while lenient
+/// mode tolerates it and reports it as the only warning.  Returns the lenient listing.
+fn check_listing(image: Vec<u8>, anomaly: Anomaly) -> Vec<DirectoryEntry<Cursor<Vec<u8>>>> {
+    assert_anomaly(entries(&open(image.clone(), ParseMode::Strict)), anomaly);
+
+    let fs = open(image, ParseMode::Lenient);
+    assert_eq!(fs.warnings(), []);
+    let entries = entries(&fs).unwrap();
+    assert_eq!(fs.warnings(), [anomaly]);
+    entries
+}
+
+#[test]
+fn parse_mode_default() {
+    let image = build_image(b"FILE.TXT;1", &[], |_| {});
+
+    assert_eq!(
+        ISO9660::new(Cursor::new(image.clone()))
+            .unwrap()
+            .parse_mode(),
+        ParseMode::Lenient
+    );
+    assert_eq!(
+        open(image, ParseMode::Strict).parse_mode(),
+        ParseMode::Strict
+    );
+}
+
+#[test]
+fn parse_mode_interleave_gap() {
+    // A gap of one block, without file units to separate.
+    let image = build_image(b"FILE.TXT;1", &[], |record| record[27] = 1);
+
+    let entries = check_listing(image, Anomaly::InterleaveGap);
+    let DirectoryEntry::File(file) = &entries[0] else {
+        panic!("Expected a file");
+    };
+
+    // The gap is ignored, so the file is read as recorded.
+    let mut contents = Vec::new();
+    file.read().read_to_end(&mut contents).unwrap();
+    assert_eq!(contents, b"hello");
+}
+
+#[test]
+fn parse_mode_susp_version() {
+    let image = build_image(b"FILE.TXT;1", &px_version_2(), |_| {});
+
+    let entries = check_listing(image, Anomaly::SuspVersion);
+    assert_eq!(entries[0].mode().map(u16::from), Some(0o644));
+}
+
+#[test]
+fn parse_mode_susp_length() {
+    // A `PX` entry with trailing bytes.
+    let px = entry(b"PX", &[&px(0o100644)[4..], &[0; 8]].concat());
+    let image = build_image(b"FILE.TXT;1", &px, |_| {});
+
+    let entries = check_listing(image, Anomaly::SuspLength);
+    assert_eq!(entries[0].mode().map(u16::from), Some(0o644));
+}
+
+#[test]
+fn parse_mode_identifier() {
+    let image = build_image(b"FILE\xFF.TXT;1", &[], |_| {});
+
+    let entries = check_listing(image, Anomaly::InvalidCodePage);
+    assert_eq!(entries[0].identifier(), "FILE\u{FFFD}.TXT");
+}
+
+#[test]
+fn parse_mode_volume_identifier() {
+    let mut image = build_image(b"FILE.TXT;1", &[], |_| {});
+    image[16 * SECTOR_SIZE + 40] = 0xFF;
+
+    assert_anomaly(
+        OpenOptions::new()
+            .parse_mode(ParseMode::Strict)
+            .open(Cursor::new(image.clone())),
+        Anomaly::InvalidCodePage,
+    );
+
+    // Found on opening the image, before anything is listed.
+    let fs = open(image, ParseMode::Lenient);
+    assert_eq!(fs.warnings(), [Anomaly::InvalidCodePage]);
+    assert!(fs
+        .primary_descriptor()
+        .volume_identifier
+        .starts_with('\u{FFFD}'));
+}
+
+#[test]
+fn parse_mode_ucs2_identifier() {
+    let image = Image::new()
+        .descriptor(joliet_descriptor())
+        .sector(&directory(
+            20,
+            &[directory_record(
+                21,
+                0,
+                0,
+                &ucs2(&[0x61, 0xD800, 0x3B, 0x31]),
+            )],
+        )) // 20
+        .sector(&[]) // 21
+        .build();
+
+    let entries = check_listing(image, Anomaly::InvalidUcs2);
+    assert_eq!(entries[0].identifier(), "a\u{10F800}");
+}
+
+#[test]
+fn parse_mode_rock_ridge_name() {
+    let image = build_image(b"FILE.TXT;1", &nm(b"file\xFF"), |_| {});
+
+    let entries = check_listing(image, Anomaly::InvalidRockRidgeName);
+    assert_eq!(entries[0].identifier(), "file\u{FFFD}");
+}
+
+#[test]
+fn parse_mode_continuation_loop() {
+    // The continuation area continues in itself.
+    let area = [nm(b"file name"), ce(21, 0)].concat();
+    let area = [nm(b"file name"), ce(21, area.len() as u32)].concat();
+    let image = Image::new()
+        .dot_system_use(&SP)
+        .record(file_record(b"FILE.TXT;1", &ce(21, area.len() as u32)))
+        .sector(b"hello") // 20
+        .sector(&area) // 21
+        .build();
+
+    let entries = check_listing(image, Anomaly::ContinuationLoop);
+    assert_eq!(entries[0].identifier(), "file name");
+}
+
+#[test]
+fn parse_mode_symlink_component() {
+    // The current directory, with a name.
+    let system_use = [px(0o120777), sl(0x02, b"name")].concat();
+    let image = build_image(b"LINK.;1", &system_use, |_| {});
+
+    let entries = check_listing(image, Anomaly::SymlinkComponent);
+    assert!(matches!(entries[0], DirectoryEntry::Symlink(_)));
+}
+
+#[test]
+fn parse_mode_child_link() {
+    // A child link on a directory, rather than the file standing in for a relocated one.
+    let cl = entry(b"CL", &both_endian32(19));
+    let record = directory_record_with_system_use(19, SECTOR_SIZE as u32, 2, b"DIR", &cl);
+    let image = Image::new().dot_system_use(&SP).record(record).build();
+
+    let entries = check_listing(image, Anomaly::ChildLink);
+    assert!(matches!(entries[0], DirectoryEntry::Directory(_)));
+}
+
+#[test]
+fn parse_mode_malformed_attributes() {
+    // An attribute name without a value.
+    let al = entry(b"AL", b"\x00\x00\x04name");
+    let image = build_image(b"FILE.TXT;1", &al, |_| {});
+
+    let entries = check_listing(image, Anomaly::MalformedAttributes);
+    assert!(entries[0].xattrs().is_empty());
+    assert_eq!(entries[0].acl(), None);
+}
+
+#[test]
+fn parse_mode_error_ends_iteration() {
+    // A version number that isn't one, followed by a valid record.
+    let image = Image::new()
+        .record(file_record(b"BAD.TXT;X", &[]))
+        .record(file_record(b"GOOD.TXT;1", &[]))
+        .sector(b"hello") // 20
+        .build();
+
+    for mode in [ParseMode::Strict, ParseMode::Lenient] {
+        let fs = open(image.clone(), mode);
+        let mut contents = fs.root().contents().skip(2);
+        assert!(contents.next().unwrap().is_err());
+        assert!(contents.next().is_none());
+    }
+}
+
+#[test]
+fn parse_mode_incomplete() {
+    // A symbolic link component longer than its entry.
+    let mut sl = sl(0, b"name");
+    sl[6] = 50;
+    let image = build_image(b"LINK.;1", &sl, |_| {});
+
+    for mode in [ParseMode::Strict, ParseMode::Lenient] {
+        let fs = open(image.clone(), mode);
+        assert!(matches!(entries(&fs), Err(ISOError::Incomplete)));
+        assert_eq!(fs.warnings(), []);
+    }
+}
+
+#[test]
+fn parse_mode_root_at() {
+    let fs = open(build_image(b"FILE.TXT;1", &[], |_| {}), ParseMode::Strict);
+
+    assert!(fs.root_at(0).is_some());
+    assert!(fs.root_at(1).is_none());
+    assert!(fs.root_at(2).is_none());
+}
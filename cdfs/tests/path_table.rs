@@ -0,0 +1,139 @@
+// SPDX-License-Identifier: (MIT OR Apache-2.0)
+
+use std::{fs::File, io::Cursor};
+
+use cdfs::{DirectoryEntry, ExtraAttributes, ISOError, PathTableRecord, PathTableType, ISO9660};
+
+mod common;
+use common::{both_endian32, collect_filenames, SECTOR_SIZE};
+
+const TEST_IMAGE: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../images/test.iso");
+const ROCKRIDGE_IMAGE: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../images/rockridge.iso");
+
+#[test]
+fn path_table_records() {
+    let fs = ISO9660::new(File::open(TEST_IMAGE).unwrap()).unwrap();
+
+    let table = fs.path_table_at(0, PathTableType::L).unwrap().unwrap();
+    assert_eq!(
+        table.records(),
+        &[
+            PathTableRecord {
+                identifier: String::new(),
+                extended_attribute_record_length: 0,
+                extent_loc: 23,
+                parent: 1,
+            },
+            PathTableRecord {
+                identifier: String::from("A"),
+                extended_attribute_record_length: 0,
+                extent_loc: 24,
+                parent: 1,
+            },
+            PathTableRecord {
+                identifier: String::from("B"),
+                extended_attribute_record_length: 0,
+                extent_loc: 25,
+                parent: 2,
+            },
+            PathTableRecord {
+                identifier: String::from("C"),
+                extended_attribute_record_length: 0,
+                extent_loc: 26,
+                parent: 3,
+            },
+        ]
+    );
+
+    assert_eq!(table.path(1).as_deref(), Some("/"));
+    assert_eq!(table.path(4).as_deref(), Some("/A/B/C"));
+    assert_eq!(table.path(5), None);
+
+    assert_eq!(table.find("/"), Some(1));
+    assert_eq!(table.find("a/b//c/"), Some(4));
+    assert_eq!(table.find("a/c"), None);
+    assert_eq!(table.children(1).collect::<Vec<_>>(), &[2]);
+
+    // The extent matches the one in the directory record.
+    let dir = fs.open("a/b/c").unwrap().unwrap();
+    assert_eq!(dir.header().extent_loc, 26);
+
+    assert_eq!(fs.path_table_at(0, PathTableType::M).unwrap(), Some(table));
+    assert_eq!(fs.path_table_at(1, PathTableType::L).unwrap(), None);
+}
+
+#[test]
+fn path_table_oversized() {
+    // A path table size far larger than the volume, in the primary volume descriptor.
+    let mut image = std::fs::read(TEST_IMAGE).unwrap();
+    let size = 16 * SECTOR_SIZE + 132;
+    image[size..size + 8].copy_from_slice(&both_endian32(u32::MAX));
+
+    let fs = ISO9660::new(Cursor::new(image)).unwrap();
+    assert!(matches!(
+        fs.path_table_at(0, PathTableType::L),
+        Err(ISOError::InvalidFs(_))
+    ));
+}
+
+#[test]
+fn path_table_supplementary() {
+    let fs = ISO9660::new(File::open(ROCKRIDGE_IMAGE).unwrap()).unwrap();
+
+    let primary = fs.path_table_at(0, PathTableType::L).unwrap().unwrap();
+    let joliet = fs.path_table_at(1, PathTableType::L).unwrap().unwrap();
+
+    // The primary tree has a relocated directory, the Joliet tree doesn't.
+    assert_eq!(primary.find("/1/2/3/4/5/6/7/8"), None);
+    assert!(primary.find("/RR_MOVED/8").is_some());
+
+    let number = joliet.find("/1/2/3/4/5/6/7/8/9/10").unwrap();
+    assert_eq!(
+        joliet.path(number).as_deref(),
+        Some("/1/2/3/4/5/6/7/8/9/10")
+    );
+}
+
+#[test]
+fn path_table_lookup() {
+    let mut fs = ISO9660::new(File::open(TEST_IMAGE).unwrap()).unwrap();
+    fs.set_path_table_lookup(true);
+
+    for path in ["a/b/c/1", "///a/b/c/1", "/a/b//c////1/", "a/./b/c/1"] {
+        match fs.open(path).unwrap() {
+            Some(DirectoryEntry::File(file)) => assert_eq!(file.identifier, "1"),
+            _ => panic!("{path} is not a file"),
+        }
+    }
+
+    match fs.open("a/b/c").unwrap() {
+        Some(DirectoryEntry::Directory(dir)) => {
+            assert_eq!(dir.identifier, "C");
+            assert_eq!(dir.contents().count(), 202);
+        }
+        _ => panic!("Not a directory"),
+    }
+
+    match fs.open("/").unwrap() {
+        Some(DirectoryEntry::Directory(dir)) => {
+            assert_eq!(collect_filenames(&dir), &[".", "..", "A", "GPL_3_0.TXT"]);
+        }
+        _ => panic!("Not a directory"),
+    }
+
+    assert!(fs.open("gpl_3_0.txt").unwrap().is_some());
+    assert!(fs.open("a/b/c/nope").unwrap().is_none());
+    assert!(fs.open("a/nope/c/1").unwrap().is_none());
+}
+
+#[test]
+fn path_table_lookup_rock_ridge() {
+    let mut fs = ISO9660::new(File::open(ROCKRIDGE_IMAGE).unwrap()).unwrap();
+    fs.set_path_table_lookup(true);
+
+    // Rock Ridge names and relocated directories are resolved by walking the tree.
+    match fs.open("1/2/3/4/5/6/7/8/9/10").unwrap() {
+        Some(DirectoryEntry::Directory(dir)) => assert_eq!(dir.identifier, "10"),
+        _ => panic!("Not a directory"),
+    }
+}
@@ -0,0 +1,75 @@
+// SPDX-License-Identifier: (MIT OR Apache-2.0)
+
+use std::{
+    fs,
+    io::{Cursor, Read},
+};
+
+use cdfs::{DirectoryEntry, RawSectorReader, SectorFormat, SectorMode, ISO9660};
+
+mod common;
+use common::{collect_filenames, raw_header, rawify, Layout};
+
+const TEST_IMAGE: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../images/test.iso");
+
+fn check(image: Vec<u8>, format: SectorFormat) {
+    let reader = RawSectorReader::new(Cursor::new(image)).unwrap();
+    assert_eq!(reader.format(), format);
+
+    let fs = ISO9660::new(reader).unwrap();
+    assert_eq!(
+        collect_filenames(fs.root()),
+        &[".", "..", "A", "GPL_3_0.TXT"]
+    );
+
+    let file = match fs.open("gpl_3_0.txt").unwrap().unwrap() {
+        DirectoryEntry::File(file) => file,
+        _ => panic!("Not a file"),
+    };
+
+    let mut text = String::new();
+    file.read().read_to_string(&mut text).unwrap();
+    let hash = md5::compute(text);
+    assert_eq!(format!("{:x}", hash), "1ebbd3e34237af26da5dc08a4e440464");
+}
+
+#[test]
+fn raw_sector_mode1() {
+    let cooked = fs::read(TEST_IMAGE).unwrap();
+    check(rawify(&cooked, Layout::Mode1), SectorFormat::Raw);
+}
+
+#[test]
+fn raw_sector_mode2_form1() {
+    let cooked = fs::read(TEST_IMAGE).unwrap();
+    check(rawify(&cooked, Layout::Mode2Form1), SectorFormat::Raw);
+}
+
+#[test]
+fn raw_sector_mode2_2336() {
+    let cooked = fs::read(TEST_IMAGE).unwrap();
+    check(rawify(&cooked, Layout::Mode2), SectorFormat::Mode2);
+}
+
+#[test]
+fn raw_sector_cooked() {
+    check(fs::read(TEST_IMAGE).unwrap(), SectorFormat::Cooked);
+}
+
+#[test]
+fn raw_sector_form2_payload() {
+    let mut sector = raw_header(0, 2);
+    sector.extend([0, 0, 0x28, 0, 0, 0, 0x28, 0]);
+    sector.extend([0x5A; 2324]);
+    sector.extend([0; 4]);
+
+    let mut reader = RawSectorReader::with_format(Cursor::new(sector), SectorFormat::Raw);
+    let (mode, data) = reader.read_sector(0).unwrap();
+    assert_eq!(mode, SectorMode::Mode2Form2);
+    assert_eq!(data, [0x5A; 2324]);
+}
+
+#[test]
+fn raw_sector_not_an_image() {
+    assert!(RawSectorReader::new(Cursor::new(vec![0; 64 * 1024])).is_err());
+}
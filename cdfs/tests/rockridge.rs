@@ -4,7 +4,7 @@ use std::fs::File;
 
 use cdfs::ISO9660;
 
-const ROCKRIDGE_IMAGE: &'static str = concat!(env!("CARGO_MANIFEST_DIR"), "/../images/rockridge.iso");
+const ROCKRIDGE_IMAGE: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../images/rockridge.iso");
 
 mod common;
 use common::collect_filenames;
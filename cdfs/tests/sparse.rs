@@ -0,0 +1,123 @@
+// SPDX-License-Identifier: (MIT OR Apache-2.0)
+
+use std::io::{Cursor, Read, Seek, SeekFrom};
+
+use cdfs::{ExtraAttributes, SparseFile, ISO9660};
+
+mod common;
+use common::{
+    both_endian32, directory_record_with_system_use, entry, open_file, Image, SECTOR_SIZE, SP,
+};
+
+fn sf(virtual_size: u64, table_depth: u8) -> Vec<u8> {
+    let data = [
+        both_endian32((virtual_size >> 32) as u32),
+        both_endian32(virtual_size as u32),
+        vec![table_depth],
+    ];
+    entry(b"SF", &data.concat())
+}
+
+fn table(entries: &[(usize, u32)]) -> Vec<u8> {
+    let mut table = vec![0; SECTOR_SIZE];
+    for &(index, entry) in entries {
+        table[index * 4..index * 4 + 4].copy_from_slice(&entry.to_le_bytes());
+    }
+    table
+}
+
+const SHALLOW_SIZE: u64 = 3 * SECTOR_SIZE as u64 + 100;
+const DEEP_SIZE: u64 = 512 * SECTOR_SIZE as u64 + 10;
+
+/// `SHALLOW.BIN` has a single table (sector 20) mapping logical blocks 0 and 3 and leaving 1 and 2
+/// as holes.  `DEEP.BIN` has two levels of tables (sectors 23 and 24) and only maps block 512.
+fn build_image() -> Vec<u8> {
+    Image::new()
+        .dot_system_use(&SP)
+        .record(directory_record_with_system_use(
+            23,
+            3 * SECTOR_SIZE as u32,
+            0,
+            b"DEEP.BIN;1",
+            &sf(DEEP_SIZE, 2),
+        ))
+        .record(directory_record_with_system_use(
+            20,
+            3 * SECTOR_SIZE as u32,
+            0,
+            b"SHALLOW.BIN;1",
+            &sf(SHALLOW_SIZE, 1),
+        ))
+        .sector(&table(&[(0, 1), (2, 1 << 31), (3, 2)])) // 20
+        .sector(&[1; SECTOR_SIZE]) // 21
+        .sector(&[4; SECTOR_SIZE]) // 22
+        .sector(&table(&[(1, 1)])) // 23
+        .sector(&table(&[(0, 2)])) // 24
+        .sector(&[7; SECTOR_SIZE]) // 25
+        .build()
+}
+
+#[test]
+fn sparse_metadata() {
+    let fs = ISO9660::new(Cursor::new(build_image())).unwrap();
+
+    let file = open_file(&fs, "shallow.bin");
+    assert!(file.is_sparse());
+    assert_eq!(
+        file.ext().sparse,
+        Some(SparseFile {
+            virtual_size: SHALLOW_SIZE,
+            table_depth: 1,
+        })
+    );
+    assert_eq!(file.size(), SHALLOW_SIZE);
+    assert_eq!(file.recorded_size(), 3 * SECTOR_SIZE as u64);
+}
+
+#[test]
+fn sparse_read() {
+    let fs = ISO9660::new(Cursor::new(build_image())).unwrap();
+
+    let mut contents = Vec::new();
+    open_file(&fs, "shallow.bin")
+        .read()
+        .read_to_end(&mut contents)
+        .unwrap();
+    let expected = [vec![1; SECTOR_SIZE], vec![0; 2 * SECTOR_SIZE], vec![4; 100]].concat();
+    assert_eq!(contents, expected);
+}
+
+#[test]
+fn sparse_read_nested_tables() {
+    let fs = ISO9660::new(Cursor::new(build_image())).unwrap();
+
+    let file = open_file(&fs, "deep.bin");
+    assert_eq!(file.size(), DEEP_SIZE);
+
+    let mut contents = Vec::new();
+    file.read().read_to_end(&mut contents).unwrap();
+    assert_eq!(contents.len() as u64, DEEP_SIZE);
+    assert!(contents[..512 * SECTOR_SIZE].iter().all(|&byte| byte == 0));
+    assert_eq!(contents[512 * SECTOR_SIZE..], [7; 10]);
+}
+
+#[test]
+fn sparse_seek() {
+    let fs = ISO9660::new(Cursor::new(build_image())).unwrap();
+
+    let mut reader = open_file(&fs, "shallow.bin").read();
+    let mut buf = [0xAA; 8];
+
+    reader.seek(SeekFrom::Start(2044)).unwrap();
+    reader.read_exact(&mut buf).unwrap();
+    assert_eq!(buf, [1, 1, 1, 1, 0, 0, 0, 0]);
+
+    reader.seek(SeekFrom::Start(3 * 2048 - 4)).unwrap();
+    reader.read_exact(&mut buf).unwrap();
+    assert_eq!(buf, [0, 0, 0, 0, 4, 4, 4, 4]);
+
+    reader.seek(SeekFrom::End(-4)).unwrap();
+    let mut tail = Vec::new();
+    reader.read_to_end(&mut tail).unwrap();
+    assert_eq!(tail, [4; 4]);
+}
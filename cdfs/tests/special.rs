@@ -0,0 +1,59 @@
+// SPDX-License-Identifier: (MIT OR Apache-2.0)
+
+use std::io::Cursor;
+
+use cdfs::{DirectoryEntry, SpecialType, ISO9660};
+
+mod common;
+use common::{both_endian32, directory_record_with_system_use, entry, px, Image, SP};
+
+fn pn(high: u32, low: u32) -> Vec<u8> {
+    entry(b"PN", &[both_endian32(high), both_endian32(low)].concat())
+}
+
+fn build_image() -> Vec<u8> {
+    let entries: [(&[u8], Vec<u8>); 5] = [
+        (b"FIFO.;1", px(0o010644)),
+        (b"NULL.;1", [px(0o020666), pn(1, 3)].concat()),
+        (b"REGULAR.;1", px(0o100644)),
+        (b"SDA.;1", [px(0o060660), pn(0, 0x0800)].concat()),
+        (b"SOCKET.;1", px(0o140777)),
+    ];
+
+    Image::new()
+        .dot_system_use(&SP)
+        .records(entries.map(|(identifier, system_use)| {
+            directory_record_with_system_use(0, 0, 0, identifier, &system_use)
+        }))
+        .build()
+}
+
+#[test]
+fn special_types() {
+    let fs = ISO9660::new(Cursor::new(build_image())).unwrap();
+
+    let special_type = |name| match fs.open(name).unwrap().unwrap() {
+        DirectoryEntry::Special(special) => Some(special.special_type()),
+        _ => None,
+    };
+    assert_eq!(special_type("fifo"), Some(SpecialType::Fifo));
+    assert_eq!(special_type("null"), Some(SpecialType::CharDevice));
+    assert_eq!(special_type("regular"), None);
+    assert_eq!(special_type("sda"), Some(SpecialType::BlockDevice));
+    assert_eq!(special_type("socket"), Some(SpecialType::Socket));
+}
+
+#[test]
+fn special_device_numbers() {
+    let fs = ISO9660::new(Cursor::new(build_image())).unwrap();
+
+    let device = |name| match fs.open(name).unwrap().unwrap() {
+        DirectoryEntry::Special(special) => special
+            .device()
+            .map(|device| (device.major(), device.minor())),
+        _ => panic!("Not a special file"),
+    };
+    assert_eq!(device("null"), Some((1, 3)));
+    assert_eq!(device("sda"), Some((8, 0)));
+    assert_eq!(device("fifo"), None);
+}
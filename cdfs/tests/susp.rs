@@ -0,0 +1,63 @@
+// SPDX-License-Identifier: (MIT OR Apache-2.0)
+
+use std::io::Cursor;
+
+use cdfs::{ExtraAttributes, ISO9660};
+
+mod common;
+use common::{collect_filenames, entry, file_record, nm, Image};
+
+fn sp(skip: u8) -> Vec<u8> {
+    entry(b"SP", &[0xBE, 0xEF, skip])
+}
+
+/// `FILE.TXT`'s system use field starts with `skip` bytes of vendor data, then pads before its
+/// `NM` entry and ends with an `ST` entry followed by garbage.
+fn build_image(dot_system_use: &[u8], skip: u8) -> Vec<u8> {
+    let file_system_use = [
+        vec![0xFF; usize::from(skip)],
+        entry(b"PD", &[0; 4]),
+        nm(b"file name"),
+        entry(b"ST", &[]),
+        nm(b"after the end"),
+        vec![0xFF; 6],
+    ]
+    .concat();
+
+    Image::new()
+        .dot_system_use(dot_system_use)
+        .record(file_record(b"FILE.TXT;1", &file_system_use))
+        .sector(b"hello") // 20
+        .build()
+}
+
+#[test]
+fn susp_skip() {
+    let fs = ISO9660::new(Cursor::new(build_image(&sp(3), 3))).unwrap();
+
+    let file = fs.open("file name").unwrap().unwrap();
+    assert_eq!(file.identifier(), "file name");
+}
+
+#[test]
+fn susp_padding_and_terminator() {
+    let fs = ISO9660::new(Cursor::new(build_image(&sp(0), 0))).unwrap();
+
+    let file = fs.open("file name").unwrap().unwrap();
+    let signatures = file
+        .ext()
+        .origins
+        .iter()
+        .map(|origin| &origin.signature)
+        .collect::<Vec<_>>();
+    assert_eq!(signatures, [b"NM"]);
+}
+
+#[test]
+fn susp_absent() {
+    // Without an `SP` entry in the root's `.` entry, system use fields hold no SUSP entries.
+    let fs = ISO9660::new(Cursor::new(build_image(&[], 0))).unwrap();
+
+    assert_eq!(collect_filenames(fs.root()), [".", "..", "FILE.TXT"]);
+    assert!(fs.open("file name").unwrap().is_none());
+}
@@ -1,13 +1,14 @@
 // SPDX-License-Identifier: (MIT OR Apache-2.0)
 
-use std::{fs::File, io::Read};
+use std::{fs::File, io::Read, sync::Arc, thread};
 
-use cdfs::{DirectoryEntry, ISO9660};
+use cdfs::{DirectoryEntry, ISOFileReader, VolumeDescriptor, ISO9660};
+use time::macros::datetime;
 
 mod common;
 use common::collect_filenames;
 
-const TEST_IMAGE: &'static str = concat!(env!("CARGO_MANIFEST_DIR"), "/../images/test.iso");
+const TEST_IMAGE: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../images/test.iso");
 
 #[test]
 fn test_dir() {
@@ -56,3 +57,68 @@ fn test_large_dir() {
     assert_eq!(dir.contents().map(Result::unwrap).count(), 202);
     assert_eq!(dir.block_count(), 4);
 }
+
+#[test]
+fn test_send_sync() {
+    fn assert_send_sync<T: Send + Sync>() {}
+
+    assert_send_sync::<ISO9660<File>>();
+    assert_send_sync::<DirectoryEntry<File>>();
+    assert_send_sync::<ISOFileReader<File>>();
+}
+
+#[test]
+fn test_concurrent_reads() {
+    let fs = Arc::new(ISO9660::new(File::open(TEST_IMAGE).unwrap()).unwrap());
+
+    let handles = (0..4)
+        .map(|_| {
+            let fs = Arc::clone(&fs);
+            thread::spawn(move || {
+                let file = match fs.open("gpl_3_0.txt").unwrap().unwrap() {
+                    DirectoryEntry::File(file) => file,
+                    _ => panic!("Not a file"),
+                };
+
+                let mut text = String::new();
+                file.read().read_to_string(&mut text).unwrap();
+                format!("{:x}", md5::compute(text))
+            })
+        })
+        .collect::<Vec<_>>();
+
+    for handle in handles {
+        assert_eq!(handle.join().unwrap(), "1ebbd3e34237af26da5dc08a4e440464");
+    }
+}
+
+#[test]
+fn test_volume_descriptors() {
+    let fs = ISO9660::new(File::open(TEST_IMAGE).unwrap()).unwrap();
+
+    let descriptors = fs.volume_descriptors();
+    assert_eq!(descriptors.len(), 2);
+    assert!(matches!(
+        descriptors[1],
+        VolumeDescriptor::VolumeDescriptorSetTerminator
+    ));
+
+    let VolumeDescriptor::Primary(primary) = &descriptors[0] else {
+        panic!("Expected the primary volume descriptor");
+    };
+    assert_eq!(primary.system_identifier, "LINUX");
+    assert_eq!(primary.volume_identifier, "CDROM");
+    assert_eq!(primary.volume_space_size, 398);
+    assert_eq!(primary.volume_set_size, 1);
+    assert_eq!(primary.volume_sequence_number, 1);
+    assert_eq!(primary.escape_sequences, [0; 32]);
+    assert_eq!(primary.application_use.len(), 512);
+    assert_eq!(primary.creation_time, datetime!(2018-03-20 14:24:03.12 -7));
+    assert_eq!(primary.modification_time, primary.creation_time);
+    assert_eq!(primary.effective_time, primary.creation_time);
+
+    assert_eq!(
+        fs.primary_descriptor().application_identifier,
+        fs.application_identifier()
+    );
+}
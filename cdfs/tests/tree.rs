@@ -0,0 +1,82 @@
+// SPDX-License-Identifier: (MIT OR Apache-2.0)
+
+use std::{fs::File, io::Cursor};
+
+use cdfs::{IsoBuilder, Tree, ISO9660};
+
+mod common;
+use common::collect_filenames;
+
+const JOLIET_IMAGE: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../images/joliet.iso");
+const RR_IMAGE: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../images/rockridge.iso");
+
+#[test]
+fn tree_joliet() {
+    let mut fs = ISO9660::new(File::open(JOLIET_IMAGE).unwrap()).unwrap();
+
+    assert_eq!(fs.trees(), [Tree::Primary, Tree::Joliet { level: 3 }]);
+    assert_eq!(fs.selected_tree(), Tree::Joliet { level: 3 });
+
+    assert!(fs.select_tree(Tree::RockRidge).is_none());
+    assert_eq!(fs.selected_tree(), Tree::Joliet { level: 3 });
+
+    let root = fs.select_tree(Tree::Primary).unwrap();
+    assert_eq!(
+        collect_filenames(root),
+        &[".", "..", "README.TXT", "READ_ME_.TXT"]
+    );
+    assert_eq!(fs.selected_tree(), Tree::Primary);
+    assert!(fs.open("README.TXT").unwrap().is_some());
+    assert!(fs.open("readme.txt").unwrap().is_some());
+    assert!(fs.open("Read Me First, Please.txt").unwrap().is_none());
+}
+
+#[test]
+fn tree_rock_ridge() {
+    let mut fs = ISO9660::new(File::open(RR_IMAGE).unwrap()).unwrap();
+
+    assert_eq!(
+        fs.trees(),
+        [Tree::Primary, Tree::RockRidge, Tree::Joliet { level: 3 }]
+    );
+    assert_eq!(fs.selected_tree(), Tree::RockRidge);
+    assert!(fs.is_rr());
+
+    let rock_ridge = collect_filenames(fs.root());
+    let primary = collect_filenames(fs.select_tree(Tree::Primary).unwrap());
+    assert_ne!(rock_ridge, primary);
+    assert!(primary.iter().all(|name| name.to_uppercase() == *name));
+    assert_eq!(collect_filenames(fs.root()), primary);
+}
+
+#[test]
+fn tree_all() {
+    let mut builder = IsoBuilder::new();
+    builder.rock_ridge(true).joliet(true);
+    builder.add_buffer("Mixed Case.txt", "contents").unwrap();
+
+    let mut image = Cursor::new(Vec::new());
+    builder.write(&mut image).unwrap();
+    let fs = ISO9660::new(image).unwrap();
+
+    assert_eq!(
+        fs.trees(),
+        [Tree::Primary, Tree::RockRidge, Tree::Joliet { level: 3 }]
+    );
+    assert_eq!(fs.selected_tree(), Tree::RockRidge);
+
+    for (tree, name) in [
+        (Tree::Primary, "MIXED_CASE.TXT"),
+        (Tree::RockRidge, "Mixed Case.txt"),
+        (Tree::Joliet { level: 3 }, "Mixed Case.txt"),
+    ] {
+        assert_eq!(collect_filenames(fs.tree(tree).unwrap())[2], name);
+    }
+
+    assert!(fs.root_at(1).is_some());
+    assert!(fs.root_at(2).is_none());
+    assert!(fs
+        .path_table_at(2, cdfs::PathTableType::L)
+        .unwrap()
+        .is_none());
+}
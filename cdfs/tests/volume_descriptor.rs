@@ -0,0 +1,72 @@
+// SPDX-License-Identifier: (MIT OR Apache-2.0)
+
+use std::io::Cursor;
+
+use cdfs::{ExtraAttributes, OpenOptions, VolumeDescriptor, ISO9660};
+
+mod common;
+use common::{
+    both_endian32, directory_record, entries, file_record, open, primary_descriptor, terminator,
+    Image, SECTOR_SIZE,
+};
+use time::macros::datetime;
+
+fn partition_descriptor() -> Vec<u8> {
+    let mut descriptor = vec![3];
+    descriptor.extend(b"CD001\x01\x00");
+    descriptor.extend(format!("{:32}", "SYSTEM").as_bytes());
+    descriptor.extend(format!("{:32}", "PARTITION").as_bytes());
+    descriptor.extend(both_endian32(20));
+    descriptor.extend(both_endian32(2));
+    descriptor.extend([0xAB; 1960]);
+    descriptor
+}
+
+#[test]
+fn volume_descriptor_partition() {
+    let root = directory_record(19, SECTOR_SIZE as u32, 2, &[0]);
+
+    let mut image = vec![0; 16 * SECTOR_SIZE];
+    image.extend(primary_descriptor(2048, 22, &root));
+    image.extend(partition_descriptor());
+    image.extend(terminator());
+
+    let mut directory = directory_record(19, SECTOR_SIZE as u32, 2, &[0]);
+    directory.extend(directory_record(19, SECTOR_SIZE as u32, 2, &[1]));
+    directory.resize(SECTOR_SIZE, 0);
+    image.extend(directory); // 19
+    image.extend([0; 2 * SECTOR_SIZE]); // 20, 21
+
+    let fs = ISO9660::new(Cursor::new(image)).unwrap();
+    let descriptors = fs.volume_descriptors();
+    assert_eq!(descriptors.len(), 3);
+    assert!(matches!(descriptors[0], VolumeDescriptor::Primary(_)));
+    assert!(matches!(
+        descriptors[2],
+        VolumeDescriptor::VolumeDescriptorSetTerminator
+    ));
+
+    let VolumeDescriptor::Partition(partition) = &descriptors[1] else {
+        panic!("Expected a partition descriptor");
+    };
+    assert_eq!(partition.system_identifier, "SYSTEM");
+    assert_eq!(partition.partition_identifier, "PARTITION");
+    assert_eq!(partition.partition_location, 20);
+    assert_eq!(partition.partition_size, 2);
+    assert_eq!(partition.system_use, [0xAB; 1960]);
+}
+
+#[test]
+fn volume_descriptor_negative_offset() {
+    // Noon on 2020-01-01 at UTC-5, which is recorded as -20 quarter hours.
+    let mut record = file_record(b"FILE.TXT;1", &[]);
+    record[18..25].copy_from_slice(&[120, 1, 1, 12, 0, 0, -20i8 as u8]);
+    let mut image = Image::new().record(record).sector(b"hello").build();
+    let creation_time = 16 * SECTOR_SIZE + 813;
+    image[creation_time..creation_time + 17].copy_from_slice(b"2020010112000000\xEC");
+
+    let fs = open(image, &OpenOptions::new());
+    let expected = datetime!(2020-01-01 12:00:00 -5);
+    assert_eq!(fs.primary_descriptor().creation_time, expected);
+    assert_eq!(entries(&fs).unwrap()[0].modify_time(), expected);
+}
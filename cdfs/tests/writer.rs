@@ -0,0 +1,493 @@
+// SPDX-License-Identifier: (MIT OR Apache-2.0)
+
+use std::{
+    fs,
+    io::{Cursor, Read},
+    path::Path,
+};
+
+use cdfs::{
+    DirectoryEntry, ExtraAttributes, ISO9660Reader, ISOError, IsoBuilder, PathTableType,
+    PosixAttributes, PosixFileMode, PosixTimestamp, ISO9660,
+};
+use time::macros::datetime;
+
+mod common;
+use common::collect_filenames;
+
+fn build(builder: &IsoBuilder) -> ISO9660<Cursor<Vec<u8>>> {
+    let mut image = Cursor::new(Vec::new());
+    builder.write(&mut image).unwrap();
+    assert_eq!(image.get_ref().len() % 2048, 0);
+    ISO9660::new(image).unwrap()
+}
+
+fn read_file<T: ISO9660Reader>(fs: &ISO9660<T>, path: &str) -> Vec<u8> {
+    let file = match fs.open(path).unwrap() {
+        Some(DirectoryEntry::File(file)) => file,
+        _ => panic!("{path} is not a file"),
+    };
+
+    let mut contents = Vec::new();
+    file.read().read_to_end(&mut contents).unwrap();
+    assert_eq!(contents.len() as u64, file.size());
+    contents
+}
+
+#[test]
+fn writer_round_trip() {
+    let large = (0..10_000).map(|i| (i % 251) as u8).collect::<Vec<_>>();
+
+    let mut builder = IsoBuilder::new();
+    builder
+        .volume_identifier("ROUND_TRIP")
+        .publisher_identifier("cdfs")
+        .timestamp(datetime!(2023-09-06 12:34:56 UTC));
+    builder.add_buffer("readme.txt", "Hello, world!\n").unwrap();
+    builder
+        .add_buffer("a/b/c/large.bin", large.clone())
+        .unwrap();
+    builder.add_buffer("a/empty.txt", "").unwrap();
+    builder.add_directory("a/d").unwrap();
+    builder.add_directory("a/d").unwrap();
+
+    let fs = build(&builder);
+    assert_eq!(fs.block_size(), 2048);
+    assert_eq!(fs.publisher_identifier(), "CDFS");
+
+    assert_eq!(
+        collect_filenames(fs.root()),
+        &[".", "..", "A", "README.TXT"]
+    );
+    assert_eq!(read_file(&fs, "readme.txt"), b"Hello, world!\n");
+    assert_eq!(read_file(&fs, "a/b/c/large.bin"), large);
+    assert_eq!(read_file(&fs, "a/empty.txt"), b"");
+
+    match fs.open("a").unwrap() {
+        Some(DirectoryEntry::Directory(dir)) => {
+            assert_eq!(collect_filenames(&dir), &[".", "..", "B", "D", "EMPTY.TXT"]);
+        }
+        _ => panic!("Not a directory"),
+    }
+
+    match fs.open("a/d").unwrap() {
+        Some(DirectoryEntry::Directory(dir)) => {
+            assert_eq!(collect_filenames(&dir), &[".", ".."]);
+        }
+        _ => panic!("Not a directory"),
+    }
+
+    let entry = fs.open("readme.txt").unwrap().unwrap();
+    assert_eq!(entry.modify_time(), datetime!(2023-09-06 12:34:56 UTC));
+}
+
+#[test]
+fn writer_path_table() {
+    let mut builder = IsoBuilder::new();
+    for path in ["z/y", "z/x", "a/b", "m"] {
+        builder.add_directory(path).unwrap();
+    }
+
+    let fs = build(&builder);
+
+    for table_type in [PathTableType::L, PathTableType::M] {
+        let table = fs.path_table_at(0, table_type).unwrap().unwrap();
+        let paths = (1..=table.records().len() as u16)
+            .map(|number| table.path(number).unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(paths, &["/", "/A", "/M", "/Z", "/A/B", "/Z/X", "/Z/Y"]);
+
+        // Every record points at the directory it names.
+        for (number, path) in (1..).zip(&paths) {
+            let entry = fs.open(path).unwrap().unwrap();
+            assert_eq!(
+                table.get(number).unwrap().extent_loc,
+                entry.header().extent_loc
+            );
+        }
+    }
+
+    let mut fs = fs;
+    fs.set_path_table_lookup(true);
+    assert!(fs.open("z/x").unwrap().is_some());
+}
+
+#[test]
+fn writer_names() {
+    let mut builder = IsoBuilder::new();
+    builder.add_buffer("a-b.txt", "1").unwrap();
+    builder.add_buffer("A_B.txt", "2").unwrap();
+    builder.add_buffer("README", "3").unwrap();
+    builder
+        .add_buffer("a rather long file name, really.tar.gz", "4")
+        .unwrap();
+    builder.add_directory("Program Files").unwrap();
+
+    let fs = build(&builder);
+    assert_eq!(
+        collect_filenames(fs.root()),
+        &[
+            ".",
+            "..",
+            "A_B.TXT",
+            "A_B1.TXT",
+            "A_RATHER_LONG_FILE_NAME__REA.GZ",
+            "PROGRAM_FILES",
+            "README",
+        ]
+    );
+
+    // Collisions are resolved in order of the original names.
+    assert_eq!(read_file(&fs, "a_b.txt"), b"2");
+    assert_eq!(read_file(&fs, "a_b1.txt"), b"1");
+    assert_eq!(read_file(&fs, "readme"), b"3");
+}
+
+#[test]
+fn writer_large_directory() {
+    let mut builder = IsoBuilder::new();
+    for i in 0..300 {
+        builder
+            .add_buffer(&format!("dir/file_{i:03}.dat"), format!("{i}"))
+            .unwrap();
+    }
+
+    let fs = build(&builder);
+    let dir = match fs.open("dir").unwrap() {
+        Some(DirectoryEntry::Directory(dir)) => dir,
+        _ => panic!("Not a directory"),
+    };
+    assert!(dir.block_count() > 1);
+    assert_eq!(dir.contents().map(Result::unwrap).count(), 302);
+    assert_eq!(read_file(&fs, "dir/file_299.dat"), b"299");
+}
+
+#[test]
+fn writer_host_files() {
+    let source = Path::new(env!("CARGO_TARGET_TMPDIR")).join("writer_host_files");
+    let _ = fs::remove_dir_all(&source);
+    fs::create_dir_all(source.join("sub/dir")).unwrap();
+    fs::write(source.join("top.txt"), "top").unwrap();
+    fs::write(source.join("sub/dir/nested.txt"), "nested").unwrap();
+
+    let mut builder = IsoBuilder::new();
+    builder.add_tree("tree", &source).unwrap();
+    builder
+        .add_file(
+            "license.txt",
+            concat!(env!("CARGO_MANIFEST_DIR"), "/../LICENSE-MIT"),
+        )
+        .unwrap();
+
+    let fs = build(&builder);
+    assert_eq!(read_file(&fs, "tree/top.txt"), b"top");
+    assert_eq!(read_file(&fs, "tree/sub/dir/nested.txt"), b"nested");
+    assert_eq!(
+        read_file(&fs, "license.txt"),
+        fs::read(concat!(env!("CARGO_MANIFEST_DIR"), "/../LICENSE-MIT")).unwrap()
+    );
+}
+
+#[test]
+fn writer_at_offset() {
+    let mut builder = IsoBuilder::new();
+    builder.add_buffer("file.txt", "offset").unwrap();
+
+    let mut image = Cursor::new(vec![0xFF; 100]);
+    image.set_position(100);
+    builder.write(&mut image).unwrap();
+
+    let fs = ISO9660::new(Cursor::new(image.into_inner().split_off(100))).unwrap();
+    assert_eq!(read_file(&fs, "file.txt"), b"offset");
+}
+
+#[test]
+fn writer_errors() {
+    let mut builder = IsoBuilder::new();
+    builder.add_buffer("a/file", "").unwrap();
+
+    for result in [
+        builder.add_buffer("a/file", ""),
+        builder.add_buffer("a/file/nested", ""),
+        builder.add_directory("a/file"),
+        builder.add_buffer("a/../escape", ""),
+        builder.add_buffer("", ""),
+    ] {
+        assert!(matches!(result, Err(ISOError::Build { .. })), "{result:?}");
+    }
+
+    assert!(matches!(
+        builder.add_file("missing", "/nonexistent/file"),
+        Err(ISOError::Io(_))
+    ));
+}
+
+fn mode(bits: u32) -> PosixFileMode {
+    PosixFileMode::from_bits_truncate(bits)
+}
+
+#[test]
+fn writer_rock_ridge() {
+    let long_name = "a rather long name that won't fit in a directory record ".repeat(5);
+    let timestamps = PosixTimestamp {
+        modify: Some(datetime!(2020-02-29 23:59:59 UTC)),
+        access: Some(datetime!(2021-01-01 00:00:00 UTC)),
+        attributes: Some(datetime!(2022-06-15 12:00:00 UTC)),
+        ..PosixTimestamp::default()
+    };
+
+    let mut builder = IsoBuilder::new();
+    builder
+        .rock_ridge(true)
+        .timestamp(datetime!(2023-09-06 12:34:56 UTC));
+    builder.add_buffer("Mixed Case.txt", "mixed").unwrap();
+    builder
+        .add_buffer(&format!("dir/{long_name}"), "long")
+        .unwrap();
+    for i in 0..20 {
+        builder
+            .add_buffer(&format!("many/{i:02} {long_name}"), format!("{i}"))
+            .unwrap();
+    }
+    builder.add_symlink("relative", "../target/./file").unwrap();
+    builder.add_symlink("absolute", "/usr/bin/env").unwrap();
+    builder
+        .add_special("null", PosixFileMode::TYPE_CHAR_DEV, 0x103)
+        .unwrap();
+    builder
+        .set_attributes(
+            "Mixed Case.txt",
+            PosixAttributes {
+                mode: mode(0o4640),
+                links: 1,
+                uid: 1000,
+                gid: 100,
+                inode: Some(42),
+            },
+        )
+        .unwrap();
+    builder
+        .set_timestamps("Mixed Case.txt", timestamps.clone())
+        .unwrap();
+
+    let fs = build(&builder);
+    assert!(fs.is_rr());
+    assert_eq!(
+        collect_filenames(fs.root()),
+        &[
+            ".",
+            "..",
+            "absolute",
+            "dir",
+            "many",
+            "Mixed Case.txt",
+            "null",
+            "relative"
+        ]
+    );
+
+    let entry = fs.open("Mixed Case.txt").unwrap().unwrap();
+    assert_eq!(entry.mode(), Some(PosixFileMode::TYPE_FILE | mode(0o4640)));
+    assert_eq!(entry.owner(), Some(1000));
+    assert_eq!(entry.group(), Some(100));
+    assert_eq!(entry.inode(), Some(42));
+    assert_eq!(entry.ext().timestamps, timestamps);
+    assert_eq!(entry.time(), datetime!(2020-02-29 23:59:59 UTC));
+    assert_eq!(read_file(&fs, "Mixed Case.txt"), b"mixed");
+
+    // Defaults
+    let entry = fs.open("dir").unwrap().unwrap();
+    assert_eq!(
+        entry.mode(),
+        Some(PosixFileMode::TYPE_DIRECTORY | mode(0o555))
+    );
+    assert_eq!(entry.owner(), Some(0));
+    assert_eq!(entry.modify_time(), datetime!(2023-09-06 12:34:56 UTC));
+
+    // Names too long for a directory record are continued elsewhere.
+    assert_eq!(read_file(&fs, &format!("dir/{long_name}")), b"long");
+    match fs.open("many").unwrap() {
+        Some(DirectoryEntry::Directory(dir)) => {
+            let names = collect_filenames(&dir);
+            assert_eq!(names.len(), 22);
+            assert_eq!(names[21], format!("19 {long_name}"));
+        }
+        _ => panic!("Not a directory"),
+    }
+    assert_eq!(read_file(&fs, &format!("many/07 {long_name}")), b"7");
+
+    for (path, target) in [
+        ("relative", "../target/./file"),
+        ("absolute", "/usr/bin/env"),
+    ] {
+        match fs.open(path).unwrap() {
+            Some(DirectoryEntry::Symlink(link)) => {
+                assert_eq!(link.target().map(String::as_str), Some(target));
+                assert_eq!(link.mode(), Some(PosixFileMode::TYPE_SYMLINK | mode(0o777)));
+            }
+            _ => panic!("{path} is not a symlink"),
+        }
+    }
+
+    let entry = fs.open("null").unwrap().unwrap();
+    assert_eq!(
+        entry.mode(),
+        Some(PosixFileMode::TYPE_CHAR_DEV | mode(0o444))
+    );
+}
+
+#[test]
+fn writer_rock_ridge_names() {
+    // Rock Ridge names are read back as they are, without an ISO 9660 version or trailing dot.
+    let mut builder = IsoBuilder::new();
+    builder.rock_ridge(true);
+    builder.add_buffer("a;b.txt", "semicolon").unwrap();
+    builder.add_buffer("trailing.", "dot").unwrap();
+    builder.add_buffer("ok.txt", "ok").unwrap();
+
+    let fs = build(&builder);
+    assert_eq!(
+        collect_filenames(fs.root()),
+        &[".", "..", "a;b.txt", "ok.txt", "trailing."]
+    );
+    for (path, contents) in [
+        ("a;b.txt", &b"semicolon"[..]),
+        ("trailing.", b"dot"),
+        ("ok.txt", b"ok"),
+    ] {
+        match fs.open(path).unwrap() {
+            Some(DirectoryEntry::File(file)) => assert_eq!(file.version, 1),
+            _ => panic!("{path} is not a file"),
+        }
+        assert_eq!(read_file(&fs, path), contents);
+    }
+}
+
+#[test]
+fn writer_rock_ridge_relocation() {
+    let deep = (1..=12)
+        .map(|i| i.to_string())
+        .collect::<Vec<_>>()
+        .join("/");
+
+    let mut builder = IsoBuilder::new();
+    builder.rock_ridge(true).joliet(true);
+    builder
+        .add_buffer(&format!("{deep}/file.txt"), "deep")
+        .unwrap();
+    for i in 0..100 {
+        builder
+            .add_buffer(&format!("1/2/3/4/5/6/7/8/file_{i:03}"), format!("{i}"))
+            .unwrap();
+    }
+
+    let fs = build(&builder);
+    assert_eq!(read_file(&fs, &format!("{deep}/file.txt")), b"deep");
+
+    // The relocated directory is larger than a sector.
+    match fs.open("1/2/3/4/5/6/7/8").unwrap() {
+        Some(DirectoryEntry::Directory(dir)) => {
+            assert!(dir.block_count() > 1);
+            assert_eq!(dir.contents().count(), 103);
+        }
+        _ => panic!("Not a directory"),
+    }
+    assert_eq!(read_file(&fs, "1/2/3/4/5/6/7/8/file_099"), b"99");
+
+    let primary = fs.path_table_at(0, PathTableType::L).unwrap().unwrap();
+    assert_eq!(primary.find("/1/2/3/4/5/6/7/8"), None);
+    assert!(primary.find("/RR_MOVED/8").is_some());
+    assert!(fs.open("rr_moved/8").unwrap().unwrap().relocated());
+
+    // The Joliet tree is recorded as is.
+    let joliet = fs.path_table_at(1, PathTableType::L).unwrap().unwrap();
+    assert!(joliet.find(&format!("/{deep}")).is_some());
+}
+
+#[test]
+fn writer_joliet() {
+    let long_name = format!("{}.txt", "Long Joliet name ".repeat(5));
+
+    let mut builder = IsoBuilder::new();
+    builder.joliet(true).volume_identifier("Joliet");
+    builder
+        .add_buffer("Ünïcödé/Mixed Case.txt", "unicode")
+        .unwrap();
+    builder.add_buffer("what?.txt", "question").unwrap();
+    builder.add_buffer(&long_name, "long").unwrap();
+    builder.add_buffer("no extension", "none").unwrap();
+
+    let fs = build(&builder);
+    assert!(!fs.is_rr());
+
+    let truncated = format!("{}.txt", &long_name[..60]);
+    assert_eq!(
+        collect_filenames(fs.root()),
+        &[
+            ".",
+            "..",
+            &truncated,
+            "no extension",
+            "what_.txt",
+            "Ünïcödé"
+        ]
+    );
+    assert_eq!(read_file(&fs, "Ünïcödé/Mixed Case.txt"), b"unicode");
+    assert_eq!(read_file(&fs, "what_.txt"), b"question");
+    assert_eq!(read_file(&fs, &truncated), b"long");
+    assert_eq!(read_file(&fs, "no extension"), b"none");
+
+    // The primary tree is still there.
+    let root = fs.root_at(0).unwrap();
+    assert_eq!(
+        collect_filenames(root),
+        &[
+            ".",
+            "..",
+            "LONG_JOLIET_NAME_LONG_JOLIE.TXT",
+            "NO_EXTENSION",
+            "WHAT_.TXT",
+            "_N_C_D_"
+        ]
+    );
+
+    let joliet = fs.path_table_at(1, PathTableType::M).unwrap().unwrap();
+    assert_eq!(joliet.path(2).as_deref(), Some("/Ünïcödé"));
+}
+
+#[cfg(unix)]
+#[test]
+fn writer_rock_ridge_host_files() {
+    use std::os::unix::fs::{symlink, MetadataExt, PermissionsExt};
+
+    let source = Path::new(env!("CARGO_TARGET_TMPDIR")).join("writer_rock_ridge_host_files");
+    let _ = fs::remove_dir_all(&source);
+    fs::create_dir_all(source.join("sub")).unwrap();
+    fs::write(source.join("sub/script.sh"), "#!/bin/sh\n").unwrap();
+    fs::set_permissions(
+        source.join("sub/script.sh"),
+        fs::Permissions::from_mode(0o751),
+    )
+    .unwrap();
+    symlink("sub/script.sh", source.join("link")).unwrap();
+
+    let mut builder = IsoBuilder::new();
+    builder.rock_ridge(true);
+    builder.add_tree("tree", &source).unwrap();
+
+    let fs = build(&builder);
+    let metadata = fs::metadata(source.join("sub/script.sh")).unwrap();
+    let entry = fs.open("tree/sub/script.sh").unwrap().unwrap();
+    assert_eq!(entry.mode(), Some(mode(metadata.mode())));
+    assert_eq!(entry.owner(), Some(metadata.uid()));
+    assert_eq!(entry.group(), Some(metadata.gid()));
+    assert_eq!(entry.modify_time().unix_timestamp(), metadata.mtime());
+    assert_eq!(read_file(&fs, "tree/sub/script.sh"), b"#!/bin/sh\n");
+
+    match fs.open("tree/link").unwrap() {
+        Some(DirectoryEntry::Symlink(link)) => {
+            assert_eq!(link.target().map(String::as_str), Some("sub/script.sh"));
+        }
+        _ => panic!("Not a symlink"),
+    }
+}
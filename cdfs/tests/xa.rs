@@ -0,0 +1,140 @@
+// SPDX-License-Identifier: (MIT OR Apache-2.0)
+
+use std::io::{Cursor, Read};
+
+use cdfs::{ExtraAttributes, RawSectorReader, XaAttributes, XaRecord, ISO9660};
+
+mod common;
+use common::{
+    directory_record_with_system_use, open_file, raw_header, rawify, Image, Layout, SECTOR_SIZE,
+};
+
+const FORM2_DATA_SIZE: usize = 2324;
+
+fn xa(attributes: u16, file_number: u8) -> Vec<u8> {
+    [
+        &[0, 0, 0, 0][..],
+        &attributes.to_be_bytes(),
+        b"XA",
+        &[file_number, 0, 0, 0, 0, 0],
+    ]
+    .concat()
+}
+
+/// The user data of Form 2 sector `n` of `VIDEO.DAT`.
+fn payload(n: u8) -> Vec<u8> {
+    (0..FORM2_DATA_SIZE).map(|i| (i as u8) ^ n).collect()
+}
+
+/// `VIDEO.DAT` is two Form 2 sectors at 20 and 21.  With `susp`, the root's `.` entry has an `SP`
+/// entry after its XA record that skips it, and `VIDEO.DAT` has a Rock Ridge name.
+fn build_image(susp: bool) -> Vec<u8> {
+    let (dot, video) = match susp {
+        true => (
+            b"SP\x07\x01\xBE\xEF\x0E".to_vec(),
+            [xa(0x1111, 1), b"NM\x0E\x01\x00video.mpg".to_vec()].concat(),
+        ),
+        false => (Vec::new(), xa(0x1111, 1)),
+    };
+
+    Image::new()
+        .root_system_use(&xa(0x8D55, 0))
+        .dot_system_use(&dot)
+        .record(directory_record_with_system_use(
+            20,
+            2 * SECTOR_SIZE as u32,
+            0,
+            b"VIDEO.DAT;1",
+            &video,
+        ))
+        .sector(&payload(0)[..SECTOR_SIZE]) // 20
+        .sector(&payload(1)[..SECTOR_SIZE]) // 21
+        .build()
+}
+
+/// The image with every sector in Mode 2, and the file's sectors in Form 2.
+fn build_raw_image() -> Vec<u8> {
+    let mut image = rawify(&build_image(false), Layout::Mode2Form1);
+    for (n, lba) in [20, 21].into_iter().enumerate() {
+        let sector = [
+            raw_header(lba, 2),
+            vec![1, 0, 0x20, 0, 1, 0, 0x20, 0],
+            payload(n as u8),
+            vec![0; 4],
+        ]
+        .concat();
+        image.splice(lba * 2352..(lba + 1) * 2352, sector);
+    }
+    image
+}
+
+#[test]
+fn xa_record() {
+    let fs = ISO9660::new(Cursor::new(build_image(false))).unwrap();
+
+    let dot = fs.root().contents().next().unwrap().unwrap();
+    assert_eq!(
+        dot.xa().map(|xa| xa.attributes),
+        Some(
+            XaAttributes::DIRECTORY
+                | XaAttributes::MODE2_FORM1
+                | XaAttributes::WORLD_EXECUTE
+                | XaAttributes::WORLD_READ
+                | XaAttributes::GROUP_EXECUTE
+                | XaAttributes::GROUP_READ
+                | XaAttributes::OWNER_EXECUTE
+                | XaAttributes::OWNER_READ
+        )
+    );
+
+    let file = open_file(&fs, "video.dat");
+    assert_eq!(
+        file.xa(),
+        Some(&XaRecord {
+            group_id: 0,
+            user_id: 0,
+            attributes: XaAttributes::MODE2_FORM2
+                | XaAttributes::WORLD_READ
+                | XaAttributes::GROUP_READ
+                | XaAttributes::OWNER_READ,
+            file_number: 1,
+        })
+    );
+    assert!(file.is_form2());
+}
+
+#[test]
+fn xa_with_susp() {
+    let fs = ISO9660::new(Cursor::new(build_image(true))).unwrap();
+
+    let file = open_file(&fs, "video.mpg");
+    assert!(file.is_form2());
+}
+
+#[test]
+fn xa_form2_raw() {
+    let reader = RawSectorReader::new(Cursor::new(build_raw_image())).unwrap();
+    let fs = ISO9660::new(reader).unwrap();
+
+    let file = open_file(&fs, "video.dat");
+    assert_eq!(file.size(), 2 * SECTOR_SIZE as u64);
+
+    let mut data = Vec::new();
+    file.read().read_to_end(&mut data).unwrap();
+    assert_eq!(data, [payload(0), payload(1)].concat());
+}
+
+#[test]
+fn xa_form2_cooked() {
+    let fs = ISO9660::new(Cursor::new(build_image(false))).unwrap();
+
+    let mut data = Vec::new();
+    open_file(&fs, "video.dat")
+        .read()
+        .read_to_end(&mut data)
+        .unwrap();
+    assert_eq!(
+        data,
+        [&payload(0)[..SECTOR_SIZE], &payload(1)[..SECTOR_SIZE]].concat()
+    );
+}
@@ -0,0 +1,171 @@
+// SPDX-License-Identifier: (MIT OR Apache-2.0)
+
+use std::io::{Cursor, ErrorKind, Read, Seek, SeekFrom};
+
+use cdfs::{ExtraAttributes, ISOError, ZisofsFile, ISO9660};
+use miniz_oxide::deflate::compress_to_vec_zlib;
+
+mod common;
+use common::{
+    both_endian32, directory_record_with_system_use, entry, open_file, Image, SECTOR_SIZE, SP,
+};
+
+const MAGIC: [u8; 8] = [0x37, 0xE4, 0x53, 0x96, 0xC9, 0xDB, 0xD6, 0x07];
+const BLOCK_SIZE_LOG2: u8 = 15;
+
+fn zf(uncompressed_size: u32) -> Vec<u8> {
+    let data = [
+        &b"pz"[..],
+        &[4, BLOCK_SIZE_LOG2],
+        &both_endian32(uncompressed_size),
+    ];
+    entry(b"ZF", &data.concat())
+}
+
+/// Four blocks: text, more text, zeros (recorded as an empty block), and a short tail.
+fn contents() -> Vec<u8> {
+    let text = |seed: usize| -> Vec<u8> {
+        (0..1 << BLOCK_SIZE_LOG2)
+            .map(|n: usize| b"abcdefghijklmnopqrstuvwxyz"[(n * seed + n / 7) % 26])
+            .collect()
+    };
+    [
+        text(3),
+        text(5),
+        vec![0; 1 << BLOCK_SIZE_LOG2],
+        b"tail".to_vec(),
+    ]
+    .concat()
+}
+
+/// `mkzftree`'s layout: header, block pointers, then each block compressed on its own.
+fn compress(contents: &[u8], magic: [u8; 8]) -> Vec<u8> {
+    let blocks = contents.chunks(1 << BLOCK_SIZE_LOG2).collect::<Vec<_>>();
+
+    let mut out = magic.to_vec();
+    out.extend((contents.len() as u32).to_le_bytes());
+    out.extend([4, BLOCK_SIZE_LOG2, 0, 0]);
+
+    let mut data = Vec::new();
+    let mut pointers = Vec::new();
+    let start = out.len() + (blocks.len() + 1) * 4;
+    for block in blocks {
+        pointers.push((start + data.len()) as u32);
+        if block.iter().any(|&byte| byte != 0) {
+            data.extend(compress_to_vec_zlib(block, 6));
+        }
+    }
+    pointers.push((start + data.len()) as u32);
+
+    for pointer in pointers {
+        out.extend(pointer.to_le_bytes());
+    }
+    out.extend(data);
+    out
+}
+
+fn build_image(magic: [u8; 8]) -> Vec<u8> {
+    let contents = contents();
+    let compressed = compress(&contents, magic);
+
+    Image::new()
+        .dot_system_use(&SP)
+        .record(directory_record_with_system_use(
+            20,
+            compressed.len() as u32,
+            0,
+            b"TEXT.TXT;1",
+            &zf(contents.len() as u32),
+        ))
+        .sector(&compressed) // 20
+        .build()
+}
+
+#[test]
+fn zisofs_metadata() {
+    let fs = ISO9660::new(Cursor::new(build_image(MAGIC))).unwrap();
+    let file = open_file(&fs, "text.txt");
+
+    assert!(file.is_compressed());
+    assert_eq!(
+        file.ext().zisofs,
+        Some(ZisofsFile {
+            algorithm: ZisofsFile::ZLIB,
+            header_size: 16,
+            block_size_log2: BLOCK_SIZE_LOG2,
+            uncompressed_size: contents().len() as u32,
+        })
+    );
+    assert_eq!(file.size(), contents().len() as u64);
+    assert_eq!(
+        file.recorded_size(),
+        compress(&contents(), MAGIC).len() as u64
+    );
+}
+
+#[test]
+fn zisofs_read() {
+    let fs = ISO9660::new(Cursor::new(build_image(MAGIC))).unwrap();
+    let file = open_file(&fs, "text.txt");
+
+    let mut data = Vec::new();
+    file.read().read_to_end(&mut data).unwrap();
+    assert_eq!(data, contents());
+
+    let mut recorded = Vec::new();
+    file.read_recorded().read_to_end(&mut recorded).unwrap();
+    assert_eq!(recorded, compress(&contents(), MAGIC));
+}
+
+#[test]
+fn zisofs_seek() {
+    let fs = ISO9660::new(Cursor::new(build_image(MAGIC))).unwrap();
+    let contents = contents();
+
+    let mut reader = open_file(&fs, "text.txt").read();
+    let mut buf = [0; 64];
+
+    // Straddling the first two blocks, then back to the start.
+    for pos in [(1 << BLOCK_SIZE_LOG2) - 32, 0, 3 << BLOCK_SIZE_LOG2 >> 1] {
+        reader.seek(SeekFrom::Start(pos as u64)).unwrap();
+        reader.read_exact(&mut buf).unwrap();
+        assert_eq!(buf, contents[pos..pos + buf.len()]);
+    }
+
+    reader.seek(SeekFrom::End(-6)).unwrap();
+    let mut tail = Vec::new();
+    reader.read_to_end(&mut tail).unwrap();
+    assert_eq!(tail, b"\0\0tail");
+}
+
+#[test]
+fn zisofs_bad_magic() {
+    let fs = ISO9660::new(Cursor::new(build_image([0; 8]))).unwrap();
+
+    let mut data = Vec::new();
+    let err = open_file(&fs, "text.txt")
+        .read()
+        .read_to_end(&mut data)
+        .unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidData);
+}
+
+#[test]
+fn zisofs_bad_pointer() {
+    // The last block pointer, just after the header, points far past the end of the file.
+    let mut image = build_image(MAGIC);
+    let pointer = 20 * SECTOR_SIZE + 16 + 4 * 4;
+    image[pointer..pointer + 4].copy_from_slice(&0xFFFF_FFF0_u32.to_le_bytes());
+
+    let fs = ISO9660::new(Cursor::new(image)).unwrap();
+    let mut data = Vec::new();
+    let err = open_file(&fs, "text.txt")
+        .read()
+        .read_to_end(&mut data)
+        .unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidData);
+    assert!(matches!(
+        err.get_ref().and_then(|err| err.downcast_ref()),
+        Some(ISOError::InvalidFs(_))
+    ));
+}